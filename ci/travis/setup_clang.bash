@@ -5,20 +5,20 @@
 set -o errexit
 
 if [[ "$TRAVIS_OS_NAME" == "osx" ]]; then
-  CLANG_DIR=$HOME/clang-3.8
+  CLANG_DIR=$HOME/clang-5.0
   cd "$HOME"
   echo "Downloading libclang"
-  wget http://llvm.org/releases/3.8.0/clang+llvm-3.8.0-x86_64-apple-darwin.tar.xz -O - | tar -xJ
-  mv clang+llvm-3.8.0-x86_64-apple-darwin "$CLANG_DIR"
+  wget http://releases.llvm.org/5.0.0/clang+llvm-5.0.0-x86_64-apple-darwin.tar.xz -O - | tar -xJ
+  mv clang+llvm-5.0.0-x86_64-apple-darwin "$CLANG_DIR"
 
   set -x
   export LLVM_CONFIG_PATH=$CLANG_DIR/bin/llvm-config
-  export CLANG_SYSTEM_INCLUDE_PATH=$CLANG_DIR/lib/clang/3.8.0/include
+  export CLANG_SYSTEM_INCLUDE_PATH=$CLANG_DIR/lib/clang/5.0.0/include
   export LD_LIBRARY_PATH=$CLANG_DIR/lib
   set +x
 else
   set -x
-  export LLVM_CONFIG_PATH=/usr/lib/llvm-3.8/bin/llvm-config
-  export CLANG_SYSTEM_INCLUDE_PATH=/usr/lib/llvm-3.8/lib/clang/3.8.0/include
+  export LLVM_CONFIG_PATH=/usr/lib/llvm-5.0/bin/llvm-config
+  export CLANG_SYSTEM_INCLUDE_PATH=/usr/lib/llvm-5.0/lib/clang/5.0.0/include
   set +x
 fi
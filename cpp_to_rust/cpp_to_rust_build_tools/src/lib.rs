@@ -12,7 +12,7 @@
 pub extern crate cpp_to_rust_common as common;
 use common::errors::{fancy_unwrap, ChainErr, Result};
 use common::cpp_build_config::{CppBuildConfig, CppBuildPaths, CppLibraryType};
-use common::BuildScriptData;
+use common::{BuildScriptData, cpp_condition_macro_name};
 use common::file_utils::{PathBufWithAdded, load_json, create_file, file_to_string, path_to_str};
 use common::cpp_lib_builder::{CppLibBuilder, CMakeVar, BuildType};
 use common::target::current_target;
@@ -117,6 +117,18 @@ impl Config {
                                        cpp_build_config_data.linked_frameworks())?);
     cmake_vars.push(CMakeVar::new("C2R_COMPILER_FLAGS",
                                   cpp_build_config_data.compiler_flags().join(" ")));
+    let target = current_target();
+    let condition_macros: Vec<_> = self
+      .build_script_data
+      .cpp_conditions
+      .iter()
+      .enumerate()
+      .filter(|&(_, condition)| condition.eval(&target))
+      .map(|(index, _)| {
+             cpp_condition_macro_name(&self.build_script_data.cpp_wrapper_lib_name, index)
+           })
+      .collect();
+    cmake_vars.push(CMakeVar::new_list("C2R_DEFINITIONS", condition_macros)?);
     let out_dir = out_dir()?;
     let c_lib_install_dir = out_dir.with_added("c_lib_install");
     let manifest_dir = manifest_dir()?;
@@ -43,6 +43,17 @@ pub struct BuildScriptData {
   pub cpp_lib_version: Option<String>,
   /// Name of C++ wrapper library
   pub cpp_wrapper_lib_name: String,
+  /// Target conditions of platform-specific items of the C++ wrapper library.
+  /// The build script defines the macro returned by `cpp_condition_macro_name`
+  /// for each condition that is true for the current target.
+  pub cpp_conditions: Vec<target::Condition>,
+}
+
+/// Returns name of the preprocessor macro that guards platform-specific
+/// items of the C++ wrapper library corresponding to condition with `index`
+/// in `BuildScriptData::cpp_conditions`.
+pub fn cpp_condition_macro_name(cpp_wrapper_lib_name: &str, index: usize) -> String {
+  format!("{}_CONDITION_{}", cpp_wrapper_lib_name.to_uppercase(), index)
 }
 
 #[cfg(test)]
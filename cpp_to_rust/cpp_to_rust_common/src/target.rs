@@ -2,7 +2,7 @@
 
 /// CPU architecture, as reported by `target_arch`.
 #[allow(non_camel_case_types)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[derive(Serialize, Deserialize)]
 pub enum Arch {
   X86,
@@ -15,7 +15,7 @@ pub enum Arch {
 }
 
 /// Operating system, as reported by `target_os`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[derive(Serialize, Deserialize)]
 pub enum OS {
   Windows,
@@ -31,7 +31,7 @@ pub enum OS {
 }
 
 /// Operating system family, as reported by `target_family`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[derive(Serialize, Deserialize)]
 pub enum Family {
   Windows,
@@ -40,7 +40,7 @@ pub enum Family {
 
 /// Further disambiguates the target platform with information about the ABI/libc,
 /// as reported by `target_env`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[derive(Serialize, Deserialize)]
 pub enum Env {
   Gnu,
@@ -51,7 +51,7 @@ pub enum Env {
 
 /// Pointer width in bits,
 /// as reported by `target_pointer_width`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[derive(Serialize, Deserialize)]
 pub enum PointerWidth {
   P64,
@@ -59,7 +59,7 @@ pub enum PointerWidth {
 }
 
 /// CPU endianness, as reported by `target_endian`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[derive(Serialize, Deserialize)]
 pub enum Endian {
   Little,
@@ -68,7 +68,7 @@ pub enum Endian {
 
 /// Combined information about a target, as reported by configuration
 /// values of the Rust compiler.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[derive(Serialize, Deserialize)]
 pub struct Target {
   /// CPU architecture
@@ -93,7 +93,7 @@ pub struct Target {
 /// logical operations on nested conditions. `True` and `False`
 /// variants provide conditions which are always true and false,
 /// respectively.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[derive(Serialize, Deserialize)]
 pub enum Condition {
   Arch(Arch),
@@ -266,6 +266,81 @@ pub fn current_target() -> Target {
 }
 
 
+impl Arch {
+  /// Returns value of `target_arch` corresponding to this architecture.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      Arch::X86 => "x86",
+      Arch::X86_64 => "x86_64",
+      Arch::Mips => "mips",
+      Arch::PowerPC => "powerpc",
+      Arch::PowerPC64 => "powerpc64",
+      Arch::Arm => "arm",
+      Arch::AArch64 => "aarch64",
+    }
+  }
+}
+
+impl OS {
+  /// Returns value of `target_os` corresponding to this operating system.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      OS::Windows => "windows",
+      OS::MacOS => "macos",
+      OS::IOS => "ios",
+      OS::Linux => "linux",
+      OS::Android => "android",
+      OS::FreeBSD => "freebsd",
+      OS::DragonFly => "dragonfly",
+      OS::Bitrig => "bitrig",
+      OS::OpenBSD => "openbsd",
+      OS::NetBSD => "netbsd",
+    }
+  }
+}
+
+impl Family {
+  /// Returns value of `target_family` corresponding to this family.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      Family::Windows => "windows",
+      Family::Unix => "unix",
+    }
+  }
+}
+
+impl Env {
+  /// Returns value of `target_env` corresponding to this environment.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      Env::Gnu => "gnu",
+      Env::Msvc => "msvc",
+      Env::Musl => "musl",
+      Env::None => "",
+    }
+  }
+}
+
+impl PointerWidth {
+  /// Returns value of `target_pointer_width` corresponding to this pointer width.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      PointerWidth::P64 => "64",
+      PointerWidth::P32 => "32",
+    }
+  }
+}
+
+impl Endian {
+  /// Returns value of `target_endian` corresponding to this endianness.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      Endian::Little => "little",
+      Endian::Big => "big",
+    }
+  }
+}
+
 impl Condition {
   /// Evaluate the condition for `target`. Returns true if
   /// `target` matches the condition.
@@ -289,4 +364,46 @@ impl Condition {
   pub fn negate(&self) -> Condition {
     Condition::Not(Box::new(self.clone()))
   }
+  /// Construct a condition that is true if both `self` and `other` are true.
+  pub fn and(&self, other: &Condition) -> Condition {
+    if self == other {
+      return self.clone();
+    }
+    let mut conditions = Vec::new();
+    for condition in &[self, other] {
+      if let Condition::And(ref nested) = **condition {
+        conditions.extend(nested.iter().cloned());
+      } else {
+        conditions.push((*condition).clone());
+      }
+    }
+    Condition::And(conditions)
+  }
+  /// Returns Rust code of this condition in the format accepted by
+  /// `#[cfg]` attribute, e.g. `all(target_os = "linux", target_pointer_width = "64")`.
+  pub fn to_cfg(&self) -> String {
+    use target::Condition::*;
+    let join = |conditions: &[Condition]| {
+      conditions
+        .iter()
+        .map(|c| c.to_cfg())
+        .collect::<Vec<_>>()
+        .join(", ")
+    };
+    match *self {
+      Arch(ref arch) => format!("target_arch = \"{}\"", arch.cfg_value()),
+      OS(ref os) => format!("target_os = \"{}\"", os.cfg_value()),
+      Family(ref family) => format!("target_family = \"{}\"", family.cfg_value()),
+      Env(ref env) => format!("target_env = \"{}\"", env.cfg_value()),
+      PointerWidth(ref pointer_width) => {
+        format!("target_pointer_width = \"{}\"", pointer_width.cfg_value())
+      }
+      Endian(ref endian) => format!("target_endian = \"{}\"", endian.cfg_value()),
+      And(ref conditions) => format!("all({})", join(conditions)),
+      Or(ref conditions) => format!("any({})", join(conditions)),
+      Not(ref condition) => format!("not({})", condition.to_cfg()),
+      True => "all()".to_string(),
+      False => "any()".to_string(),
+    }
+  }
 }
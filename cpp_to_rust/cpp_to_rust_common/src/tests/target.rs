@@ -0,0 +1,43 @@
+use target::*;
+
+fn linux_target() -> Target {
+  Target {
+    arch: Arch::X86_64,
+    os: OS::Linux,
+    family: Family::Unix,
+    env: Env::Gnu,
+    pointer_width: PointerWidth::P64,
+    endian: Endian::Little,
+  }
+}
+
+#[test]
+fn condition_to_cfg() {
+  assert_eq!(Condition::OS(OS::Windows).to_cfg(),
+             "target_os = \"windows\"");
+  assert_eq!(Condition::Env(Env::None).to_cfg(), "target_env = \"\"");
+  assert_eq!(Condition::And(vec![Condition::Family(Family::Unix),
+                                 Condition::PointerWidth(PointerWidth::P64)])
+                 .to_cfg(),
+             "all(target_family = \"unix\", target_pointer_width = \"64\")");
+  assert_eq!(Condition::Or(vec![Condition::Arch(Arch::X86), Condition::Arch(Arch::Arm)]).to_cfg(),
+             "any(target_arch = \"x86\", target_arch = \"arm\")");
+  assert_eq!(Condition::OS(OS::MacOS).negate().to_cfg(),
+             "not(target_os = \"macos\")");
+  assert_eq!(Condition::True.to_cfg(), "all()");
+  assert_eq!(Condition::False.to_cfg(), "any()");
+}
+
+#[test]
+fn condition_and() {
+  let c1 = Condition::Family(Family::Unix);
+  let c2 = Condition::Endian(Endian::Little);
+  let c3 = Condition::Arch(Arch::X86_64);
+  assert_eq!(c1.and(&c1), c1);
+  let c12 = c1.and(&c2);
+  assert_eq!(c12, Condition::And(vec![c1.clone(), c2.clone()]));
+  assert_eq!(c12.and(&c3),
+             Condition::And(vec![c1.clone(), c2.clone(), c3.clone()]));
+  assert!(c12.and(&c3).eval(&linux_target()));
+  assert!(!c12.and(&c3).negate().eval(&linux_target()));
+}
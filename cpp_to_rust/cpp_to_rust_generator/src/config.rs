@@ -5,7 +5,9 @@ use common::errors::Result;
 use cpp_method::CppMethod;
 use cpp_data::ParserCppData;
 pub use cpp_data::CppTypeAllocationPlace;
+pub use rust_info::RustEnumRepresentation;
 use common::cpp_build_config::CppBuildConfig;
+use common::target::Target;
 use std::collections::HashMap;
 use common;
 
@@ -31,6 +33,27 @@ impl ::std::fmt::Debug for CppDataFilter {
   }
 }
 
+/// Target platform for the C++ parser added with `Config::add_cpp_parser_target`.
+#[derive(Debug, Clone)]
+pub struct CppParserTarget {
+  /// Properties of the target platform
+  pub target: Target,
+  /// Additional clang arguments used to parse headers for this target
+  pub clang_arguments: Vec<String>,
+}
+
+/// Pair of arguments of a C++ method added with `Config::add_cpp_callback`
+/// that represent a callback and its user data pointer.
+#[derive(Debug, Clone)]
+pub struct CppCallback {
+  /// Full name of the C++ method (e.g. `Scheduler::post`)
+  pub method_name: String,
+  /// Name of the function pointer argument
+  pub callback_argument: String,
+  /// Name of the `void*` argument passed to the callback
+  pub user_data_argument: String,
+}
+
 /// Information about an extra non-`cpp_to_rust`-based dependency.
 #[derive(Default, Debug, Clone)]
 pub struct CrateDependency {
@@ -242,13 +265,21 @@ pub struct Config {
   target_include_paths: Vec<PathBuf>,
   include_directives: Vec<PathBuf>,
   cpp_parser_arguments: Vec<String>,
+  cpp_parser_targets: Vec<CppParserTarget>,
   cpp_parser_blocked_names: Vec<String>,
+  cpp_parser_allowed_macros: Vec<String>,
   cpp_ffi_generator_filters: Vec<CppFfiGeneratorFilter>,
   cpp_data_filters: Vec<CppDataFilter>,
   cpp_filtered_namespaces: Vec<String>,
+  catch_cpp_exceptions: bool,
+  cpp_exception_types: Vec<String>,
+  cpp_callbacks: Vec<CppCallback>,
+  cpp_subclasses: Vec<String>,
   cpp_build_config: CppBuildConfig, // TODO: add CppBuildPaths when needed
   write_dependencies_local_paths: bool,
   type_allocation_places: HashMap<String, CppTypeAllocationPlace>,
+  enum_representation: RustEnumRepresentation,
+  cpp_enum_representations: HashMap<String, RustEnumRepresentation>,
   debug_logging_config: DebugLoggingConfig,
   quiet_mode: bool,
   write_cache: bool,
@@ -276,12 +307,20 @@ impl Config {
       target_include_paths: Default::default(),
       include_directives: Default::default(),
       cpp_parser_arguments: Default::default(),
+      cpp_parser_targets: Default::default(),
       cpp_parser_blocked_names: Default::default(),
+      cpp_parser_allowed_macros: Default::default(),
       cpp_ffi_generator_filters: Default::default(),
       cpp_data_filters: Default::default(),
       cpp_filtered_namespaces: Default::default(),
+      catch_cpp_exceptions: false,
+      cpp_exception_types: Default::default(),
+      cpp_callbacks: Default::default(),
+      cpp_subclasses: Default::default(),
       cpp_build_config: Default::default(),
       type_allocation_places: Default::default(),
+      enum_representation: Default::default(),
+      cpp_enum_representations: Default::default(),
       write_dependencies_local_paths: true,
       cache_usage: CacheUsage::default(),
       debug_logging_config: DebugLoggingConfig::default(),
@@ -366,6 +405,27 @@ impl Config {
     }
   }
 
+  /// Adds a name of a preprocessor macro that should be
+  /// converted to a Rust constant. Only object-like macros
+  /// expanding to an integer, floating point, boolean or string
+  /// literal are supported. A name ending with `*` allows
+  /// all macros starting with the preceding prefix.
+  /// By default, no macros are processed.
+  pub fn add_cpp_parser_allowed_macro<P: Into<String>>(&mut self, name: P) {
+    self.cpp_parser_allowed_macros.push(name.into());
+  }
+
+  /// Adds multiple allowed macro names.
+  /// See `Config::add_cpp_parser_allowed_macro`.
+  pub fn add_cpp_parser_allowed_macros<Item, Iter>(&mut self, items: Iter)
+    where Item: Into<String>,
+          Iter: IntoIterator<Item = Item>
+  {
+    for item in items {
+      self.cpp_parser_allowed_macros.push(item.into());
+    }
+  }
+
   /// Adds a command line argument for clang C++ parser.
   ///
   /// Note that this value is not used when building the wrapper library.
@@ -386,6 +446,25 @@ impl Config {
     }
   }
 
+  /// Adds a target platform for the C++ parser. `clang_arguments` are added
+  /// to the common parser arguments when parsing headers for this target
+  /// (e.g. `-target x86_64-pc-windows-msvc` and platform-specific include paths).
+  ///
+  /// If no targets are added, headers are parsed once for the current platform.
+  /// Otherwise headers are parsed once for each added target (the current
+  /// platform should be added explicitly if required). Classes and methods
+  /// that are not available for all targets are guarded with `#[cfg]` attributes
+  /// in the generated crate and with preprocessor conditions in the C++ wrapper library.
+  /// Typedefs, variables and macros are only kept if they are available for all targets.
+  pub fn add_cpp_parser_target(&mut self, target: Target, clang_arguments: Vec<String>) {
+    self
+      .cpp_parser_targets
+      .push(CppParserTarget {
+              target: target,
+              clang_arguments: clang_arguments,
+            });
+  }
+
 
   /// Adds path to an include directory.
   /// It's supplied to the C++ parser via `-I` option.
@@ -472,6 +551,58 @@ impl Config {
     }
   }
 
+  /// Enables catching C++ exceptions in the wrapper library.
+  /// If enabled, FFI wrappers of C++ functions that may throw
+  /// (i.e. functions without `noexcept` or `throw()` specification)
+  /// catch `std::exception` and types added with `Config::add_cpp_exception_type`,
+  /// and the corresponding Rust functions return `Result<T, cpp_utils::CppException>`.
+  /// Catching exceptions is disabled by default.
+  pub fn set_catch_cpp_exceptions(&mut self, value: bool) {
+    self.catch_cpp_exceptions = value;
+  }
+
+  /// Adds a C++ exception type that should be caught by the wrapper library
+  /// in addition to `std::exception`. Name of the type is used as the exception
+  /// message. Types derived from `std::exception` don't need to be added.
+  /// See also `Config::set_catch_cpp_exceptions`.
+  pub fn add_cpp_exception_type<S: Into<String>>(&mut self, name: S) {
+    self.cpp_exception_types.push(name.into());
+  }
+
+  /// Declares that `callback_argument` of C++ method `method_name`
+  /// is a function pointer that receives `user_data_argument` of the same method
+  /// as one of its arguments. The generated Rust method accepts
+  /// a closure instead of these two arguments.
+  ///
+  /// The closure is only valid during the call, so this conversion must not be
+  /// used for methods that store the callback for later use. Callback arguments
+  /// of methods that were not declared with this function are kept as raw
+  /// function pointers.
+  pub fn add_cpp_callback<S1, S2, S3>(&mut self,
+                                      method_name: S1,
+                                      callback_argument: S2,
+                                      user_data_argument: S3)
+    where S1: Into<String>,
+          S2: Into<String>,
+          S3: Into<String>
+  {
+    self
+      .cpp_callbacks
+      .push(CppCallback {
+              method_name: method_name.into(),
+              callback_argument: callback_argument.into(),
+              user_data_argument: user_data_argument.into(),
+            });
+  }
+
+  /// Requests generation of a C++ subclass of class `class_name`
+  /// that allows to override its virtual methods in Rust.
+  /// The generated Rust crate will contain a trait with all
+  /// supported virtual methods of the class. Default implementations
+  /// of the trait's methods call the base class implementation.
+  pub fn add_cpp_subclass<S: Into<String>>(&mut self, class_name: S) {
+    self.cpp_subclasses.push(class_name.into());
+  }
 
   /// Overrides automatic selection of type allocation place for `type_name` and uses `place`
   /// instead. See `CppTypeAllocationPlace` for more information.
@@ -495,6 +626,23 @@ impl Config {
     }
   }
 
+  /// Sets representation of C++ enums in the generated crate.
+  /// Default is `RustEnumRepresentation::Enum`.
+  /// See `RustEnumRepresentation` for more information.
+  pub fn set_enum_representation(&mut self, representation: RustEnumRepresentation) {
+    self.enum_representation = representation;
+  }
+
+  /// Overrides representation of C++ enum `enum_name` (e.g. `Qt::Key`) in the generated crate.
+  /// See also `Config::set_enum_representation`.
+  pub fn set_cpp_enum_representation<S: Into<String>>(&mut self,
+                                                      representation: RustEnumRepresentation,
+                                                      enum_name: S) {
+    self
+      .cpp_enum_representations
+      .insert(enum_name.into(), representation);
+  }
+
   /// Changes how debug logs are handled. See `DebugLoggingConfig` for more information.
   pub fn set_debug_logging_config(&mut self, config: DebugLoggingConfig) {
     self.debug_logging_config = config;
@@ -587,12 +735,23 @@ impl Config {
     &self.cpp_parser_blocked_names
   }
 
+  /// Returns names added with `Config::add_cpp_parser_allowed_macro`
+  /// and similar methods.
+  pub fn cpp_parser_allowed_macros(&self) -> &[String] {
+    &self.cpp_parser_allowed_macros
+  }
+
   /// Returns names added with `Config::add_cpp_parser_argument`
   /// and similar methods.
   pub fn cpp_parser_arguments(&self) -> &[String] {
     &self.cpp_parser_arguments
   }
 
+  /// Returns values added by `Config::add_cpp_parser_target`.
+  pub fn cpp_parser_targets(&self) -> &[CppParserTarget] {
+    &self.cpp_parser_targets
+  }
+
 
   /// Returns values added by `Config::add_include_path`.
   pub fn include_paths(&self) -> &[PathBuf] {
@@ -637,6 +796,37 @@ impl Config {
     &self.cpp_filtered_namespaces
   }
 
+  /// Returns value set by `Config::set_catch_cpp_exceptions`.
+  pub fn catch_cpp_exceptions(&self) -> bool {
+    self.catch_cpp_exceptions
+  }
+
+  /// Returns values added by `Config::add_cpp_exception_type`.
+  pub fn cpp_exception_types(&self) -> &[String] {
+    &self.cpp_exception_types
+  }
+
+  /// Returns values added by `Config::add_cpp_callback`.
+  pub fn cpp_callbacks(&self) -> &[CppCallback] {
+    &self.cpp_callbacks
+  }
+
+  /// Returns values added by `Config::add_cpp_subclass`.
+  pub fn cpp_subclasses(&self) -> &[String] {
+    &self.cpp_subclasses
+  }
+
+  /// Returns value set by `Config::set_enum_representation`.
+  pub fn enum_representation(&self) -> &RustEnumRepresentation {
+    &self.enum_representation
+  }
+
+  /// Returns values added by `Config::set_cpp_enum_representation`.
+  /// Keys of the hash map are names of C++ enums.
+  pub fn cpp_enum_representations(&self) -> &HashMap<String, RustEnumRepresentation> {
+    &self.cpp_enum_representations
+  }
+
   /// Returns current `CppBuildConfig` value.
   pub fn cpp_build_config(&self) -> &CppBuildConfig {
     &self.cpp_build_config
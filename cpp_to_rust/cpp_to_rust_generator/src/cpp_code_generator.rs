@@ -1,17 +1,30 @@
 use cpp_ffi_data::{QtSlotWrapper, CppIndirectionChange, CppAndFfiMethod, CppFfiArgumentMeaning,
-                   CppFfiHeaderData, CppFfiType, CppFieldAccessorType, CppFfiMethodKind};
-use cpp_method::ReturnValueAllocationPlace;
+                   CppFfiHeaderData, CppFfiType, CppFieldAccessorType, CppFfiMethodKind,
+                   CppSubclass, QtDynamicMetaObject};
+use cpp_method::{ReturnValueAllocationPlace, CppExceptionSpecification};
 use cpp_type::{CppTypeIndirection, CppTypeBase, CppType};
+use cpp_operator::CppOperator;
 use common::errors::{Result, ChainErr, unexpected};
 use common::file_utils::{PathBufWithAdded, create_dir_all, create_file, path_to_str};
 use common::string_utils::JoinWithSeparator;
 use common::utils::MapIfOk;
 use common::utils::get_command_output;
+use common::target::Condition;
+use common::cpp_condition_macro_name;
 
 use std::path::PathBuf;
 use std::iter::once;
 use std::process::Command;
 
+/// Returns C++ code declaring variable `name` of type `type1`.
+fn variable_declaration(type1: &CppType, name: &str) -> Result<String> {
+  if let CppTypeBase::FunctionPointer(..) = type1.base {
+    type1.to_cpp_code(Some(name))
+  } else {
+    Ok(format!("{} {}", type1.to_cpp_code(None)?, name))
+  }
+}
+
 /// Generates C++ code for the C wrapper library.
 pub struct CppCodeGenerator {
   /// Library name
@@ -20,37 +33,88 @@ pub struct CppCodeGenerator {
   lib_name_upper: String,
   /// Path to the directory where the library is generated
   lib_path: ::std::path::PathBuf,
+  /// C++ exception types caught by wrapper functions
+  /// in addition to `std::exception`
+  exception_types: Vec<String>,
+  /// Target conditions of platform-specific items
+  /// (see `BuildScriptData::cpp_conditions`)
+  conditions: Vec<Condition>,
 }
 
 impl CppCodeGenerator {
   /// Creates a generator for a library.
   /// lib_name: library name
   /// lib_path: path to the directory where the library is generated
-  pub fn new(lib_name: String, lib_path: ::std::path::PathBuf) -> Self {
+  /// exception_types: C++ exception types caught by wrapper functions
+  /// in addition to `std::exception`
+  /// conditions: target conditions of platform-specific items
+  pub fn new(lib_name: String,
+             lib_path: ::std::path::PathBuf,
+             exception_types: Vec<String>,
+             conditions: Vec<Condition>)
+             -> Self {
     CppCodeGenerator {
       lib_name: lib_name.clone(),
       lib_name_upper: lib_name.to_uppercase(),
       lib_path: lib_path,
+      exception_types: exception_types,
+      conditions: conditions,
     }
   }
 
+  /// Returns name of the preprocessor macro that is defined by the build script
+  /// if `condition` is true for the target platform.
+  pub fn condition_macro(&self, condition: &Condition) -> Result<String> {
+    let index = self
+      .conditions
+      .iter()
+      .position(|c| c == condition)
+      .chain_err(|| format!("unknown condition: {:?}", condition))?;
+    Ok(cpp_condition_macro_name(&self.lib_name, index))
+  }
+
+  /// Wraps `code` in a preprocessor condition if `condition` is not None.
+  fn guarded_code(&self, condition: Option<&Condition>, code: String) -> Result<String> {
+    Ok(match condition {
+         Some(condition) => {
+           format!("#ifdef {}\n{}#endif\n", self.condition_macro(condition)?, code)
+         }
+         None => code,
+       })
+  }
+
   /// Generates function name, return type and arguments list
   /// as it appears in both function declaration and implementation.
   fn function_signature(&self, method: &CppAndFfiMethod) -> Result<String> {
     let mut arg_texts = Vec::new();
     for arg in &method.c_signature.arguments {
-      arg_texts.push(arg.to_cpp_code()?);
+      if arg.argument_type.conversion == CppIndirectionChange::StdFunctionToClosure {
+        arg_texts.push(format!("{} {}", self.ffi_type_to_cpp_code(&arg.argument_type)?, arg.name));
+      } else {
+        arg_texts.push(arg.to_cpp_code()?);
+      }
     }
     let name_with_args = format!("{}({})", method.c_name, arg_texts.join(", "));
-    let return_type = &method.c_signature.return_type.ffi_type;
-    let r = if let CppTypeBase::FunctionPointer(..) = return_type.base {
-      return_type.to_cpp_code(Some(&name_with_args))?
+    let return_type = &method.c_signature.return_type;
+    let r = if let CppTypeBase::FunctionPointer(..) = return_type.ffi_type.base {
+      return_type.ffi_type.to_cpp_code(Some(&name_with_args))?
     } else {
-      format!("{} {}", return_type.to_cpp_code(None)?, name_with_args)
+      format!("{} {}", self.ffi_type_to_cpp_code(return_type)?, name_with_args)
     };
     Ok(r)
   }
 
+  /// Returns C++ code representing FFI type of `type1`.
+  /// `std::function` types are passed through FFI as closure structs
+  /// declared in the library's global header.
+  fn ffi_type_to_cpp_code(&self, type1: &CppFfiType) -> Result<String> {
+    if type1.conversion == CppIndirectionChange::StdFunctionToClosure {
+      Ok(format!("{}_closure", self.lib_name))
+    } else {
+      type1.ffi_type.to_cpp_code(None)
+    }
+  }
+
   /// Generates method declaration for the header.
   fn function_declaration(&self, method: &CppAndFfiMethod) -> Result<String> {
     Ok(format!("{}_EXPORT {};\n",
@@ -92,6 +156,150 @@ impl CppCodeGenerator {
 
   }
 
+  /// Generates code for classes that allow to define Qt meta-objects at runtime
+  fn qt_dynamic_meta_object(&self, data: &QtDynamicMetaObject) -> Result<String> {
+    let func_type = CppType {
+      base: CppTypeBase::FunctionPointer(data.function_type.clone()),
+      indirection: CppTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    };
+    Ok(format!(include_str!("../templates/c_lib/qt_dynamic_meta_object.h"),
+               builder_class_name = data.builder_class_name,
+               object_class_name = data.object_class_name,
+               callback_arg = func_type.to_cpp_code(Some("callback"))?,
+               callback_field = func_type.to_cpp_code(Some("m_callback"))?))
+  }
+
+  /// Generates code for a subclass that forwards calls of its virtual methods
+  /// to callbacks. A virtual method calls the base class implementation
+  /// if its callback is not set.
+  fn subclass(&self, subclass: &CppSubclass) -> Result<String> {
+    let mut methods = Vec::new();
+    let mut fields = Vec::new();
+    for method in &subclass.virtual_methods {
+      let info = method
+        .cpp_method
+        .class_membership
+        .as_ref()
+        .chain_err(|| unexpected("virtual method must be a class member"))?;
+      let func_type = CppType {
+        base: CppTypeBase::FunctionPointer(method.function_type.clone()),
+        indirection: CppTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
+      };
+      let field_name = format!("m_{}_callback", method.name);
+      let args = method
+        .cpp_method
+        .arguments
+        .iter()
+        .enumerate()
+        .map_if_ok(|(num, arg)| variable_declaration(&arg.argument_type, &format!("arg{}", num)))?
+        .join(", ");
+      let arg_names = (0..method.cpp_method.arguments.len())
+        .map(|num| format!("arg{}", num))
+        .join(", ");
+      let const_qualifier = if info.is_const { " const" } else { "" };
+      let exception_specification = match method.cpp_method.exception_specification {
+        CppExceptionSpecification::None => String::new(),
+        CppExceptionSpecification::NoThrow => " noexcept".to_string(),
+        CppExceptionSpecification::Dynamic(ref types) => format!(" throw({})", types.join(", ")),
+      };
+      let callback_args = once("m_data".to_string())
+        .chain(once("this".to_string()))
+        .chain(method
+                 .arguments
+                 .iter()
+                 .enumerate()
+                 .map_if_ok(|(num, t)| if t.conversion == CppIndirectionChange::ValueToPointer {
+                              // the argument only needs to live until the callback returns
+                              Ok(format!("&arg{}", num))
+                            } else {
+                              self.convert_type_to_ffi(t, format!("arg{}", num))
+                            })?)
+        .join(", ");
+      let callback_call = format!("{}({})", field_name, callback_args);
+      let return_type = method.cpp_method.return_type.to_cpp_code(None)?;
+      let callback_code = match method.return_type.conversion {
+        CppIndirectionChange::NoChange => {
+          if method.return_type.ffi_type.is_void() {
+            format!("{};\n      return;", callback_call)
+          } else {
+            format!("return {};", callback_call)
+          }
+        }
+        CppIndirectionChange::ValueToPointer => {
+          // the callback passes ownership of the returned object
+          let mut object_type = method.return_type.ffi_type.clone();
+          object_type.indirection = CppTypeIndirection::None;
+          format!("std::unique_ptr<{}> result({});\n      return std::move(*result);",
+                  object_type.to_cpp_code(None)?,
+                  callback_call)
+        }
+        CppIndirectionChange::ReferenceToPointer => format!("return *{};", callback_call),
+        CppIndirectionChange::QFlagsToUInt => {
+          format!("return {}({});", return_type, callback_call)
+        }
+        CppIndirectionChange::StdFunctionToClosure => {
+          return Err(unexpected("std::function types are not supported in subclasses").into());
+        }
+      };
+      let fallback = if info.is_pure_virtual {
+        "std::terminate();".to_string()
+      } else {
+        format!("return {}::{}({});",
+                subclass.base_class_name,
+                method.cpp_method.name,
+                arg_names)
+      };
+      methods.push(format!("  void set_{name}_callback({callback_arg}) {{\n    \
+                              {field} = callback;\n  \
+                            }}\n  \
+                            {return_type} {method_name}({args}){const_qualifier}\
+                            {exception_specification} override {{\n    \
+                              if ({field}) {{\n      \
+                                {callback_code}\n    \
+                              }}\n    \
+                              {fallback}\n  \
+                            }}\n",
+                           name = method.name,
+                           callback_arg = func_type.to_cpp_code(Some("callback"))?,
+                           field = field_name,
+                           return_type = return_type,
+                           method_name = method.cpp_method.name,
+                           args = args,
+                           const_qualifier = const_qualifier,
+                           exception_specification = exception_specification,
+                           callback_code = callback_code,
+                           fallback = fallback));
+      if !info.is_pure_virtual {
+        methods.push(format!("  {return_type} {name}_base({args}){const_qualifier} {{\n    \
+                                return {base_class_name}::{method_name}({arg_names});\n  \
+                              }}\n",
+                             return_type = return_type,
+                             name = method.name,
+                             args = args,
+                             const_qualifier = const_qualifier,
+                             base_class_name = subclass.base_class_name,
+                             method_name = method.cpp_method.name,
+                             arg_names = arg_names));
+      }
+      fields.push(format!("  {} = 0;\n", func_type.to_cpp_code(Some(&field_name))?));
+    }
+    let base_constructor_name = subclass
+      .base_class_name
+      .split("::")
+      .last()
+      .chain_err(|| unexpected("split can't return empty iterator"))?;
+    Ok(format!(include_str!("../templates/c_lib/subclass.h"),
+               class_name = subclass.class_name,
+               base_class_name = subclass.base_class_name,
+               base_constructor_name = base_constructor_name,
+               methods = methods.join(""),
+               fields = fields.join("")))
+  }
+
   /// Generates code that wraps `expression` of type `type1.original_type` and
   /// converts it to type `type1.ffi_type`
   fn convert_type_to_ffi(&self, type1: &CppFfiType, expression: String) -> Result<String> {
@@ -104,6 +312,12 @@ impl CppCodeGenerator {
          }
          CppIndirectionChange::ReferenceToPointer => format!("&{}", expression),
          CppIndirectionChange::QFlagsToUInt => format!("uint({})", expression),
+         CppIndirectionChange::StdFunctionToClosure => {
+           format!("{}_std_function<{}>::to_closure({})",
+                   self.lib_name,
+                   type1.original_type.base.to_cpp_code(None)?,
+                   expression)
+         }
        })
   }
 
@@ -139,6 +353,17 @@ impl CppCodeGenerator {
       CppIndirectionChange::QFlagsToUInt => {
         result = format!("uint({})", result);
       }
+      CppIndirectionChange::StdFunctionToClosure => {
+        result = format!("{}_std_function<{}>::to_closure({})",
+                         self.lib_name,
+                         method
+                           .c_signature
+                           .return_type
+                           .original_type
+                           .base
+                           .to_cpp_code(None)?,
+                         result);
+      }
     }
 
     if method.allocation_place == ReturnValueAllocationPlace::Stack &&
@@ -183,6 +408,16 @@ impl CppCodeGenerator {
             };
             result = format!("{}({})", type_text, result);
           }
+          CppIndirectionChange::StdFunctionToClosure => {
+            result = format!("{}_std_function<{}>::from_closure({})",
+                             self.lib_name,
+                             c_argument
+                               .argument_type
+                               .original_type
+                               .base
+                               .to_cpp_code(None)?,
+                             result);
+          }
         }
         filled_arguments.push(result);
       } else {
@@ -205,6 +440,18 @@ impl CppCodeGenerator {
       } else {
         return Err(unexpected("no this arg in destructor").into());
       }
+    } else if let Some(CppOperator::Conversion(ref target)) = method.cpp_method.operator {
+      // conversion operators implementing the safe bool idiom
+      // can't be called by name, but `static_cast` works for all of them
+      if let Some(arg) = method
+           .c_signature
+           .arguments
+           .iter()
+           .find(|x| x.meaning == CppFfiArgumentMeaning::This) {
+        format!("static_cast<{}>(*{})", target.to_cpp_code(None)?, arg.name)
+      } else {
+        return Err(unexpected("no this arg in conversion operator").into());
+      }
     } else {
       let mut is_field_accessor = false;
       let result_without_args = if let Some(info) = method.cpp_method.class_info_if_constructor() {
@@ -289,26 +536,75 @@ impl CppCodeGenerator {
 
   /// Generates body of the FFI method implementation.
   fn source_body(&self, method: &CppAndFfiMethod) -> Result<String> {
-    if method.cpp_method.is_destructor() &&
-       method.allocation_place == ReturnValueAllocationPlace::Heap {
+    let body = if method.cpp_method.is_destructor() &&
+                  method.allocation_place == ReturnValueAllocationPlace::Heap {
       if let Some(arg) = method
            .c_signature
            .arguments
            .iter()
            .find(|x| x.meaning == CppFfiArgumentMeaning::This) {
-        Ok(format!("delete {};\n", arg.name))
+        format!("delete {};\n", arg.name)
       } else {
         panic!("Error: no this argument found\n{:?}", method);
       }
     } else {
-      Ok(format!("{}{};\n",
-                 if method.c_signature.return_type.ffi_type.is_void() {
-                   ""
-                 } else {
-                   "return "
-                 },
-                 self.returned_expression(&method)?))
+      format!("{}{};\n",
+              if method.c_signature.return_type.ffi_type.is_void() {
+                ""
+              } else {
+                "return "
+              },
+              self.returned_expression(&method)?)
+    };
+    if let Some(arg) = method
+         .c_signature
+         .arguments
+         .iter()
+         .find(|x| x.meaning == CppFfiArgumentMeaning::ExceptionSlot) {
+      self.catch_exceptions(method, &arg.name, &body)
+    } else {
+      Ok(body)
+    }
+  }
+
+  /// Wraps `body` of the FFI method implementation in a `try` block.
+  /// Information about caught C++ exceptions is written to
+  /// the `slot_name` argument.
+  fn catch_exceptions(&self,
+                      method: &CppAndFfiMethod,
+                      slot_name: &str,
+                      body: &str)
+                      -> Result<String> {
+    let mut handlers = Vec::new();
+    for exception_type in &self.exception_types {
+      handlers.push(format!("catch (const {name}&) {{\n    \
+                             {lib}_set_exception({slot}, \"{name}\");\n  }}",
+                            name = exception_type,
+                            lib = self.lib_name,
+                            slot = slot_name));
     }
+    handlers.push(format!("catch (const std::exception& e) {{\n    \
+                           {lib}_set_exception({slot}, e.what());\n  }}",
+                          lib = self.lib_name,
+                          slot = slot_name));
+    handlers.push(format!("catch (...) {{\n    \
+                           {lib}_set_exception({slot}, \"unknown C++ exception\");\n  }}",
+                          lib = self.lib_name,
+                          slot = slot_name));
+    let return_type = &method.c_signature.return_type;
+    let default_return = if return_type.ffi_type.is_void() {
+      String::new()
+    } else if let CppTypeBase::FunctionPointer(..) = return_type.ffi_type.base {
+      "  return 0;\n".to_string()
+    } else {
+      format!("  return {}_default_value<{}>();\n",
+              self.lib_name,
+              self.ffi_type_to_cpp_code(return_type)?)
+    };
+    Ok(format!("try {{\n    {body}  }} {handlers}\n{default_return}",
+               body = body,
+               handlers = handlers.join(" "),
+               default_return = default_return))
   }
 
   /// Generates implementation of the FFI method for the source file.
@@ -414,10 +710,17 @@ impl CppCodeGenerator {
       for wrapper in &data.qt_slot_wrappers {
         h_file.write(self.qt_slot_wrapper(wrapper)?)?;
       }
+      for subclass in &data.subclasses {
+        h_file.write(self.subclass(subclass)?)?;
+      }
+      if let Some(ref meta_object) = data.qt_dynamic_meta_object {
+        h_file.write(self.qt_dynamic_meta_object(meta_object)?)?;
+      }
       h_file.write("extern \"C\" {\n\n")?;
       for method in &data.methods {
-        h_file.write(self.function_declaration(method)?)?;
-        cpp_file.write(self.function_implementation(method)?)?;
+        let condition = method.cpp_method.condition.as_ref();
+        h_file.write(self.guarded_code(condition, self.function_declaration(method)?)?)?;
+        cpp_file.write(self.guarded_code(condition, self.function_implementation(method)?)?)?;
       }
 
       h_file.write("\n} // extern \"C\"\n\n")?;
@@ -444,6 +747,9 @@ pub struct CppTypeSizeRequest {
   pub cpp_code: String,
   /// Name of the constant in `type_sizes.rs`.
   pub size_const_name: String,
+  /// Name of the preprocessor macro guarding this request
+  /// if the type is platform-specific.
+  pub condition_macro: Option<String>,
 }
 
 /// Generates a C++ program that determines sizes of target C++ types
@@ -458,9 +764,15 @@ pub fn generate_cpp_type_size_requester(requests: &[CppTypeSizeRequest],
   }
   result.push("#include <iostream>\n\nint main() {\n".to_string());
   for request in requests {
+    if let Some(ref condition_macro) = request.condition_macro {
+      result.push(format!("#ifdef {}\n", condition_macro));
+    }
     result.push(format!("  std::cout << \"pub const {}: usize = \" << sizeof({}) << \";\\n\";\n",
                         request.size_const_name,
                         request.cpp_code));
+    if request.condition_macro.is_some() {
+      result.push("#endif\n".to_string());
+    }
   }
   result.push("}\n".to_string());
   Ok(result.join(""))
@@ -7,6 +7,7 @@ use cpp_type::{CppType, CppTypeBase, CppTypeIndirection, CppTypeClassBase};
 use common::errors::{Result, ChainErr};
 use common::file_utils::open_file;
 use common::log;
+use common::target::Condition;
 
 use std::collections::{HashSet, HashMap};
 use std::iter::once;
@@ -85,6 +86,11 @@ pub enum CppTypeKind {
     /// List of using directives, like "using BaseClass::method1;"
     using_directives: Vec<CppClassUsingDirective>,
   },
+  /// Union declaration
+  Union {
+    /// List of union fields
+    fields: Vec<CppClassField>,
+  },
 }
 
 /// Location of a C++ type's definition in header files.
@@ -109,6 +115,16 @@ pub enum CppVisibility {
   Private,
 }
 
+/// Information about a C++ entity marked as deprecated
+/// (using `[[deprecated]]`, `__attribute__((deprecated))`
+/// or a macro expanding to one of them, like `QT_DEPRECATED`)
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Serialize, Deserialize)]
+pub struct CppDeprecation {
+  /// Message specified in the attribute, if any
+  pub message: Option<String>,
+}
+
 /// C++ documentation for a type
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[derive(Serialize, Deserialize)]
@@ -136,6 +152,115 @@ pub struct CppTypeData {
   pub kind: CppTypeKind,
   /// C++ documentation data for this type
   pub doc: Option<CppTypeDoc>,
+  /// Deprecation information, if the type is deprecated
+  pub deprecation: Option<CppDeprecation>,
+  /// Condition on target platforms this type is available for.
+  /// None if the type is available for all parsed targets.
+  pub condition: Option<Condition>,
+  /// Qt properties declared in this class with `Q_PROPERTY` macro
+  pub qt_properties: Vec<CppQtProperty>,
+  /// For types nested into a template class (like `QList<T>::iterator`),
+  /// name of the enclosing template class
+  pub enclosing_template_class: Option<String>,
+}
+
+/// Information about a Qt property declared with `Q_PROPERTY` macro
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct CppQtProperty {
+  /// Name of the property
+  pub name: String,
+  /// C++ type of the property, as written in the declaration
+  pub type_name: String,
+  /// Name of the getter method (`READ`)
+  pub read: Option<String>,
+  /// Name of the setter method (`WRITE`)
+  pub write: Option<String>,
+  /// Name of the method that resets the property to its default value (`RESET`)
+  pub reset: Option<String>,
+  /// Name of the signal emitted when the property's value changes (`NOTIFY`)
+  pub notify: Option<String>,
+}
+
+/// Information about a C++ typedef or a type alias
+/// declared with `using`
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct CppTypedef {
+  /// Identifier, including namespaces and nested classes
+  /// (separated with "::", like in C++)
+  pub name: String,
+  /// File name of the include file (without full path)
+  pub include_file: String,
+  /// Exact location of the declaration
+  pub origin_location: CppOriginLocation,
+  /// Type this alias refers to
+  pub target_type: CppType,
+  /// C++ documentation data for this alias
+  pub doc: Option<CppTypeDoc>,
+}
+
+/// Value of a C++ compile-time constant
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize)]
+pub enum CppLiteralValue {
+  /// Boolean literal
+  Bool(bool),
+  /// Integer literal
+  Integer(i64),
+  /// Floating point literal, stored as source code without suffixes
+  Float(String),
+  /// String literal
+  String(String),
+}
+
+/// Information about a C++ variable: a global or namespace-level variable,
+/// a static data member of a class or a `constexpr` constant
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct CppVariable {
+  /// Identifier, including namespaces and nested classes
+  /// (separated with "::", like in C++)
+  pub name: String,
+  /// Class type if this is a static data member
+  pub class_type: Option<CppTypeClassBase>,
+  /// Type of the variable
+  pub variable_type: CppType,
+  /// True if the variable itself is `const`
+  pub is_const: bool,
+  /// True if the variable is declared `constexpr`
+  pub is_constexpr: bool,
+  /// Value of the variable if it's a constant initialized with a literal
+  pub value: Option<CppLiteralValue>,
+  /// File name of the include file (without full path)
+  pub include_file: String,
+  /// Exact location of the declaration
+  pub origin_location: CppOriginLocation,
+}
+
+impl CppVariable {
+  /// Returns name of the variable without namespaces and class name.
+  pub fn short_name(&self) -> &str {
+    match self.name.rfind("::") {
+      Some(index) => &self.name[index + 2..],
+      None => &self.name,
+    }
+  }
+}
+
+/// Information about an object-like C++ preprocessor macro
+/// that expands to a literal
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct CppMacro {
+  /// Identifier of the macro
+  pub name: String,
+  /// Value the macro expands to
+  pub value: CppLiteralValue,
+  /// File name of the include file (without full path)
+  pub include_file: String,
+  /// Exact location of the declaration
+  pub origin_location: CppOriginLocation,
 }
 
 /// Information about template arguments of a C++ class type
@@ -158,6 +283,9 @@ pub struct TemplateArgumentsDeclaration {
   /// Names of template arguments. Names themselves are
   /// not particularly important, but their count is.
   pub names: Vec<String>,
+  /// Indexes of non-type template parameters (integral or enum constants),
+  /// like `Prealloc` in `template<class T, int Prealloc> class QVarLengthArray`.
+  pub value_parameters: Vec<usize>,
 }
 
 /// Information about a C++ template class
@@ -180,6 +308,23 @@ pub struct CppTemplateInstantiations {
   pub instantiations: Vec<CppTemplateInstantiation>,
 }
 
+/// Information about a C++ class that can be iterated over
+/// using a pair of iterators.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct CppContainerIterator {
+  /// Name of the container class
+  pub container_class: String,
+  /// Name of the iterator class returned by `begin_method` and `end_method`
+  pub iterator_class: String,
+  /// Name of the method returning iterator to the first item
+  /// (e.g. `begin` or `constBegin`)
+  pub begin_method: String,
+  /// Name of the method returning past-the-end iterator
+  /// (e.g. `end` or `constEnd`)
+  pub end_method: String,
+}
+
 /// Type allocation place of a C++ type.
 ///
 /// The generator chooses type allocation place for each C++ type based on the library's API.
@@ -203,6 +348,12 @@ pub struct ParserCppData {
   pub types: Vec<CppTypeData>,
   /// List of found methods
   pub methods: Vec<CppMethod>,
+  /// List of found typedefs and type aliases
+  pub typedefs: Vec<CppTypedef>,
+  /// List of found global variables, static data members and constants
+  pub variables: Vec<CppVariable>,
+  /// List of found macros allowed by `Config::add_cpp_parser_allowed_macro`
+  pub macros: Vec<CppMacro>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
@@ -222,6 +373,12 @@ pub struct ProcessedCppData {
   /// List of selected (automatically or in configuration)
   /// type allocation places for all class types.
   pub type_allocation_places: HashMap<String, CppTypeAllocationPlace>,
+  /// List of classes that can be copied by copying their memory
+  /// (see `CppDataWithDeps::is_trivially_copyable`).
+  pub trivially_copyable_types: Vec<String>,
+  /// List of classes that provide a pair of iterators usable
+  /// for implementing Rust's `Iterator` trait.
+  pub container_iterators: Vec<CppContainerIterator>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
@@ -257,10 +414,12 @@ pub struct CppDataWithDeps<'a> {
 
 
 impl CppTypeData {
-  /// Checks if the type is a class type.
+  /// Checks if the type is a class type. Unions are also
+  /// considered class types because they are referenced the same way.
   pub fn is_class(&self) -> bool {
     match self.kind {
-      CppTypeKind::Class { .. } => true,
+      CppTypeKind::Class { .. } |
+      CppTypeKind::Union { .. } => true,
       _ => false,
     }
   }
@@ -276,6 +435,7 @@ impl CppTypeData {
     Ok(CppTypeClassBase {
          name: self.name.clone(),
          template_arguments: self.default_template_arguments(),
+         enclosing_template_class: self.enclosing_template_class.clone(),
        })
   }
 
@@ -352,6 +512,7 @@ impl ParserCppData {
           if let CppTypeBase::Class(CppTypeClassBase {
                                       ref name,
                                       ref template_arguments,
+                                      ..
                                     }) = base.base_type.base {
             if template_arguments.is_some() {
               return true;
@@ -535,15 +696,19 @@ impl<'a> CppDataWithDeps<'a> {
     if let CppTypeBase::Class(CppTypeClassBase {
                                 ref name,
                                 ref template_arguments,
+                                ref enclosing_template_class,
                               }) = type1.base {
       if let Some(ref template_arguments) = *template_arguments {
+        // types nested into a template class are instantiated
+        // along with the enclosing class
         let is_valid = |cpp_data: &CppData| {
           cpp_data
             .processed
             .template_instantiations
             .iter()
             .any(|inst| {
-                   &inst.class_name == name &&
+                   (&inst.class_name == name ||
+                    Some(&inst.class_name) == enclosing_template_class.as_ref()) &&
                    inst
                      .instantiations
                      .iter()
@@ -595,6 +760,56 @@ impl<'a> CppDataWithDeps<'a> {
       .find(f)
   }
 
+  /// Returns true if class `class_name` is trivially copyable,
+  /// i.e. doesn't have any C++ logic in its copy constructor,
+  /// assignment operator and destructor.
+  pub fn is_trivially_copyable(&self, class_name: &str) -> bool {
+    once(&self.current)
+      .chain(self.dependencies.iter().map(|x| *x))
+      .any(|data| {
+             data
+               .processed
+               .trivially_copyable_types
+               .iter()
+               .any(|x| x == class_name)
+           })
+  }
+
+  /// Returns information about iterators of container class `class_name`
+  /// if it was found in this `CppData` or any of dependencies.
+  pub fn container_iterator(&self, class_name: &str) -> Option<&CppContainerIterator> {
+    once(&self.current)
+      .chain(self.dependencies.iter().map(|x| *x))
+      .filter_map(|data| {
+                    data
+                      .processed
+                      .container_iterators
+                      .iter()
+                      .find(|x| x.container_class == class_name)
+                  })
+      .next()
+  }
+
+  /// Returns true if `target_class` has a converting constructor
+  /// (see `CppMethod::is_converting_constructor`) receiving `source_class`
+  /// in this `CppData` or any of dependencies.
+  pub fn has_converting_constructor(&self,
+                                    target_class: &CppTypeClassBase,
+                                    source_class: &CppTypeClassBase)
+                                    -> bool {
+    once(&self.current)
+      .chain(self.dependencies.iter().map(|x| *x))
+      .flat_map(|data| data.parser.methods.iter())
+      .any(|method| {
+             method.is_converting_constructor() &&
+             method
+               .class_membership
+               .as_ref()
+               .map_or(false, |info| &info.class_type == target_class) &&
+             method.arguments[0].argument_type.base == CppTypeBase::Class(source_class.clone())
+           })
+  }
+
 
 
 
@@ -620,6 +835,7 @@ impl<'a> CppDataWithDeps<'a> {
             if let CppTypeBase::Class(CppTypeClassBase {
                                         ref name,
                                         ref template_arguments,
+                                        ..
                                       }) = base.base_type.base {
               if name == base_name {
                 log::llog(log::DebugInheritance, || {
@@ -927,6 +1143,36 @@ impl<'a> CppDataWithDeps<'a> {
     false
   }
 
+  /// Returns all virtual methods (own or inherited) of the specified class,
+  /// excluding destructors.
+  pub fn virtual_methods(&self, class_name: &str) -> Vec<&CppMethod> {
+    once(&self.current)
+      .chain(self.dependencies.iter().map(|d| *d))
+      .flat_map(|data| data.parser.methods.iter().chain(data.processed.inherited_methods.iter()))
+      .filter(|method| if let Some(ref info) = method.class_membership {
+                &info.class_type.name == class_name && info.is_virtual &&
+                !info.kind.is_destructor()
+              } else {
+                false
+              })
+      .collect()
+  }
+
+  /// Checks if specified class is declared with `Q_OBJECT` macro,
+  /// i.e. it has its own `metaObject` method. Such classes can be used
+  /// as target types of `qobject_cast`.
+  pub fn has_q_object_macro(&self, class_name: &str) -> bool {
+    once(&self.current)
+      .chain(self.dependencies.iter().map(|d| *d))
+      .flat_map(|data| data.parser.methods.iter())
+      .any(|method| if let Some(ref info) = method.class_membership {
+             &info.class_type.name == class_name && method.name == "metaObject" &&
+             info.is_virtual
+           } else {
+             false
+           })
+  }
+
 
   //
   //  /// Returns true if C++ type `name` is polymorphic, i.e. has
@@ -962,6 +1208,21 @@ impl<'a> CppDataWithDeps<'a> {
         result.insert(tp.include_file.clone());
       }
     }
+    for typedef in &self.current.parser.typedefs {
+      if !result.contains(&typedef.include_file) {
+        result.insert(typedef.include_file.clone());
+      }
+    }
+    for variable in &self.current.parser.variables {
+      if !result.contains(&variable.include_file) {
+        result.insert(variable.include_file.clone());
+      }
+    }
+    for cpp_macro in &self.current.parser.macros {
+      if !result.contains(&cpp_macro.include_file) {
+        result.insert(cpp_macro.include_file.clone());
+      }
+    }
     for instantiations in &self.current.processed.template_instantiations {
       let type_info =
         self
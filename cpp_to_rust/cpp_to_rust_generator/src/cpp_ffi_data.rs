@@ -29,7 +29,6 @@ pub enum CppCast {
     is_direct: bool,
   },
   Dynamic,
-  #[allow(unused)]
   QObject,
 }
 
@@ -96,6 +95,10 @@ pub enum CppIndirectionChange {
   /// C++ argument is QFlags<T>
   /// and FFI argument is uint
   QFlagsToUInt,
+  /// C++ argument is `std::function<R(Args...)>`
+  /// and FFI argument is a closure struct
+  /// (layout of `cpp_utils::CppClosure`)
+  StdFunctionToClosure,
 }
 
 /// Information that indicates how an FFI function argument
@@ -112,6 +115,10 @@ pub enum CppFfiArgumentMeaning {
   /// This argument receives pointer to the buffer where
   /// the return value should be transferred to using placement new.
   ReturnValue,
+  /// This argument receives pointer to the buffer where
+  /// information about a caught C++ exception should be written
+  /// (see `cpp_utils::CppExceptionSlot`).
+  ExceptionSlot,
 }
 
 impl CppFfiArgumentMeaning {
@@ -381,6 +388,51 @@ pub struct QtSlotWrapper {
   pub receiver_id: String,
 }
 
+/// Information about generated C++ classes that allow to
+/// define Qt signals, slots and properties at runtime
+#[derive(Debug, Clone)]
+pub struct QtDynamicMetaObject {
+  /// Generated name of the class that builds a `QMetaObject`
+  pub builder_class_name: String,
+  /// Generated name of the `QObject` subclass that uses
+  /// a meta-object created by the builder class
+  pub object_class_name: String,
+  /// The function pointer type of the callback that receives
+  /// slot invocations and property accesses of the object
+  pub function_type: CppFunctionPointerType,
+}
+
+/// Information about a virtual method of a generated subclass
+/// that can be overridden by Rust code
+#[derive(Debug, Clone)]
+pub struct CppSubclassVirtualMethod {
+  /// The virtual method of the base class
+  pub cpp_method: CppMethod,
+  /// Identifier of the method, unique within the subclass.
+  /// It's used in names of the subclass's helper methods.
+  pub name: String,
+  /// Arguments of the method
+  pub arguments: Vec<CppFfiType>,
+  /// Return type of the method
+  pub return_type: CppFfiType,
+  /// The function pointer type of the callback that implements the method.
+  /// The callback receives the subclass's data pointer, the object
+  /// and the method's arguments.
+  pub function_type: CppFunctionPointerType,
+}
+
+/// Information about a generated C++ subclass that forwards
+/// calls of its virtual methods to callbacks
+#[derive(Debug, Clone)]
+pub struct CppSubclass {
+  /// Generated name of the subclass
+  pub class_name: String,
+  /// Name of the base class
+  pub base_class_name: String,
+  /// Virtual methods that can be overridden
+  pub virtual_methods: Vec<CppSubclassVirtualMethod>,
+}
+
 /// Information about a header of the generated C++ wrapper library
 #[derive(Debug, Clone)]
 pub struct CppFfiHeaderData {
@@ -390,4 +442,8 @@ pub struct CppFfiHeaderData {
   pub methods: Vec<CppAndFfiMethod>,
   /// Generated Qt slot wrappers
   pub qt_slot_wrappers: Vec<QtSlotWrapper>,
+  /// Generated subclasses
+  pub subclasses: Vec<CppSubclass>,
+  /// Generated classes for defining Qt meta-objects at runtime
+  pub qt_dynamic_meta_object: Option<QtDynamicMetaObject>,
 }
@@ -2,13 +2,16 @@ use caption_strategy::{TypeCaptionStrategy, MethodCaptionStrategy};
 use cpp_data::{CppVisibility, CppTypeAllocationPlace, CppDataWithDeps, CppTypeKind,
                CppTemplateInstantiation, CppOperator};
 use cpp_type::{CppTypeRole, CppType, CppTypeBase, CppTypeIndirection, CppTypeClassBase,
-               CppFunctionPointerType};
-use cpp_ffi_data::{CppAndFfiMethod, c_base_name, CppFfiHeaderData, QtSlotWrapper,
-                   CppFfiMethodKind, CppFieldAccessorType, CppMethodWithFfiSignature, CppCast};
+               CppFunctionPointerType, CppBuiltInNumericType};
+use cpp_ffi_data::{CppAndFfiMethod, c_base_name, CppFfiHeaderData, QtSlotWrapper, CppSubclass,
+                   CppSubclassVirtualMethod, QtDynamicMetaObject, CppFfiMethodKind,
+                   CppFieldAccessorType, CppMethodWithFfiSignature, CppCast,
+                   CppFfiMethodArgument, CppFfiType, CppIndirectionChange, CppFfiArgumentMeaning};
 use cpp_method::{CppMethod, CppMethodKind, CppMethodArgument, CppMethodClassMembership,
-                 ReturnValueAllocationPlace};
+                 ReturnValueAllocationPlace, CppExceptionSpecification};
 use common::errors::{Result, ChainErr, unexpected};
 use common::log;
+use common::target::Condition;
 use common::utils::{MapIfOk, add_to_multihash};
 use config::CppFfiGeneratorFilterFn;
 use std::collections::{HashSet, HashMap};
@@ -22,6 +25,12 @@ struct CppFfiGenerator<'a> {
   cpp_ffi_lib_name: String,
   /// FFI filters passed to `Config`
   filters: Vec<&'a Box<CppFfiGeneratorFilterFn>>,
+  /// If true, FFI functions for methods that may throw
+  /// receive an extra argument for catching C++ exceptions
+  catch_exceptions: bool,
+  /// Classes that should receive generated subclasses
+  /// (see `Config::add_cpp_subclass`)
+  subclasses: &'a [String],
 }
 
 #[derive(Debug, Clone)]
@@ -47,12 +56,16 @@ impl CppMethodWithKind {
 /// Runs the FFI generator
 pub fn run(cpp_data: &CppDataWithDeps,
            cpp_ffi_lib_name: String,
-           filters: Vec<&Box<CppFfiGeneratorFilterFn>>)
+           filters: Vec<&Box<CppFfiGeneratorFilterFn>>,
+           catch_exceptions: bool,
+           subclasses: &[String])
            -> Result<Vec<CppFfiHeaderData>> {
   let generator = CppFfiGenerator {
     cpp_data: cpp_data,
     cpp_ffi_lib_name: cpp_ffi_lib_name,
     filters: filters,
+    catch_exceptions: catch_exceptions,
+    subclasses: subclasses,
   };
 
   let mut c_headers = Vec::new();
@@ -66,6 +79,7 @@ pub fn run(cpp_data: &CppDataWithDeps,
   let mut extra_methods = Vec::new();
   extra_methods.append(&mut instantiate_templates(&generator.cpp_data)?);
   extra_methods.append(&mut generate_field_accessors(&generator.cpp_data)?);
+  extra_methods.append(&mut generate_variable_accessors(&generator.cpp_data)?);
   extra_methods.append(&mut generate_casts(&generator.cpp_data)?);
 
   for include_file in &include_name_list {
@@ -97,12 +111,20 @@ pub fn run(cpp_data: &CppDataWithDeps,
                        include_file_base_name: include_file_base_name,
                        methods: methods,
                        qt_slot_wrappers: Vec::new(),
+                       subclasses: Vec::new(),
+                       qt_dynamic_meta_object: None,
                      });
     }
   }
   if let Some(header) = generator.generate_slot_wrappers()? {
     c_headers.push(header);
   }
+  if let Some(header) = generator.generate_subclasses()? {
+    c_headers.push(header);
+  }
+  if let Some(header) = generator.generate_dynamic_meta_object()? {
+    c_headers.push(header);
+  }
   if c_headers.is_empty() {
     return Err("No FFI headers generated".into());
   }
@@ -191,19 +213,29 @@ fn instantiate_templates(data: &CppDataWithDeps) -> Result<Vec<CppMethodWithKind
         if let CppTypeBase::Class(CppTypeClassBase {
                                     ref name,
                                     ref template_arguments,
+                                    ref enclosing_template_class,
                                   }) = type1.base {
           if let Some(ref template_arguments) = *template_arguments {
             assert!(!template_arguments.is_empty());
             if template_arguments
                  .iter()
                  .all(|x| x.base.is_template_parameter()) {
-              if let Some(template_instantiations) =
+              let find_instantiations = |class_name: &String| {
                 data
                   .current
                   .processed
                   .template_instantiations
                   .iter()
-                  .find(|x| &x.class_name == name) {
+                  .find(|x| &x.class_name == class_name)
+              };
+              // types nested into a template class are instantiated
+              // along with the enclosing class
+              if let Some(template_instantiations) =
+                find_instantiations(name).or_else(|| {
+                                                    enclosing_template_class
+                                                      .as_ref()
+                                                      .and_then(|x| find_instantiations(x))
+                                                  }) {
                 let nested_level = if let CppTypeBase::TemplateParameter {
                          nested_level, ..
                        } = template_arguments[0].base {
@@ -269,7 +301,12 @@ fn generate_field_accessors(cpp_data: &CppDataWithDeps) -> Result<Vec<CppMethodW
   log::status("Adding field accessors");
   let mut new_methods = Vec::new();
   for type_info in &cpp_data.current.parser.types {
-    if let CppTypeKind::Class { ref fields, .. } = type_info.kind {
+    let fields = match type_info.kind {
+      CppTypeKind::Class { ref fields, .. } |
+      CppTypeKind::Union { ref fields } => Some(fields),
+      CppTypeKind::Enum { .. } => None,
+    };
+    if let Some(fields) = fields {
       for field in fields {
         let create_method =
           |name, accessor_type, return_type, arguments| -> Result<CppMethodWithKind> {
@@ -281,6 +318,7 @@ fn generate_field_accessors(cpp_data: &CppDataWithDeps) -> Result<Vec<CppMethodW
                                             kind: CppMethodKind::Regular,
                                             is_virtual: false,
                                             is_pure_virtual: false,
+                                            is_final: false,
                                             is_const: match accessor_type {
                                               CppFieldAccessorType::CopyGetter |
                                               CppFieldAccessorType::ConstRefGetter => true,
@@ -291,11 +329,17 @@ fn generate_field_accessors(cpp_data: &CppDataWithDeps) -> Result<Vec<CppMethodW
                                             visibility: CppVisibility::Public,
                                             is_signal: false,
                                             is_slot: false,
+                                            is_explicit: false,
+                                            is_deleted: false,
+                                            is_defaulted: false,
                                           }),
                    operator: None,
                    return_type: return_type,
                    arguments: arguments,
                    allows_variadic_arguments: false,
+                   exception_specification: CppExceptionSpecification::NoThrow,
+                   deprecation: None,
+                   condition: type_info.condition.clone(),
                    include_file: type_info.include_file.clone(),
                    origin_location: None,
                    template_arguments: None,
@@ -352,14 +396,124 @@ fn generate_field_accessors(cpp_data: &CppDataWithDeps) -> Result<Vec<CppMethodW
   Ok(new_methods)
 }
 
+/// Adds fictional getter and setter functions for each global variable
+/// and static data member. Constants with known values are exposed
+/// as Rust constants instead and don't need accessors.
+fn generate_variable_accessors(cpp_data: &CppDataWithDeps) -> Result<Vec<CppMethodWithKind>> {
+  log::status("Adding variable accessors");
+  let mut new_methods = Vec::new();
+  for variable in &cpp_data.current.parser.variables {
+    if variable.value.is_some() {
+      continue;
+    }
+    let create_method = |name: String, accessor_type, return_type, arguments| {
+      let (name, class_membership, field_name) = match variable.class_type {
+        Some(ref class_type) => {
+          (name,
+           Some(CppMethodClassMembership {
+                  class_type: class_type.clone(),
+                  kind: CppMethodKind::Regular,
+                  is_virtual: false,
+                  is_pure_virtual: false,
+                  is_final: false,
+                  is_const: false,
+                  is_static: true,
+                  visibility: CppVisibility::Public,
+                  is_signal: false,
+                  is_slot: false,
+                  is_explicit: false,
+                  is_deleted: false,
+                  is_defaulted: false,
+                }),
+           variable.short_name().to_string())
+        }
+        None => {
+          let full_name = match variable.name.rfind("::") {
+            Some(index) => format!("{}::{}", &variable.name[0..index], name),
+            None => name,
+          };
+          (full_name, None, variable.name.clone())
+        }
+      };
+      CppMethodWithKind {
+        method: CppMethod {
+          name: name,
+          class_membership: class_membership,
+          operator: None,
+          return_type: return_type,
+          arguments: arguments,
+          allows_variadic_arguments: false,
+          exception_specification: CppExceptionSpecification::NoThrow,
+          deprecation: None,
+          condition: None,
+          include_file: variable.include_file.clone(),
+          origin_location: None,
+          template_arguments: None,
+          template_arguments_values: None,
+          declaration_code: None,
+          doc: None,
+          inheritance_chain: Vec::new(),
+          is_ffi_whitelisted: false,
+        },
+        kind: CppFfiMethodKind::FieldAccessor {
+          accessor_type: accessor_type,
+          field_name: field_name,
+        },
+      }
+    };
+    let short_name = variable.short_name().to_string();
+    let variable_type = &variable.variable_type;
+    if variable_type.indirection == CppTypeIndirection::None && variable_type.base.is_class() {
+      let mut type2_const = variable_type.clone();
+      type2_const.is_const = true;
+      type2_const.indirection = CppTypeIndirection::Ref;
+      new_methods.push(create_method(short_name.clone(),
+                                     CppFieldAccessorType::ConstRefGetter,
+                                     type2_const,
+                                     Vec::new()));
+      if !variable.is_const {
+        let mut type2_mut = variable_type.clone();
+        type2_mut.is_const = false;
+        type2_mut.indirection = CppTypeIndirection::Ref;
+        new_methods.push(create_method(format!("{}_mut", short_name),
+                                       CppFieldAccessorType::MutRefGetter,
+                                       type2_mut,
+                                       Vec::new()));
+      }
+    } else {
+      let mut return_type = variable_type.clone();
+      if return_type.indirection == CppTypeIndirection::None {
+        return_type.is_const = false;
+      }
+      new_methods.push(create_method(short_name.clone(),
+                                     CppFieldAccessorType::CopyGetter,
+                                     return_type,
+                                     Vec::new()));
+    }
+    if !variable.is_const {
+      let arg = CppMethodArgument {
+        argument_type: variable_type.clone(),
+        name: "value".to_string(),
+        has_default_value: false,
+      };
+      new_methods.push(create_method(format!("set_{}", short_name),
+                                     CppFieldAccessorType::Setter,
+                                     CppType::void(),
+                                     vec![arg]));
+    }
+  }
+  Ok(new_methods)
+}
+
 /// Convenience function to create `CppMethod` object for
-/// `static_cast` or `dynamic_cast` from type `from` to type `to`.
+/// `static_cast`, `dynamic_cast` or `qobject_cast` from type `from` to type `to`.
 /// See `CppMethod`'s documentation for more information
 /// about `is_unsafe_static_cast` and `is_direct_static_cast`.
 fn create_cast_method(cast: CppCast,
                       from: &CppType,
                       to: &CppType,
-                      include_file: &str)
+                      include_file: &str,
+                      condition: Option<Condition>)
                       -> CppMethodWithKind {
   CppMethodWithKind {
     method: CppMethod {
@@ -373,6 +527,9 @@ fn create_cast_method(cast: CppCast,
                         has_default_value: false,
                       }],
       allows_variadic_arguments: false,
+      exception_specification: CppExceptionSpecification::NoThrow,
+      deprecation: None,
+      condition: condition,
       include_file: include_file.to_string(),
       origin_location: None,
       template_arguments: None,
@@ -387,6 +544,100 @@ fn create_cast_method(cast: CppCast,
 }
 
 
+/// Creates a method of generated class `class_type` that is
+/// exposed through FFI.
+fn generated_class_method(class_type: &CppTypeClassBase,
+                          name: String,
+                          kind: CppMethodKind,
+                          is_const: bool,
+                          arguments: Vec<CppMethodArgument>,
+                          return_type: CppType,
+                          include_file: &str)
+                          -> CppMethodWithKind {
+  CppMethodWithKind {
+    method: CppMethod {
+      name: name,
+      class_membership: Some(CppMethodClassMembership {
+                               class_type: class_type.clone(),
+                               is_virtual: false,
+                               is_pure_virtual: false,
+                               is_final: false,
+                               is_const: is_const,
+                               is_static: false,
+                               visibility: CppVisibility::Public,
+                               is_signal: false,
+                               is_slot: false,
+                               is_explicit: false,
+                               is_deleted: false,
+                               is_defaulted: false,
+                               kind: kind,
+                             }),
+      operator: None,
+      return_type: return_type,
+      arguments: arguments,
+      allows_variadic_arguments: false,
+      exception_specification: CppExceptionSpecification::NoThrow,
+      deprecation: None,
+      condition: None,
+      include_file: include_file.to_string(),
+      origin_location: None,
+      template_arguments: None,
+      template_arguments_values: None,
+      declaration_code: None,
+      doc: None,
+      inheritance_chain: Vec::new(),
+      is_ffi_whitelisted: false,
+    },
+    kind: CppFfiMethodKind::Real,
+  }
+}
+
+/// Returns FFI types of arguments and return type of `method`
+/// if its calls can be forwarded to a callback by a generated subclass.
+fn virtual_method_ffi_types(method: &CppMethod) -> Result<(Vec<CppFfiType>, CppFfiType)> {
+  if let Some(ref info) = method.class_membership {
+    if info.visibility == CppVisibility::Private {
+      return Err("private methods are not supported".into());
+    }
+  } else {
+    return Err(unexpected("virtual method must be a class member").into());
+  }
+  if method.operator.is_some() {
+    return Err("operators are not supported".into());
+  }
+  if method.allows_variadic_arguments {
+    return Err("variadic arguments are not supported".into());
+  }
+  if method.template_arguments.is_some() {
+    return Err("template methods are not supported".into());
+  }
+  if method
+       .all_involved_types()
+       .iter()
+       .any(|x| x.base.is_or_contains_template_parameter()) {
+    return Err("template parameters are not supported".into());
+  }
+  let arguments = method
+    .arguments
+    .iter()
+    .map_if_ok(|arg| arg.argument_type.to_cpp_ffi_type(CppTypeRole::NotReturnType))?;
+  let return_type = method
+    .return_type
+    .to_cpp_ffi_type(CppTypeRole::ReturnType)?;
+  for type1 in arguments.iter().chain(once(&return_type)) {
+    if type1.conversion == CppIndirectionChange::StdFunctionToClosure {
+      return Err("std::function types are not supported".into());
+    }
+  }
+  if let CppTypeBase::FunctionPointer(..) = return_type.original_type.base {
+    return Err("function pointer return types are not supported".into());
+  }
+  if return_type.conversion == CppIndirectionChange::QFlagsToUInt &&
+     return_type.original_type.indirection != CppTypeIndirection::None {
+    return Err("QFlags references are not supported as return types".into());
+  }
+  Ok((arguments, return_type))
+}
 
 /// Performs a portion of `generate_casts` operation.
 /// Adds casts between `target_type` and `base_type` and calls
@@ -419,20 +670,31 @@ fn generate_casts_one(cpp_data: &CppDataWithDeps,
                                       },
                                       &base_ptr_type,
                                       &target_ptr_type,
-                                      &type_info.include_file));
+                                      &type_info.include_file,
+                                      type_info.condition.clone()));
   new_methods.push(create_cast_method(CppCast::Static {
                                         is_unsafe: false,
                                         is_direct: is_direct,
                                       },
                                       &target_ptr_type,
                                       &base_ptr_type,
-                                      &type_info.include_file));
+                                      &type_info.include_file,
+                                      type_info.condition.clone()));
   if let CppTypeBase::Class(ref base) = base_type.base {
     if cpp_data.has_virtual_methods(&base.name) {
       new_methods.push(create_cast_method(CppCast::Dynamic,
                                           &base_ptr_type,
                                           &target_ptr_type,
-                                          &type_info.include_file));
+                                          &type_info.include_file,
+                                          type_info.condition.clone()));
+    }
+    if (base.name == "QObject" || cpp_data.inherits(&base.name, "QObject")) &&
+       cpp_data.has_q_object_macro(&target_type.name) {
+      new_methods.push(create_cast_method(CppCast::QObject,
+                                          &base_ptr_type,
+                                          &target_ptr_type,
+                                          &type_info.include_file,
+                                          type_info.condition.clone()));
     }
   }
 
@@ -451,8 +713,8 @@ fn generate_casts_one(cpp_data: &CppDataWithDeps,
   Ok(new_methods)
 }
 
-/// Adds `static_cast` and `dynamic_cast` functions for all appropriate pairs of types
-/// in this `CppData`.
+/// Adds `static_cast`, `dynamic_cast` and `qobject_cast` functions for all
+/// appropriate pairs of types in this `CppData`.
 fn generate_casts(cpp_data: &CppDataWithDeps) -> Result<Vec<CppMethodWithKind>> {
   log::status("Adding cast functions");
   let mut new_methods = Vec::new();
@@ -472,7 +734,8 @@ fn generate_casts(cpp_data: &CppDataWithDeps) -> Result<Vec<CppMethodWithKind>>
 /// Generates the FFI function signature for this method.
 fn method_to_ffi_signature<'a>(method: CppMethodRefWithKind<'a>,
                                cpp_data: &CppDataWithDeps,
-                               type_allocation_places_override: Option<CppTypeAllocationPlace>)
+                               type_allocation_places_override: Option<CppTypeAllocationPlace>,
+                               catch_exceptions: bool)
                                -> Result<CppMethodWithFfiSignature> {
   let get_place = |name| -> Result<ReturnValueAllocationPlace> {
     let v = if let Some(ref x) = type_allocation_places_override {
@@ -506,7 +769,30 @@ fn method_to_ffi_signature<'a>(method: CppMethodRefWithKind<'a>,
     ReturnValueAllocationPlace::NotApplicable
   };
 
-  let c_signature = method.method.c_signature(place.clone())?;
+  let mut c_signature = method.method.c_signature(place.clone())?;
+  if catch_exceptions && method.method.may_throw() {
+    c_signature
+      .arguments
+      .push(CppFfiMethodArgument {
+              name: "exception_slot".to_string(),
+              argument_type: CppFfiType {
+                original_type: CppType {
+                  base: CppTypeBase::Void,
+                  indirection: CppTypeIndirection::Ptr,
+                  is_const: false,
+                  is_const2: false,
+                },
+                ffi_type: CppType {
+                  base: CppTypeBase::Void,
+                  indirection: CppTypeIndirection::Ptr,
+                  is_const: false,
+                  is_const2: false,
+                },
+                conversion: CppIndirectionChange::NoChange,
+              },
+              meaning: CppFfiArgumentMeaning::ExceptionSlot,
+            });
+  }
   Ok(CppMethodWithFfiSignature {
        cpp_method: method.method.clone(),
        kind: method.kind,
@@ -553,6 +839,11 @@ impl<'a> CppFfiGenerator<'a> {
       if membership.is_signal {
         return Ok(false);
       }
+      if membership.is_deleted {
+        log::llog(log::DebugFfiSkips,
+                  || format!("Skipping deleted method: \n{}\n", method.short_text()));
+        return Ok(false);
+      }
     }
     if method.template_arguments.is_some() {
       return Ok(false);
@@ -587,7 +878,8 @@ impl<'a> CppFfiGenerator<'a> {
       let mut process_one = |method: CppMethodRefWithKind| {
         match method_to_ffi_signature(method.clone(),
                                       &self.cpp_data,
-                                      type_allocation_places_override.clone()) {
+                                      type_allocation_places_override.clone(),
+                                      self.catch_exceptions) {
           Err(msg) => {
             log::llog(log::DebugFfiSkips, || {
               format!("Unable to produce C function for method:\n{}\nError:{}\n",
@@ -753,20 +1045,28 @@ impl<'a> CppFfiGenerator<'a> {
                                      class_type: CppTypeClassBase {
                                        name: class_name.clone(),
                                        template_arguments: None,
+                                       enclosing_template_class: None,
                                      },
                                      is_virtual: true,
                                      is_pure_virtual: false,
+                                     is_final: false,
                                      is_const: false,
                                      is_static: false,
                                      visibility: CppVisibility::Public,
                                      is_signal: false,
                                      is_slot: is_slot,
+                                     is_explicit: false,
+                                     is_deleted: false,
+                                     is_defaulted: false,
                                      kind: kind,
                                    }),
             operator: None,
             return_type: CppType::void(),
             arguments: arguments,
             allows_variadic_arguments: false,
+            exception_specification: CppExceptionSpecification::NoThrow,
+            deprecation: None,
+            condition: None,
             include_file: include_file_name.to_string(),
             origin_location: None,
             template_arguments: None,
@@ -807,6 +1107,25 @@ impl<'a> CppFfiGenerator<'a> {
                                    "set".to_string(),
                                    false,
                                    method_set_args));
+      let deleter_type = CppFunctionPointerType {
+        return_type: Box::new(CppType::void()),
+        arguments: vec![void_ptr.clone()],
+        allows_variadic_arguments: false,
+      };
+      let method_set_deleter_args = vec![CppMethodArgument {
+                                           name: "deleter".to_string(),
+                                           argument_type: CppType {
+                                             base: CppTypeBase::FunctionPointer(deleter_type),
+                                             indirection: CppTypeIndirection::None,
+                                             is_const: false,
+                                             is_const2: false,
+                                           },
+                                           has_default_value: false,
+                                         }];
+      methods.push(create_function(CppMethodKind::Regular,
+                                   "set_deleter".to_string(),
+                                   false,
+                                   method_set_deleter_args));
 
       let method_custom_slot = create_function(CppMethodKind::Regular,
                                                "custom_slot".to_string(),
@@ -834,6 +1153,7 @@ impl<'a> CppFfiGenerator<'a> {
         base: CppTypeBase::Class(CppTypeClassBase {
                                    name: class_name.clone(),
                                    template_arguments: None,
+                                   enclosing_template_class: None,
                                  }),
         indirection: CppTypeIndirection::Ptr,
         is_const: false,
@@ -843,6 +1163,7 @@ impl<'a> CppFfiGenerator<'a> {
         base: CppTypeBase::Class(CppTypeClassBase {
                                    name: "QObject".to_string(),
                                    template_arguments: None,
+                                   enclosing_template_class: None,
                                  }),
         indirection: CppTypeIndirection::Ptr,
         is_const: false,
@@ -854,7 +1175,8 @@ impl<'a> CppFfiGenerator<'a> {
                                       },
                                       &cast_from,
                                       &cast_to,
-                                      include_file_name));
+                                      include_file_name,
+                                      None));
     }
     Ok(Some(CppFfiHeaderData {
               include_file_base_name: include_file_name.to_string(),
@@ -863,6 +1185,476 @@ impl<'a> CppFfiGenerator<'a> {
                                  Some(CppTypeAllocationPlace::Heap),
                                  methods.iter().map(|i| i.as_ref()))?,
               qt_slot_wrappers: qt_slot_wrappers,
+              subclasses: Vec::new(),
+              qt_dynamic_meta_object: None,
+            }))
+  }
+
+  /// Generates subclasses for classes added with `Config::add_cpp_subclass`.
+  fn generate_subclasses(&'a self) -> Result<Option<CppFfiHeaderData>> {
+    let include_file_name = "subclasses";
+    if self.subclasses.is_empty() {
+      return Ok(None);
+    }
+    let mut subclasses = Vec::new();
+    let mut methods = Vec::new();
+    for base_class_name in self.subclasses {
+      let (subclass, mut subclass_methods) =
+        self
+          .generate_subclass(base_class_name, include_file_name)
+          .chain_err(|| format!("failed to generate subclass of {}", base_class_name))?;
+      subclasses.push(subclass);
+      methods.append(&mut subclass_methods);
+    }
+    Ok(Some(CppFfiHeaderData {
+              include_file_base_name: include_file_name.to_string(),
+              methods: self
+                .process_methods(include_file_name,
+                                 Some(CppTypeAllocationPlace::Heap),
+                                 methods.iter().map(|i| i.as_ref()))?,
+              qt_slot_wrappers: Vec::new(),
+              subclasses: subclasses,
+              qt_dynamic_meta_object: None,
+            }))
+  }
+
+  /// Generates classes that allow to define Qt signals, slots and properties
+  /// at runtime. The classes are only generated for the library
+  /// that contains `QObject` class.
+  fn generate_dynamic_meta_object(&'a self) -> Result<Option<CppFfiHeaderData>> {
+    let include_file_name = "dynamic_meta_object";
+    let has_qobject = self
+      .cpp_data
+      .current
+      .parser
+      .types
+      .iter()
+      .any(|t| t.name == "QObject");
+    if !has_qobject {
+      return Ok(None);
+    }
+    let builder_class_name = format!("{}_DynamicMetaObject", self.cpp_ffi_lib_name);
+    let object_class_name = format!("{}_DynamicObject", self.cpp_ffi_lib_name);
+    let class_type = |name: &str, indirection: CppTypeIndirection, is_const: bool| {
+      CppType {
+        base: CppTypeBase::Class(CppTypeClassBase {
+                                   name: name.to_string(),
+                                   template_arguments: None,
+                                   enclosing_template_class: None,
+                                 }),
+        indirection: indirection,
+        is_const: is_const,
+        is_const2: false,
+      }
+    };
+    let simple_type = |base: CppTypeBase, indirection: CppTypeIndirection, is_const: bool| {
+      CppType {
+        base: base,
+        indirection: indirection,
+        is_const: is_const,
+        is_const2: false,
+      }
+    };
+    let int = simple_type(CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+                          CppTypeIndirection::None,
+                          false);
+    let bool_type = simple_type(CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool),
+                                CppTypeIndirection::None,
+                                false);
+    let c_string = simple_type(CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Char),
+                               CppTypeIndirection::Ptr,
+                               true);
+    let void_ptr = simple_type(CppTypeBase::Void, CppTypeIndirection::Ptr, false);
+    let void_ptr_ptr = simple_type(CppTypeBase::Void, CppTypeIndirection::PtrPtr, false);
+    let argument = |name: &str, argument_type: &CppType| {
+      CppMethodArgument {
+        name: name.to_string(),
+        argument_type: argument_type.clone(),
+        has_default_value: false,
+      }
+    };
+    let function_type = CppFunctionPointerType {
+      return_type: Box::new(CppType::void()),
+      arguments: vec![void_ptr.clone(),
+                      class_type(&object_class_name, CppTypeIndirection::Ptr, false),
+                      int.clone(),
+                      int.clone(),
+                      int.clone(),
+                      void_ptr_ptr.clone()],
+      allows_variadic_arguments: false,
+    };
+    let deleter_type = CppFunctionPointerType {
+      return_type: Box::new(CppType::void()),
+      arguments: vec![void_ptr.clone()],
+      allows_variadic_arguments: false,
+    };
+
+    let mut methods = Vec::new();
+    let builder_type = CppTypeClassBase {
+      name: builder_class_name.clone(),
+      template_arguments: None,
+      enclosing_template_class: None,
+    };
+    let builder_method = |name: &str,
+                          kind: CppMethodKind,
+                          is_const: bool,
+                          arguments: Vec<CppMethodArgument>,
+                          return_type: &CppType| {
+      generated_class_method(&builder_type,
+                             name.to_string(),
+                             kind,
+                             is_const,
+                             arguments,
+                             return_type.clone(),
+                             include_file_name)
+    };
+    methods.push(builder_method(&builder_class_name,
+                                CppMethodKind::Constructor,
+                                false,
+                                vec![argument("class_name", &c_string)],
+                                &CppType::void()));
+    methods.push(builder_method(&format!("~{}", builder_class_name),
+                                CppMethodKind::Destructor,
+                                false,
+                                Vec::new(),
+                                &CppType::void()));
+    for name in &["add_signal", "add_slot"] {
+      methods.push(builder_method(name,
+                                  CppMethodKind::Regular,
+                                  false,
+                                  vec![argument("signature", &c_string)],
+                                  &int));
+    }
+    methods.push(builder_method("add_property",
+                                CppMethodKind::Regular,
+                                false,
+                                vec![argument("name", &c_string),
+                                     argument("type_name", &c_string),
+                                     argument("is_writable", &bool_type),
+                                     argument("notify_signal", &int)],
+                                &int));
+    for name in &["signal_count", "slot_count", "property_count"] {
+      methods.push(builder_method(name, CppMethodKind::Regular, true, Vec::new(), &int));
+    }
+    methods.push(builder_method("finalize",
+                                CppMethodKind::Regular,
+                                false,
+                                Vec::new(),
+                                &CppType::void()));
+    methods.push(builder_method("is_finalized",
+                                CppMethodKind::Regular,
+                                true,
+                                Vec::new(),
+                                &bool_type));
+    methods.push(builder_method("meta_object",
+                                CppMethodKind::Regular,
+                                true,
+                                Vec::new(),
+                                &class_type("QMetaObject", CppTypeIndirection::Ptr, true)));
+
+    let object_type = CppTypeClassBase {
+      name: object_class_name.clone(),
+      template_arguments: None,
+      enclosing_template_class: None,
+    };
+    let object_method = |name: &str, kind: CppMethodKind, arguments: Vec<CppMethodArgument>| {
+      generated_class_method(&object_type,
+                             name.to_string(),
+                             kind,
+                             false,
+                             arguments,
+                             CppType::void(),
+                             include_file_name)
+    };
+    methods.push(object_method(&object_class_name,
+                               CppMethodKind::Constructor,
+                               vec![argument("meta_object",
+                                             &class_type(&builder_class_name,
+                                                         CppTypeIndirection::Ref,
+                                                         true))]));
+    methods.push(object_method(&format!("~{}", object_class_name),
+                               CppMethodKind::Destructor,
+                               Vec::new()));
+    methods.push(object_method("set_handler",
+                               CppMethodKind::Regular,
+                               vec![argument("callback",
+                                             &simple_type(CppTypeBase::FunctionPointer(function_type.clone()),
+                                                          CppTypeIndirection::None,
+                                                          false)),
+                                    argument("data", &void_ptr),
+                                    argument("deleter",
+                                             &simple_type(CppTypeBase::FunctionPointer(deleter_type),
+                                                          CppTypeIndirection::None,
+                                                          false))]));
+    methods.push(object_method("emit_signal",
+                               CppMethodKind::Regular,
+                               vec![argument("signal", &int), argument("args", &void_ptr_ptr)]));
+    methods.push(create_cast_method(CppCast::Static {
+                                      is_unsafe: false,
+                                      is_direct: true,
+                                    },
+                                    &class_type(&object_class_name, CppTypeIndirection::Ptr, false),
+                                    &class_type("QObject", CppTypeIndirection::Ptr, false),
+                                    include_file_name,
+                                    None));
+    Ok(Some(CppFfiHeaderData {
+              include_file_base_name: include_file_name.to_string(),
+              methods: self
+                .process_methods(include_file_name,
+                                 Some(CppTypeAllocationPlace::Heap),
+                                 methods.iter().map(|i| i.as_ref()))?,
+              qt_slot_wrappers: Vec::new(),
+              subclasses: Vec::new(),
+              qt_dynamic_meta_object: Some(QtDynamicMetaObject {
+                                             builder_class_name: builder_class_name,
+                                             object_class_name: object_class_name,
+                                             function_type: function_type,
+                                           }),
             }))
   }
+
+  /// Generates a subclass of `base_class_name` that forwards calls of its
+  /// virtual methods to callbacks. Returns information about the subclass
+  /// and its methods exposed through FFI: constructors of the base class,
+  /// the destructor, setters of the callbacks and their data pointer,
+  /// methods calling base class implementations of the virtual methods
+  /// and the cast to the base class.
+  fn generate_subclass(&self,
+                       base_class_name: &str,
+                       include_file_name: &str)
+                       -> Result<(CppSubclass, Vec<CppMethodWithKind>)> {
+    let type_info = self
+      .cpp_data
+      .find_type_info(|t| &t.name == base_class_name)
+      .chain_err(|| "class not found")?;
+    match type_info.kind {
+      CppTypeKind::Class { ref template_arguments, .. } => {
+        if template_arguments.is_some() {
+          return Err("template classes are not supported".into());
+        }
+      }
+      _ => return Err("not a class".into()),
+    }
+    if type_info.condition.is_some() {
+      return Err("platform-specific classes are not supported".into());
+    }
+    let class_name = format!("{}_Subclass_{}",
+                             self.cpp_ffi_lib_name,
+                             base_class_name.replace("::", "_"));
+    let class_type = CppTypeClassBase {
+      name: class_name.clone(),
+      template_arguments: None,
+      enclosing_template_class: None,
+    };
+    let void_ptr = CppType {
+      base: CppTypeBase::Void,
+      indirection: CppTypeIndirection::Ptr,
+      is_const: false,
+      is_const2: false,
+    };
+    let function_pointer = |function_type: CppFunctionPointerType| {
+      CppType {
+        base: CppTypeBase::FunctionPointer(function_type),
+        indirection: CppTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
+      }
+    };
+    let mut methods = Vec::new();
+
+    let base_methods = once(&self.cpp_data.current)
+      .chain(self.cpp_data.dependencies.iter().map(|d| *d))
+      .flat_map(|data| data.parser.methods.iter());
+    for method in base_methods {
+      if let Some(ref info) = method.class_membership {
+        if &info.class_type.name != base_class_name || !info.kind.is_constructor() ||
+           info.visibility != CppVisibility::Public || info.is_deleted {
+          continue;
+        }
+      } else {
+        continue;
+      }
+      let is_copy_or_move_constructor = method.arguments.len() == 1 &&
+                                        match method.arguments[0].argument_type.base {
+                                          CppTypeBase::Class(ref t) => &t.name == base_class_name,
+                                          _ => false,
+                                        };
+      if is_copy_or_move_constructor {
+        continue;
+      }
+      let mut constructor = generated_class_method(&class_type,
+                                                   class_name.clone(),
+                                                   CppMethodKind::Constructor,
+                                                   false,
+                                                   method.arguments.clone(),
+                                                   CppType::void(),
+                                                   include_file_name);
+      constructor.method.exception_specification = method.exception_specification.clone();
+      methods.push(constructor);
+    }
+    methods.push(generated_class_method(&class_type,
+                                        format!("~{}", class_name),
+                                        CppMethodKind::Destructor,
+                                        false,
+                                        Vec::new(),
+                                        CppType::void(),
+                                        include_file_name));
+    methods.push(generated_class_method(&class_type,
+                                        "set_virtuals_data".to_string(),
+                                        CppMethodKind::Regular,
+                                        false,
+                                        vec![CppMethodArgument {
+                                               name: "data".to_string(),
+                                               argument_type: void_ptr.clone(),
+                                               has_default_value: false,
+                                             }],
+                                        CppType::void(),
+                                        include_file_name));
+    let deleter_type = CppFunctionPointerType {
+      return_type: Box::new(CppType::void()),
+      arguments: vec![void_ptr.clone()],
+      allows_variadic_arguments: false,
+    };
+    methods.push(generated_class_method(&class_type,
+                                        "set_virtuals_deleter".to_string(),
+                                        CppMethodKind::Regular,
+                                        false,
+                                        vec![CppMethodArgument {
+                                               name: "deleter".to_string(),
+                                               argument_type: function_pointer(deleter_type),
+                                               has_default_value: false,
+                                             }],
+                                        CppType::void(),
+                                        include_file_name));
+
+    let mut overridable_methods = Vec::new();
+    for method in self.cpp_data.virtual_methods(base_class_name) {
+      if method
+           .class_membership
+           .as_ref()
+           .map_or(false, |info| info.is_final) {
+        log::llog(log::DebugFfiSkips,
+                  || format!("Can't override final method: {}", method.short_text()));
+        continue;
+      }
+      match virtual_method_ffi_types(method) {
+        Ok((arguments, return_type)) => overridable_methods.push((method, arguments, return_type)),
+        Err(err) => {
+          if method
+               .class_membership
+               .as_ref()
+               .map_or(false, |info| info.is_pure_virtual) {
+            return Err(err).chain_err(|| {
+                                        format!("can't override pure virtual method: {}",
+                                                method.short_text())
+                                      });
+          }
+          log::llog(log::DebugFfiSkips, || {
+            format!("Can't override virtual method: {}: {}",
+                    method.short_text(),
+                    err)
+          });
+        }
+      }
+    }
+    let mut virtual_methods = Vec::new();
+    let mut name_counters: HashMap<&str, usize> = HashMap::new();
+    for &(method, ref arguments, ref return_type) in &overridable_methods {
+      let info = method
+        .class_membership
+        .as_ref()
+        .chain_err(|| unexpected("virtual method must be a class member"))?;
+      // overloaded virtual methods receive numbered names
+      let name = if overridable_methods
+           .iter()
+           .filter(|x| x.0.name == method.name)
+           .count() > 1 {
+        let counter = name_counters.entry(&method.name).or_insert(0);
+        *counter += 1;
+        format!("{}_{}", method.name, counter)
+      } else {
+        method.name.clone()
+      };
+      let this_type = CppType {
+        base: CppTypeBase::Class(class_type.clone()),
+        indirection: CppTypeIndirection::Ptr,
+        is_const: info.is_const,
+        is_const2: false,
+      };
+      let function_type = CppFunctionPointerType {
+        return_type: Box::new(return_type.ffi_type.clone()),
+        arguments: once(void_ptr.clone())
+          .chain(once(this_type))
+          .chain(arguments.iter().map(|t| t.ffi_type.clone()))
+          .collect(),
+        allows_variadic_arguments: false,
+      };
+      methods.push(generated_class_method(&class_type,
+                                          format!("set_{}_callback", name),
+                                          CppMethodKind::Regular,
+                                          false,
+                                          vec![CppMethodArgument {
+                                                 name: "callback".to_string(),
+                                                 argument_type:
+                                                   function_pointer(function_type.clone()),
+                                                 has_default_value: false,
+                                               }],
+                                          CppType::void(),
+                                          include_file_name));
+      if !info.is_pure_virtual {
+        let mut base_method = generated_class_method(&class_type,
+                                                     format!("{}_base", name),
+                                                     CppMethodKind::Regular,
+                                                     info.is_const,
+                                                     method
+                                                       .arguments
+                                                       .iter()
+                                                       .map(|arg| {
+                                                              CppMethodArgument {
+                                                                has_default_value: false,
+                                                                ..arg.clone()
+                                                              }
+                                                            })
+                                                       .collect(),
+                                                     method.return_type.clone(),
+                                                     include_file_name);
+        base_method.method.exception_specification = method.exception_specification.clone();
+        methods.push(base_method);
+      }
+      virtual_methods.push(CppSubclassVirtualMethod {
+                             cpp_method: method.clone(),
+                             name: name,
+                             arguments: arguments.clone(),
+                             return_type: return_type.clone(),
+                             function_type: function_type,
+                           });
+    }
+
+    let cast_from = CppType {
+      base: CppTypeBase::Class(class_type.clone()),
+      indirection: CppTypeIndirection::Ptr,
+      is_const: false,
+      is_const2: false,
+    };
+    let cast_to = CppType {
+      base: CppTypeBase::Class(type_info.default_class_type()?),
+      indirection: CppTypeIndirection::Ptr,
+      is_const: false,
+      is_const2: false,
+    };
+    methods.push(create_cast_method(CppCast::Static {
+                                      is_unsafe: false,
+                                      is_direct: true,
+                                    },
+                                    &cast_from,
+                                    &cast_to,
+                                    include_file_name,
+                                    None));
+    Ok((CppSubclass {
+          class_name: class_name,
+          base_class_name: base_class_name.to_string(),
+          virtual_methods: virtual_methods,
+        },
+        methods))
+  }
 }
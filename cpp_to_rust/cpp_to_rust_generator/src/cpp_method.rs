@@ -1,10 +1,12 @@
 //! Types for handling information about C++ methods.
 
 
-use cpp_data::{CppVisibility, CppOriginLocation, TemplateArgumentsDeclaration, CppBaseSpecifier};
+use cpp_data::{CppVisibility, CppOriginLocation, TemplateArgumentsDeclaration, CppBaseSpecifier,
+               CppDeprecation};
 use cpp_ffi_data::{CppFfiType, CppFfiMethodSignature, CppFfiMethodArgument, CppFfiArgumentMeaning};
 use cpp_type::{CppType, CppTypeIndirection, CppTypeRole, CppTypeBase, CppTypeClassBase};
 use common::errors::{Result, unexpected};
+use common::target::Condition;
 use common::string_utils::JoinWithSeparator;
 use common::utils::MapIfOk;
 
@@ -71,6 +73,8 @@ pub struct CppMethodClassMembership {
   pub is_virtual: bool,
   /// True if this is a pure virtual method (requires is_virtual = true)
   pub is_pure_virtual: bool,
+  /// True if this is a virtual method declared `final`
+  pub is_final: bool,
   /// True if this is a const method, i.e. "this" pointer receives by
   /// this method has const type
   pub is_const: bool,
@@ -82,6 +86,13 @@ pub struct CppMethodClassMembership {
   pub is_signal: bool,
   /// True if the method is a Qt slot
   pub is_slot: bool,
+  /// True if this is a constructor or a conversion operator
+  /// declared with `explicit` specifier
+  pub is_explicit: bool,
+  /// True if this method is explicitly deleted (`= delete`)
+  pub is_deleted: bool,
+  /// True if this method is explicitly defaulted (`= default`)
+  pub is_defaulted: bool,
 
   // / If this method is a generated field accessor, this field contains
   // / information about it. Field accessors do not have real C++ methods corresponding to them.
@@ -107,6 +118,19 @@ pub struct CppMethodDoc {
   pub cross_references: Vec<String>,
 }
 
+/// Exception specification of a C++ function
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Serialize, Deserialize)]
+pub enum CppExceptionSpecification {
+  /// No exception specification. The function may throw any exception.
+  None,
+  /// `noexcept`, `noexcept(true)` or `throw()`. The function never throws.
+  NoThrow,
+  /// Dynamic exception specification (`throw(T1, T2)`).
+  /// The function may only throw exceptions of listed types.
+  Dynamic(Vec<String>),
+}
+
 /// Information about a C++ method
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[derive(Serialize, Deserialize)]
@@ -130,6 +154,8 @@ pub struct CppMethod {
   //  pub arguments_before_omitting: Option<Vec<CppMethodArgument>>,
   /// Whether the argument list is terminated with "..."
   pub allows_variadic_arguments: bool,
+  /// Exception specification (`noexcept` or `throw(...)`) of the method
+  pub exception_specification: CppExceptionSpecification,
   /// File name of the include file where the method is defined
   /// (without full path)
   pub include_file: String,
@@ -159,6 +185,11 @@ pub struct CppMethod {
   //pub is_fake_inherited_method: bool,
   /// C++ documentation data for this method
   pub doc: Option<CppMethodDoc>,
+  /// Deprecation information, if the method is deprecated
+  pub deprecation: Option<CppDeprecation>,
+  /// Condition on target platforms this method is available for.
+  /// None if the method is available for all parsed targets.
+  pub condition: Option<Condition>,
   /// If true, FFI generator skips some checks
   pub is_ffi_whitelisted: bool,
 }
@@ -207,6 +238,19 @@ impl CppMethodKind {
 }
 
 impl CppMethod {
+  /// Returns true if the method may throw a C++ exception according to
+  /// its exception specification. Destructors are assumed to never throw.
+  pub fn may_throw(&self) -> bool {
+    if self.is_destructor() {
+      return false;
+    }
+    match self.exception_specification {
+      CppExceptionSpecification::None => true,
+      CppExceptionSpecification::NoThrow => false,
+      CppExceptionSpecification::Dynamic(ref types) => !types.is_empty(),
+    }
+  }
+
   /// Checks if two methods have exactly the same set of input argument types
   pub fn argument_types_equal(&self, other: &CppMethod) -> bool {
     if self.arguments.len() != other.arguments.len() {
@@ -485,6 +529,91 @@ impl CppMethod {
     self.operator.is_some()
   }
 
+  /// Returns true if this method is a public non-explicit constructor
+  /// that can be called with a single argument, i.e. a converting constructor
+  /// used by C++ for implicit conversions. Copy and move constructors
+  /// are not considered converting constructors.
+  pub fn is_converting_constructor(&self) -> bool {
+    if let Some(ref info) = self.class_membership {
+      if !info.kind.is_constructor() || info.is_explicit ||
+         info.visibility != CppVisibility::Public {
+        return false;
+      }
+      match self.arguments.get(0) {
+        Some(arg) => {
+          if let CppTypeBase::Class(ref class_type) = arg.argument_type.base {
+            if class_type == &info.class_type {
+              return false;
+            }
+          }
+        }
+        None => return false,
+      }
+      self
+        .arguments
+        .iter()
+        .skip(1)
+        .all(|arg| arg.has_default_value)
+    } else {
+      false
+    }
+  }
+
+  /// Returns true if this method is a copy constructor,
+  /// i.e. a constructor that can be called with a single argument
+  /// of type `const T&` or `T&` where `T` is its class.
+  pub fn is_copy_constructor(&self) -> bool {
+    if let Some(ref info) = self.class_membership {
+      if !info.kind.is_constructor() {
+        return false;
+      }
+      match self.arguments.get(0) {
+        Some(arg) => {
+          if arg.argument_type.indirection != CppTypeIndirection::Ref ||
+             arg.argument_type.base != CppTypeBase::Class(info.class_type.clone()) {
+            return false;
+          }
+        }
+        None => return false,
+      }
+      self
+        .arguments
+        .iter()
+        .skip(1)
+        .all(|arg| arg.has_default_value)
+    } else {
+      false
+    }
+  }
+
+  /// Returns true if this method is a move constructor or a move assignment operator,
+  /// i.e. receives `T&&` where `T` is its class as the only argument.
+  pub fn is_move_operation(&self) -> bool {
+    if let Some(ref info) = self.class_membership {
+      if !info.kind.is_constructor() && self.operator != Some(CppOperator::Assignment) {
+        return false;
+      }
+      self.arguments.len() == 1 &&
+      self.arguments[0].argument_type.indirection == CppTypeIndirection::RValueRef &&
+      self.arguments[0].argument_type.base == CppTypeBase::Class(info.class_type.clone())
+    } else {
+      false
+    }
+  }
+
+  /// Returns true if this method is a non-explicit conversion operator
+  /// (e.g. `operator QVariant() const`).
+  pub fn is_implicit_conversion_operator(&self) -> bool {
+    if let Some(CppOperator::Conversion(..)) = self.operator {
+      self
+        .class_membership
+        .as_ref()
+        .map_or(false, |info| !info.is_explicit)
+    } else {
+      false
+    }
+  }
+
   /// Returns collection of all types found in the signature of this method,
   /// including argument types, return type and type of `this` implicit parameter.
   pub fn all_involved_types(&self) -> Vec<CppType> {
@@ -0,0 +1,245 @@
+//! Merging of C++ parser results obtained for different target platforms.
+
+use cpp_data::ParserCppData;
+use cpp_method::{CppMethod, CppExceptionSpecification};
+use common::log;
+use common::target::{Condition, Target};
+
+use std::collections::{HashMap, HashSet};
+
+/// Returns a condition that is true if both `a` and `b` are true.
+/// `None` represents a condition that is always true.
+pub fn and_conditions(a: Option<&Condition>, b: Option<&Condition>) -> Option<Condition> {
+  match (a, b) {
+    (Some(a), Some(b)) => Some(a.and(b)),
+    (Some(a), None) => Some(a.clone()),
+    (None, Some(b)) => Some(b.clone()),
+    (None, None) => None,
+  }
+}
+
+/// Returns a condition that is true if any of `conditions` is true.
+/// `None` represents a condition that is always true.
+pub fn or_conditions<'a, I>(conditions: I) -> Option<Condition>
+  where I: IntoIterator<Item = Option<&'a Condition>>
+{
+  let mut result = Vec::new();
+  for condition in conditions {
+    match condition {
+      Some(condition) => {
+        if !result.contains(condition) {
+          result.push(condition.clone());
+        }
+      }
+      None => return None,
+    }
+  }
+  if result.len() == 1 {
+    result.pop()
+  } else {
+    Some(Condition::Or(result))
+  }
+}
+
+/// Returns simple conditions describing properties of `target`.
+/// More general properties come first so that generated conditions
+/// are more likely to hold for similar targets that were not parsed.
+fn target_properties(target: &Target) -> Vec<Condition> {
+  vec![Condition::Family(target.family),
+       Condition::OS(target.os),
+       Condition::Arch(target.arch),
+       Condition::PointerWidth(target.pointer_width),
+       Condition::Env(target.env),
+       Condition::Endian(target.endian)]
+}
+
+/// Returns a condition that is true for `targets` with `true` value
+/// in `presence` and false for other `targets`. The condition is based
+/// on a single target property if possible.
+pub fn presence_condition(targets: &[Target], presence: &[bool]) -> Condition {
+  let properties: Vec<_> = targets.iter().map(target_properties).collect();
+  let properties_count = properties[0].len();
+  for index in 0..properties_count {
+    let mut conditions = Vec::new();
+    for (target_properties, &is_present) in properties.iter().zip(presence) {
+      if is_present && !conditions.contains(&target_properties[index]) {
+        conditions.push(target_properties[index].clone());
+      }
+    }
+    let separates_targets = properties
+      .iter()
+      .zip(presence)
+      .all(|(target_properties, &is_present)| {
+             is_present || !conditions.contains(&target_properties[index])
+           });
+    if separates_targets {
+      return if conditions.len() == 1 {
+               conditions.pop().unwrap()
+             } else {
+               Condition::Or(conditions)
+             };
+    }
+  }
+  let mut conditions: Vec<_> = properties
+    .into_iter()
+    .zip(presence)
+    .filter(|&(_, &is_present)| is_present)
+    .map(|(target_properties, _)| Condition::And(target_properties))
+    .collect();
+  if conditions.len() == 1 {
+    conditions.pop().unwrap()
+  } else {
+    Condition::Or(conditions)
+  }
+}
+
+/// Returns a copy of `method` with fields that may differ
+/// between targets for the same declaration cleared.
+fn normalized_method(method: &CppMethod) -> CppMethod {
+  let mut method = method.clone();
+  method.origin_location = None;
+  method.declaration_code = None;
+  method.doc = None;
+  method.deprecation = None;
+  method.exception_specification = CppExceptionSpecification::None;
+  method
+}
+
+/// Returns names returned by `names` for all of the targets' data.
+fn names_for_all_targets<F>(data: &[(Target, ParserCppData)], names: F) -> HashSet<String>
+  where F: Fn(&ParserCppData) -> Vec<String>
+{
+  let mut sets = data
+    .iter()
+    .map(|x| names(&x.1).into_iter().collect::<HashSet<_>>());
+  let first = sets.next().unwrap_or_else(HashSet::new);
+  sets.fold(first, |a, b| a.intersection(&b).cloned().collect())
+}
+
+/// Merges parser results for multiple targets into a single `ParserCppData`.
+/// Types and methods that are not available for all targets receive
+/// a `condition` describing targets they are available for.
+/// Methods of platform-specific types also receive the type's condition.
+/// Typedefs, variables and macros are only kept if they are available for all targets.
+/// If a type's declaration differs between targets, the type and its methods are skipped.
+pub fn merge(data: Vec<(Target, ParserCppData)>) -> ParserCppData {
+  let targets: Vec<_> = data.iter().map(|x| x.0.clone()).collect();
+  let mut result = ParserCppData::default();
+  let mut type_presence: Vec<Vec<bool>> = Vec::new();
+  let mut type_indexes: HashMap<String, usize> = HashMap::new();
+  let mut method_presence: Vec<Vec<bool>> = Vec::new();
+  let mut method_indexes: HashMap<CppMethod, usize> = HashMap::new();
+  let mut mismatched_types = HashSet::new();
+  for (target_index, &(ref target, ref target_data)) in data.iter().enumerate() {
+    for type1 in &target_data.types {
+      if let Some(&index) = type_indexes.get(&type1.name) {
+        if result.types[index].kind != type1.kind && !mismatched_types.contains(&type1.name) {
+          log::llog(log::DebugParser, || {
+            format!("Declaration of type {} differs for target {:?}; the type is skipped",
+                    type1.name,
+                    target)
+          });
+          mismatched_types.insert(type1.name.clone());
+        }
+        type_presence[index][target_index] = true;
+      } else {
+        type_indexes.insert(type1.name.clone(), result.types.len());
+        let mut presence = vec![false; targets.len()];
+        presence[target_index] = true;
+        type_presence.push(presence);
+        result.types.push(type1.clone());
+      }
+    }
+    for method in &target_data.methods {
+      let key = normalized_method(method);
+      if let Some(&index) = method_indexes.get(&key) {
+        method_presence[index][target_index] = true;
+      } else {
+        method_indexes.insert(key, result.methods.len());
+        let mut presence = vec![false; targets.len()];
+        presence[target_index] = true;
+        method_presence.push(presence);
+        result.methods.push(method.clone());
+      }
+    }
+  }
+  // field accessors and sizes can't be generated from a single declaration
+  // that is not valid for all targets
+  let (types, type_presence): (Vec<_>, Vec<_>) = result
+    .types
+    .into_iter()
+    .zip(type_presence)
+    .filter(|&(ref type1, _)| !mismatched_types.contains(&type1.name))
+    .unzip();
+  result.types = types;
+  let (methods, method_presence): (Vec<_>, Vec<_>) = result
+    .methods
+    .into_iter()
+    .zip(method_presence)
+    .filter(|&(ref method, _)| {
+              method
+                .class_name()
+                .map_or(true, |name| !mismatched_types.contains(name))
+            })
+    .unzip();
+  result.methods = methods;
+  for (type1, presence) in result.types.iter_mut().zip(&type_presence) {
+    if presence.iter().any(|x| !x) {
+      let condition = presence_condition(&targets, presence);
+      log::llog(log::DebugParser,
+                || format!("Type {} is platform-specific: {:?}", type1.name, condition));
+      type1.condition = Some(condition);
+    }
+  }
+  let type_conditions: HashMap<_, _> = result
+    .types
+    .iter()
+    .filter_map(|t| t.condition.clone().map(|c| (t.name.clone(), c)))
+    .collect();
+  for (method, presence) in result.methods.iter_mut().zip(&method_presence) {
+    if presence.iter().any(|x| !x) {
+      let condition = presence_condition(&targets, presence);
+      log::llog(log::DebugParser, || {
+        format!("Method {} is platform-specific: {:?}",
+                method.short_text(),
+                condition)
+      });
+      method.condition = Some(condition);
+    }
+    let class_condition = method
+      .class_name()
+      .and_then(|name| type_conditions.get(name))
+      .cloned();
+    method.condition = and_conditions(method.condition.as_ref(), class_condition.as_ref());
+  }
+
+  let typedef_names =
+    names_for_all_targets(&data,
+                          |d| d.typedefs.iter().map(|x| x.name.clone()).collect());
+  let variable_names =
+    names_for_all_targets(&data,
+                          |d| d.variables.iter().map(|x| x.name.clone()).collect());
+  let macro_names = names_for_all_targets(&data,
+                                          |d| d.macros.iter().map(|x| x.name.clone()).collect());
+  if let Some(&(_, ref first_data)) = data.first() {
+    result.typedefs = first_data
+      .typedefs
+      .iter()
+      .filter(|x| typedef_names.contains(&x.name))
+      .cloned()
+      .collect();
+    result.variables = first_data
+      .variables
+      .iter()
+      .filter(|x| variable_names.contains(&x.name))
+      .cloned()
+      .collect();
+    result.macros = first_data
+      .macros
+      .iter()
+      .filter(|x| macro_names.contains(&x.name))
+      .cloned()
+      .collect();
+  }
+  result
+}
@@ -1,14 +1,16 @@
 use cpp_data::{ParserCppData, CppData, CppTypeData, CppTypeKind, CppClassField, CppEnumValue,
                CppOriginLocation, CppVisibility, CppClassUsingDirective, CppBaseSpecifier,
-               TemplateArgumentsDeclaration};
-use cpp_method::{CppMethod, CppMethodArgument, CppMethodKind, CppMethodClassMembership};
+               TemplateArgumentsDeclaration, CppTypedef, CppVariable, CppLiteralValue,
+               CppMacro, CppDeprecation, CppTypeDoc, CppQtProperty};
+use cpp_method::{CppMethod, CppMethodArgument, CppMethodKind, CppMethodClassMembership,
+                 CppExceptionSpecification, CppMethodDoc};
 use cpp_operator::CppOperator;
 use cpp_type::{CppType, CppTypeBase, CppBuiltInNumericType, CppTypeIndirection,
                CppSpecificNumericTypeKind, CppTypeClassBase, CppSpecificNumericType,
                CppFunctionPointerType};
 use common::errors::{Result, ChainErr, unexpected};
 use common::file_utils::{remove_file, open_file, create_file, path_to_str, os_str_to_str};
-use common::string_utils::JoinWithSeparator;
+use common::string_utils::{JoinWithSeparator, CaseOperations};
 use common::log;
 
 use std::io::{BufRead, BufReader};
@@ -16,6 +18,7 @@ use std::path::{Path, PathBuf};
 
 use clang::*;
 use clang;
+use clang::documentation::CommentChild;
 
 use regex::Regex;
 
@@ -52,6 +55,12 @@ struct CppParser<'a> {
   config: CppParserConfig,
   /// C++ types found by the parser
   types: Vec<CppTypeData>,
+  /// C++ typedefs and type aliases found by the parser
+  typedefs: Vec<CppTypedef>,
+  /// C++ variables and constants found by the parser
+  variables: Vec<CppVariable>,
+  /// Allowed C++ macros found by the parser
+  macros: Vec<CppMacro>,
   /// Processed C++ data of the dependencies
   dependencies_data: &'a [&'a CppData],
 }
@@ -94,23 +103,519 @@ fn get_template_arguments(entity: Entity) -> Option<TemplateArgumentsDeclaration
       nested_level = args.nested_level + 1;
     }
   }
-  let names: Vec<_> = entity
-    .get_children()
-    .into_iter()
-    .filter(|c| c.get_kind() == EntityKind::TemplateTypeParameter)
-    .enumerate()
-    .map(|(i, c)| c.get_name().unwrap_or_else(|| format!("Type{}", i + 1)))
-    .collect();
+  let mut names = Vec::new();
+  let mut value_parameters = Vec::new();
+  for child in entity.get_children() {
+    match child.get_kind() {
+      EntityKind::TemplateTypeParameter => {
+        let index = names.len();
+        names.push(child
+                     .get_name()
+                     .unwrap_or_else(|| format!("Type{}", index + 1)));
+      }
+      EntityKind::NonTypeTemplateParameter => {
+        let index = names.len();
+        value_parameters.push(index);
+        names.push(child
+                     .get_name()
+                     .unwrap_or_else(|| format!("Value{}", index + 1)));
+      }
+      _ => {}
+    }
+  }
   if names.is_empty() {
     None
   } else {
     Some(TemplateArgumentsDeclaration {
            nested_level: nested_level,
            names: names,
+           value_parameters: value_parameters,
          })
   }
 }
 
+/// Extract template argument declarations from a class `entity` or,
+/// if it's not a template class itself, from the closest template class
+/// it's nested into.
+fn get_class_template_arguments(entity: Entity) -> Option<TemplateArgumentsDeclaration> {
+  if let Some(args) = get_template_arguments(entity) {
+    return Some(args);
+  }
+  if let Some(parent) = entity.get_semantic_parent() {
+    match parent.get_kind() {
+      EntityKind::ClassDecl |
+      EntityKind::ClassTemplate |
+      EntityKind::StructDecl |
+      EntityKind::UnionDecl => return get_class_template_arguments(parent),
+      _ => {}
+    }
+  }
+  None
+}
+
+/// Parses a C++ literal represented by `tokens`, like `42`, `-1.5f`,
+/// `0x10u`, `true` or `"text"`. Returns `None` if `tokens` is not
+/// a single (possibly negated or parenthesized) literal.
+fn parse_literal(tokens: &[String]) -> Option<CppLiteralValue> {
+  fn remove_parentheses(mut tokens: &[String]) -> &[String] {
+    while tokens.len() >= 2 && tokens[0] == "(" && tokens[tokens.len() - 1] == ")" {
+      tokens = &tokens[1..tokens.len() - 1];
+    }
+    tokens
+  }
+  let mut tokens = remove_parentheses(tokens);
+  let is_negative = !tokens.is_empty() && tokens[0] == "-";
+  if is_negative {
+    tokens = remove_parentheses(&tokens[1..]);
+  }
+  if tokens.is_empty() {
+    return None;
+  }
+  if tokens.iter().all(|t| t.starts_with('"')) {
+    // adjacent string literals are concatenated
+    if is_negative {
+      return None;
+    }
+    let mut result = String::new();
+    for token in tokens {
+      match parse_string_literal(token) {
+        Some(value) => result.push_str(&value),
+        None => return None,
+      }
+    }
+    return Some(CppLiteralValue::String(result));
+  }
+  if tokens.len() != 1 {
+    return None;
+  }
+  if let Some(value) = parse_char_literal(&tokens[0]) {
+    return Some(CppLiteralValue::Integer(if is_negative { -value } else { value }));
+  }
+  let token = remove_digit_separators(&tokens[0]);
+  if token == "true" || token == "false" {
+    if is_negative {
+      return None;
+    }
+    return Some(CppLiteralValue::Bool(token == "true"));
+  }
+  if let Some(value) = parse_integer_literal(&token) {
+    return Some(CppLiteralValue::Integer(if is_negative { -value } else { value }));
+  }
+  if let Some(value) = parse_float_literal(&token) {
+    return Some(CppLiteralValue::Float(if is_negative {
+                                         format!("-{}", value)
+                                       } else {
+                                         value
+                                       }));
+  }
+  None
+}
+
+/// Checks if a constant of type `cpp_type` initialized with `value`
+/// can be represented as a Rust constant.
+fn literal_fits_type(value: &CppLiteralValue, cpp_type: &CppType) -> bool {
+  if cpp_type.indirection == CppTypeIndirection::Ptr {
+    return cpp_type.is_const &&
+           cpp_type.base == CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Char) &&
+           match *value {
+             CppLiteralValue::String(..) => true,
+             _ => false,
+           };
+  }
+  if cpp_type.indirection != CppTypeIndirection::None {
+    return false;
+  }
+  match cpp_type.base {
+    CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool) => {
+      match *value {
+        CppLiteralValue::Bool(..) => true,
+        _ => false,
+      }
+    }
+    CppTypeBase::BuiltInNumeric(ref numeric) => {
+      match *value {
+        CppLiteralValue::Integer(..) => true,
+        CppLiteralValue::Float(..) => numeric.is_float(),
+        _ => false,
+      }
+    }
+    CppTypeBase::SpecificNumeric(CppSpecificNumericType { ref kind, .. }) => {
+      match *value {
+        CppLiteralValue::Integer(..) => true,
+        CppLiteralValue::Float(..) => kind == &CppSpecificNumericTypeKind::FloatingPoint,
+        _ => false,
+      }
+    }
+    CppTypeBase::PointerSizedInteger { .. } => {
+      match *value {
+        CppLiteralValue::Integer(..) => true,
+        _ => false,
+      }
+    }
+    _ => false,
+  }
+}
+
+/// Parses an unprefixed C++ character literal, like `'a'` or `'\n'`,
+/// and returns its code.
+fn parse_char_literal(token: &str) -> Option<i64> {
+  if token.len() < 3 || !token.starts_with('\'') || !token.ends_with('\'') {
+    return None;
+  }
+  let value = match parse_string_literal(&format!("\"{}\"", &token[1..token.len() - 1])) {
+    Some(value) => value,
+    None => return None,
+  };
+  let mut chars = value.chars();
+  match (chars.next(), chars.next()) {
+    (Some(c), None) => Some(c as i64),
+    _ => None,
+  }
+}
+
+/// Removes digit separators from a numeric literal `token`,
+/// like in `1'000'000` or `0xFFFF'FFFF`.
+fn remove_digit_separators(token: &str) -> String {
+  let chars: Vec<char> = token.chars().collect();
+  chars
+    .iter()
+    .enumerate()
+    .filter(|&(index, &c)| {
+              !(c == '\'' && index > 0 && index + 1 < chars.len() &&
+                chars[index - 1].is_digit(16) && chars[index + 1].is_digit(16))
+            })
+    .map(|(_, &c)| c)
+    .collect()
+}
+
+/// Parses a C++ integer literal without sign, like `42`, `0x1Fu` or `010`.
+fn parse_integer_literal(token: &str) -> Option<i64> {
+  lazy_static! {
+    static ref REGEX: Regex =
+      Regex::new(r"^(0[xX][0-9a-fA-F]+|0[bB][01]+|0[0-7]*|[1-9][0-9]*)[uUlL]*$").unwrap();
+  }
+  let digits = match REGEX.captures(token).and_then(|matches| matches.at(1)) {
+    Some(digits) => digits,
+    None => return None,
+  };
+  let (digits, radix) = if digits.starts_with("0x") || digits.starts_with("0X") {
+    (&digits[2..], 16)
+  } else if digits.starts_with("0b") || digits.starts_with("0B") {
+    (&digits[2..], 2)
+  } else if digits.len() > 1 && digits.starts_with('0') {
+    (&digits[1..], 8)
+  } else {
+    (digits, 10)
+  };
+  i64::from_str_radix(digits, radix).ok()
+}
+
+/// Parses a C++ floating point literal without sign, like `1.5f` or `1e-3`.
+/// Returns text of the literal without suffix that is also valid in Rust.
+fn parse_float_literal(token: &str) -> Option<String> {
+  lazy_static! {
+    static ref REGEX: Regex =
+      Regex::new(r"^((?:\d+\.\d*|\.\d+|\d+)(?:[eE][+-]?\d+)?)[fFlL]?$").unwrap();
+  }
+  let mut value = match REGEX.captures(token).and_then(|matches| matches.at(1)) {
+    Some(value) => value.to_string(),
+    None => return None,
+  };
+  if !value.contains('.') && !value.contains('e') && !value.contains('E') {
+    return None;
+  }
+  if value.starts_with('.') {
+    value = format!("0{}", value);
+  }
+  if let Some(index) = value.find('.') {
+    let after_dot = &value[index + 1..];
+    if after_dot.is_empty() || after_dot.starts_with('e') || after_dot.starts_with('E') {
+      value = format!("{}0{}", &value[0..index + 1], after_dot);
+    }
+  }
+  Some(value)
+}
+
+/// Parses a plain C++ string literal token, like `"text\n"`.
+/// Returns `None` if the literal has a prefix or uses unsupported escape sequences.
+fn parse_string_literal(token: &str) -> Option<String> {
+  if token.len() < 2 || !token.starts_with('"') || !token.ends_with('"') {
+    return None;
+  }
+  let mut result = String::new();
+  let mut chars = token[1..token.len() - 1].chars();
+  while let Some(c) = chars.next() {
+    if c == '\\' {
+      result.push(match chars.next() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('0') => '\0',
+                    Some('\\') => '\\',
+                    Some('"') => '"',
+                    Some('\'') => '\'',
+                    _ => return None,
+                  });
+    } else {
+      result.push(c);
+    }
+  }
+  Some(result)
+}
+
+/// Escapes special HTML characters in `text`.
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+}
+
+/// Converts inline content of a doc comment (text and inline commands
+/// like `\c` or `\b`) to HTML.
+fn doc_comment_inline_to_html(children: &[CommentChild]) -> String {
+  let mut html = String::new();
+  let mut previous_is_text = false;
+  for child in children {
+    let is_text = match *child {
+      CommentChild::Text(ref text) => {
+        // adjacent text parts come from different lines of the comment
+        if previous_is_text {
+          html.push(' ');
+        }
+        html.push_str(&escape_html(text));
+        true
+      }
+      CommentChild::InlineCommand(ref command) => {
+        let text = escape_html(&command.arguments.join(" "));
+        html.push_str(&match command.command.as_str() {
+                        "c" | "p" => format!("<code>{}</code>", text),
+                        "b" => format!("<b>{}</b>", text),
+                        "a" | "e" | "em" => format!("<em>{}</em>", text),
+                        _ => text,
+                      });
+        false
+      }
+      CommentChild::Paragraph(ref children) => {
+        html.push_str(&doc_comment_inline_to_html(children));
+        false
+      }
+      _ => false,
+    };
+    previous_is_text = is_text;
+  }
+  html.split_whitespace().join(" ")
+}
+
+/// Converts the doc comment attached to `entity` to HTML.
+/// Brief description goes first, followed by other paragraphs,
+/// descriptions of parameters and the return value.
+/// Returns `None` if `entity` doesn't have a doc comment.
+fn get_doc_comment_html(entity: Entity) -> Option<String> {
+  let comment = match entity.get_parsed_comment() {
+    Some(comment) => comment,
+    None => return None,
+  };
+  let mut brief = None;
+  let mut paragraphs = Vec::new();
+  let mut params = Vec::new();
+  let mut returns = None;
+  for child in comment.get_children() {
+    match child {
+      CommentChild::Paragraph(ref children) => {
+        let text = doc_comment_inline_to_html(children);
+        if !text.is_empty() {
+          paragraphs.push(text);
+        }
+      }
+      CommentChild::BlockCommand(ref command) => {
+        let text = doc_comment_inline_to_html(&command.children);
+        match command.command.as_str() {
+          "brief" | "short" => brief = Some(text),
+          "return" | "returns" | "result" => returns = Some(text),
+          name => paragraphs.push(format!("<b>{}:</b> {}", name.to_class_case(), text)),
+        }
+      }
+      CommentChild::ParamCommand(ref command) => {
+        params.push(format!("<li><code>{}</code>: {}</li>",
+                            escape_html(&command.parameter),
+                            doc_comment_inline_to_html(&command.children)));
+      }
+      CommentChild::VerbatimCommand(ref lines) => {
+        paragraphs.push(format!("<pre>{}</pre>", escape_html(&lines.join("\n"))));
+      }
+      _ => {}
+    }
+  }
+  let mut html: Vec<String> = brief
+    .into_iter()
+    .chain(paragraphs.into_iter())
+    .map(|text| format!("<p>{}</p>", text))
+    .collect();
+  if !params.is_empty() {
+    html.push(format!("<p>Parameters:</p><ul>{}</ul>", params.join("")));
+  }
+  if let Some(returns) = returns {
+    html.push(format!("<p>Returns: {}</p>", returns));
+  }
+  if html.is_empty() {
+    None
+  } else {
+    Some(html.join("\n"))
+  }
+}
+
+/// Returns type documentation extracted from the doc comment of `entity`, if any.
+fn get_type_doc(entity: Entity) -> Option<CppTypeDoc> {
+  get_doc_comment_html(entity).map(|html| {
+                                     CppTypeDoc {
+                                       html: html,
+                                       url: String::new(),
+                                       cross_references: Vec::new(),
+                                     }
+                                   })
+}
+
+/// Returns deprecation information for `entity` if it's marked as deprecated.
+fn get_deprecation(entity: Entity) -> Option<CppDeprecation> {
+  match entity.get_availability() {
+    Availability::Deprecated => {}
+    _ => return None,
+  }
+  // libclang doesn't provide the message of the attribute, so it's extracted
+  // from tokens of the declaration header and tokens of the attributes
+  let mut tokens = Vec::new();
+  if let Some(range) = entity.get_range() {
+    tokens.extend(range
+                    .tokenize()
+                    .into_iter()
+                    .map(|token| token.get_spelling())
+                    .take_while(|token| token != "{"));
+  }
+  for child in entity.get_children() {
+    if child.get_kind() == EntityKind::UnexposedAttr {
+      if let Some(range) = child.get_range() {
+        tokens.extend(range.tokenize().into_iter().map(|token| token.get_spelling()));
+      }
+    }
+  }
+  let is_deprecation_token = |token: &str| {
+    token == "deprecated" || token == "__deprecated__" || token.ends_with("DEPRECATED_X") ||
+    token.starts_with("QT_DEPRECATED_VERSION_X")
+  };
+  let mut message = None;
+  for (index, token) in tokens.iter().enumerate() {
+    if !is_deprecation_token(token) || tokens.get(index + 1).map_or(true, |t| t != "(") {
+      continue;
+    }
+    // adjacent string literals are concatenated
+    let parts: Vec<String> = tokens[index + 2..]
+      .iter()
+      .take_while(|t| t.starts_with('"'))
+      .filter_map(|t| parse_string_literal(t))
+      .collect();
+    if !parts.is_empty() {
+      message = Some(parts.join(""));
+      break;
+    }
+  }
+  Some(CppDeprecation { message: message })
+}
+
+/// Returns the text inside the parentheses at the end of `text`,
+/// like `std::bad_alloc` in `void () throw(std::bad_alloc)`.
+fn trailing_parenthesized(text: &str) -> Option<&str> {
+  let text = text.trim_right();
+  if !text.ends_with(')') {
+    return None;
+  }
+  let mut depth = 0;
+  for (index, c) in text.char_indices().rev() {
+    match c {
+      ')' => depth += 1,
+      '(' => {
+        depth -= 1;
+        if depth == 0 {
+          return Some(&text[index + 1..text.len() - 1]);
+        }
+      }
+      _ => {}
+    }
+  }
+  None
+}
+
+/// Splits `text` by commas that are not nested in brackets,
+/// like a list of template arguments or types.
+fn split_top_level(text: &str) -> Vec<String> {
+  let mut parts = Vec::new();
+  let mut depth = 0;
+  let mut start = 0;
+  for (index, c) in text.char_indices() {
+    match c {
+      '<' | '(' | '[' => depth += 1,
+      '>' | ')' | ']' => depth -= 1,
+      ',' if depth == 0 => {
+        parts.push(text[start..index].trim().to_string());
+        start = index + 1;
+      }
+      _ => {}
+    }
+  }
+  parts.push(text[start..].trim().to_string());
+  parts.into_iter().filter(|x| !x.is_empty()).collect()
+}
+
+/// Returns exception specification of function `entity`.
+fn parse_exception_specification(entity: Entity) -> CppExceptionSpecification {
+  // clang doesn't report the types of a dynamic exception specification
+  // and the argument of a computed `noexcept`, so they are taken from
+  // the end of the function type's spelling (with all macros expanded)
+  let type_spelling = entity.get_type().map(|t| t.get_display_name());
+  let argument = type_spelling
+    .as_ref()
+    .and_then(|spelling| trailing_parenthesized(spelling));
+  match entity.get_exception_specification() {
+    Some(ExceptionSpecification::BasicNoexcept) |
+    Some(ExceptionSpecification::DynamicNone) => CppExceptionSpecification::NoThrow,
+    Some(ExceptionSpecification::ComputedNoexcept) => {
+      if argument.map_or(false, |x| x.trim() == "true") {
+        CppExceptionSpecification::NoThrow
+      } else {
+        CppExceptionSpecification::None
+      }
+    }
+    Some(ExceptionSpecification::Dynamic) => {
+      let types = argument.map_or_else(Vec::new, split_top_level);
+      if types.is_empty() {
+        CppExceptionSpecification::None
+      } else {
+        CppExceptionSpecification::Dynamic(types)
+      }
+    }
+    _ => CppExceptionSpecification::None,
+  }
+}
+
+/// Returns the closest template class `entity` is nested into, if any.
+fn get_enclosing_template_class(entity: Entity) -> Option<Entity> {
+  if let Some(parent) = entity.get_semantic_parent() {
+    match parent.get_kind() {
+      EntityKind::ClassDecl |
+      EntityKind::ClassTemplate |
+      EntityKind::StructDecl |
+      EntityKind::UnionDecl => {
+        if get_template_arguments(parent).is_some() {
+          return Some(parent);
+        }
+        return get_enclosing_template_class(parent);
+      }
+      _ => {}
+    }
+  }
+  None
+}
+
 /// Returns fully qualified name of `entity`.
 fn get_full_name(entity: Entity) -> Result<String> {
   let mut current_entity = entity;
@@ -120,6 +625,7 @@ fn get_full_name(entity: Entity) -> Result<String> {
         EntityKind::ClassDecl |
         EntityKind::ClassTemplate |
         EntityKind::StructDecl |
+        EntityKind::UnionDecl |
         EntityKind::Namespace |
         EntityKind::EnumDecl |
         EntityKind::ClassTemplatePartialSpecialization => {
@@ -141,6 +647,90 @@ fn get_full_name(entity: Entity) -> Result<String> {
   }
 }
 
+/// Returns true if `name` is the full name of `std::function` class template.
+/// Standard library implementations may place it in an inline namespace
+/// (e.g. `std::__1::function`).
+fn is_std_function(name: &str) -> bool {
+  name == "std::function" ||
+  (name.starts_with("std::") && name.ends_with("::function") && name.matches("::").count() == 2)
+}
+
+/// Joins `tokens` into a C++ code string, separating only adjacent identifiers
+/// and keywords by spaces (e.g. `QList<unsigned int>`).
+fn join_tokens(tokens: &[String]) -> String {
+  let is_word = |token: &str| {
+    token
+      .chars()
+      .next()
+      .map_or(false, |c| c.is_alphanumeric() || c == '_')
+  };
+  let mut result = String::new();
+  for (index, token) in tokens.iter().enumerate() {
+    if index > 0 && is_word(&tokens[index - 1]) && is_word(token) {
+      result.push(' ');
+    }
+    result.push_str(token);
+  }
+  result
+}
+
+/// Parses contents of a `Q_PROPERTY` macro invocation represented by `tokens`
+/// (everything between the parentheses).
+fn parse_qt_property(tokens: &[String]) -> Result<CppQtProperty> {
+  let keywords = ["READ", "WRITE", "RESET", "NOTIFY", "MEMBER", "REVISION", "DESIGNABLE",
+                  "SCRIPTABLE", "STORED", "USER", "BINDABLE", "CONSTANT", "FINAL", "REQUIRED"];
+  // keywords that are not followed by a value
+  let flag_keywords = ["CONSTANT", "FINAL", "REQUIRED"];
+  let keywords_start = tokens
+    .iter()
+    .position(|token| keywords.contains(&token.as_str()))
+    .unwrap_or(tokens.len());
+  if keywords_start < 2 {
+    return Err(format!("invalid property declaration: {}", tokens.join(" ")).into());
+  }
+  let mut property = CppQtProperty {
+    name: tokens[keywords_start - 1].clone(),
+    type_name: join_tokens(&tokens[..keywords_start - 1]),
+    read: None,
+    write: None,
+    reset: None,
+    notify: None,
+  };
+  let mut index = keywords_start;
+  while index < tokens.len() {
+    let keyword = tokens[index].as_str();
+    if flag_keywords.contains(&keyword) {
+      index += 1;
+      continue;
+    }
+    if keyword == "REVISION" && tokens.get(index + 1).map(|x| x.as_str()) == Some("(") {
+      // `REVISION(n)` form
+      match tokens[index..].iter().position(|x| x == ")") {
+        Some(end) => index += end + 1,
+        None => {
+          return Err(format!("missing ')' after REVISION in property {}", property.name).into())
+        }
+      }
+      continue;
+    }
+    let value = match tokens.get(index + 1) {
+      Some(value) => value.clone(),
+      None => {
+        return Err(format!("missing value of {} in property {}", keyword, property.name).into())
+      }
+    };
+    match keyword {
+      "READ" => property.read = Some(value),
+      "WRITE" => property.write = Some(value),
+      "RESET" => property.reset = Some(value),
+      "NOTIFY" => property.notify = Some(value),
+      _ => {}
+    }
+    index += 2;
+  }
+  Ok(property)
+}
+
 /// C++ parser configuration
 #[derive(Clone, Debug)]
 pub struct CppParserConfig {
@@ -161,6 +751,9 @@ pub struct CppParserConfig {
   /// List of names that should be excluded from the processing.
   /// See `Config::add_cpp_parser_blocked_name` for more details.
   pub name_blacklist: Vec<String>,
+  /// List of macro names that should be processed.
+  /// See `Config::add_cpp_parser_allowed_macro` for more details.
+  pub macro_whitelist: Vec<String>,
 }
 
 #[cfg(test)]
@@ -234,6 +827,8 @@ fn run_clang<R, F: Fn(Entity) -> Result<R>>(config: &CppParserConfig,
   let tu = index
     .parser(&config.tmp_cpp_path)
     .arguments(&args)
+    // macro definitions are only reported with a detailed preprocessing record
+    .detailed_preprocessing_record(!config.macro_whitelist.is_empty())
     .parse()
     .map_err(|err| format!("clang parse failed: {:?}", err))?;
   let translation_unit = tu.get_entity();
@@ -269,6 +864,9 @@ pub fn run(config: CppParserConfig, dependencies_data: &[&CppData]) -> Result<Pa
   let (mut parser, methods) = run_clang(&config, None, |translation_unit| {
     let mut parser = CppParser {
       types: Vec::new(),
+      typedefs: Vec::new(),
+      variables: Vec::new(),
+      macros: Vec::new(),
       config: config.clone(),
       dependencies_data: dependencies_data,
     };
@@ -281,10 +879,15 @@ pub fn run(config: CppParserConfig, dependencies_data: &[&CppData]) -> Result<Pa
   log::status("Checking data integrity");
   let (good_methods, good_types) = parser.check_integrity(methods);
   parser.types = good_types;
+  let good_typedefs = parser.check_typedefs_integrity();
+  let good_variables = parser.check_variables_integrity();
   log::status("Searching for template instantiations");
   Ok(ParserCppData {
        types: parser.types,
        methods: good_methods,
+       typedefs: good_typedefs,
+       variables: good_variables,
+       macros: parser.macros,
      })
 }
 
@@ -316,6 +919,8 @@ impl<'a> CppParser<'a> {
                           -> Result<CppType> {
     lazy_static! {
       static ref TEMPLATE_CLASS_REGEX: Regex  = Regex::new(r"^([\w:]+)<(.+)>$").unwrap();
+      static ref NESTED_IN_TEMPLATE_CLASS_REGEX: Regex =
+        Regex::new(r"^([\w:]+)<(.+)>::([\w:]+)$").unwrap();
     }
     let (is_const, name) = if let Some(type1) = type1 {
       let is_const = type1.is_const_qualified();
@@ -338,6 +943,20 @@ impl<'a> CppParser<'a> {
                                get_full_name(declaration).unwrap_or("?".into()))
                            .into());
           }
+          if let Some((enclosing_name, args)) =
+            self
+              .parse_enclosing_template_arguments(declaration, context_class, context_method)? {
+            return Ok(CppType {
+                        base: CppTypeBase::Class(CppTypeClassBase {
+                                                   name: get_full_name(declaration)?,
+                                                   template_arguments: Some(args),
+                                                   enclosing_template_class: Some(enclosing_name),
+                                                 }),
+                        is_const: is_const,
+                        is_const2: false,
+                        indirection: CppTypeIndirection::None,
+                      });
+          }
           if let Some(matches) = TEMPLATE_CLASS_REGEX.captures(name.as_ref()) {
             let mut arg_types = Vec::new();
             if let Some(items) = matches.at(2) {
@@ -362,6 +981,7 @@ impl<'a> CppParser<'a> {
                         base: CppTypeBase::Class(CppTypeClassBase {
                                                    name: get_full_name(declaration)?,
                                                    template_arguments: Some(arg_types),
+                                                   enclosing_template_class: None,
                                                  }),
                         is_const: is_const,
                         is_const2: false,
@@ -421,7 +1041,7 @@ impl<'a> CppParser<'a> {
       }
     }
     if let Some(e) = context_class {
-      if let Some(args) = get_template_arguments(e) {
+      if let Some(args) = get_class_template_arguments(e) {
         if let Some(index) = args.names.iter().position(|x| *x == name) {
           return Ok(CppType {
                       base: CppTypeBase::TemplateParameter {
@@ -495,15 +1115,47 @@ impl<'a> CppParser<'a> {
         CppTypeKind::Enum { .. } => {
           result_type.base = CppTypeBase::Enum { name: remaining_name.to_string() }
         }
-        CppTypeKind::Class { .. } => {
+        CppTypeKind::Class { .. } |
+        CppTypeKind::Union { .. } => {
           result_type.base = CppTypeBase::Class(CppTypeClassBase {
                                                   name: remaining_name.to_string(),
                                                   template_arguments: None,
+                                                  enclosing_template_class: None,
                                                 })
         }
       }
       return Ok(result_type);
     }
+    if let Some(matches) = NESTED_IN_TEMPLATE_CLASS_REGEX.captures(remaining_name) {
+      let class_name = format!("{}::{}", &matches[1], &matches[3]);
+      if self
+           .find_type(|x| x.name == class_name && x.is_class())
+           .is_some() {
+        let enclosing_type = self
+          .parse_unexposed_type(None,
+                                Some(format!("{}<{}>", &matches[1], &matches[2])),
+                                context_class,
+                                context_method)?;
+        if let CppTypeBase::Class(CppTypeClassBase {
+                                    name: enclosing_name,
+                                    template_arguments,
+                                    ..
+                                  }) = enclosing_type.base {
+          result_type.base = CppTypeBase::Class(CppTypeClassBase {
+                                                  name: class_name,
+                                                  template_arguments: template_arguments,
+                                                  enclosing_template_class: Some(enclosing_name),
+                                                });
+          return Ok(result_type);
+        }
+      }
+    }
+    if result_type.indirection == CppTypeIndirection::None {
+      if let Some(base) = self.parse_template_argument_value(remaining_name) {
+        result_type.base = base;
+        return Ok(result_type);
+      }
+    }
 
     if let Some(matches) = TEMPLATE_CLASS_REGEX.captures(remaining_name) {
       if matches.len() < 3 {
@@ -531,6 +1183,7 @@ impl<'a> CppParser<'a> {
         result_type.base = CppTypeBase::Class(CppTypeClassBase {
                                                 name: class_name.to_string(),
                                                 template_arguments: Some(arg_types),
+                                                enclosing_template_class: None,
                                               });
         return Ok(result_type);
       }
@@ -649,6 +1302,11 @@ impl<'a> CppParser<'a> {
               if arg_types.is_empty() {
                 return Err(unexpected("arg_types is empty").into());
               }
+              if arg_types.iter().any(|x| x.is_none()) {
+                // non-type template arguments are only available
+                // in the type's display name
+                return self.parse_unexposed_type(Some(type1), None, context_class, context_method);
+              }
               for arg_type in arg_types {
                 match arg_type {
                   None => return Err("Template argument is None".into()),
@@ -666,11 +1324,39 @@ impl<'a> CppParser<'a> {
               Some(r)
             }
           };
+          if is_std_function(&declaration_name) {
+            if let Some(ref args) = template_arguments {
+              if args.len() == 1 && args[0].indirection == CppTypeIndirection::None {
+                if let CppTypeBase::FunctionPointer(ref function) = args[0].base {
+                  return Ok(CppType {
+                              base: CppTypeBase::StdFunction(function.clone()),
+                              is_const: is_const,
+                              is_const2: false,
+                              indirection: CppTypeIndirection::None,
+                            });
+                }
+              }
+            }
+            return Err(format!("Unsupported std::function type: {}", display_name).into());
+          }
+          let (template_arguments, enclosing_template_class) = match template_arguments {
+            Some(args) => (Some(args), None),
+            None => {
+              match self
+                      .parse_enclosing_template_arguments(declaration,
+                                                          context_class,
+                                                          context_method)? {
+                Some((enclosing_name, args)) => (Some(args), Some(enclosing_name)),
+                None => (None, None),
+              }
+            }
+          };
 
           Ok(CppType {
                base: CppTypeBase::Class(CppTypeClassBase {
                                           name: declaration_name,
                                           template_arguments: template_arguments,
+                                          enclosing_template_class: enclosing_template_class,
                                         }),
                is_const: is_const,
                is_const2: false,
@@ -804,6 +1490,116 @@ impl<'a> CppParser<'a> {
     }
   }
 
+  /// Returns name and template arguments of the template class `declaration`
+  /// is nested into, or `None` if it's not nested into a template class.
+  /// For types nested into a template class declaration (like `QList<T>::iterator`),
+  /// template parameters of the enclosing class are returned.
+  fn parse_enclosing_template_arguments(&self,
+                                        declaration: Entity,
+                                        context_class: Option<Entity>,
+                                        context_method: Option<Entity>)
+                                        -> Result<Option<(String, Vec<CppType>)>> {
+    let mut current = declaration;
+    while let Some(parent) = current.get_semantic_parent() {
+      match parent.get_kind() {
+        EntityKind::ClassTemplate => {
+          let args = get_template_arguments(parent)
+            .chain_err(|| "missing template arguments of enclosing class")?;
+          let parameters = (0..args.names.len())
+            .map(|index| {
+                   CppType {
+                     base: CppTypeBase::TemplateParameter {
+                       nested_level: args.nested_level,
+                       index: index,
+                     },
+                     is_const: false,
+                     is_const2: false,
+                     indirection: CppTypeIndirection::None,
+                   }
+                 })
+            .collect();
+          return Ok(Some((get_full_name(parent)?, parameters)));
+        }
+        EntityKind::ClassDecl |
+        EntityKind::StructDecl => {
+          if parent.get_template().is_some() {
+            let parent_type = parent
+              .get_type()
+              .chain_err(|| "failed to get type of enclosing template class")?;
+            let parsed = self.parse_type(parent_type, context_class, context_method)?;
+            if let CppTypeBase::Class(CppTypeClassBase {
+                                        name,
+                                        template_arguments,
+                                        ..
+                                      }) = parsed.base {
+              return Ok(template_arguments.map(|args| (name, args)));
+            }
+            return Err(unexpected("enclosing template class type expected").into());
+          }
+        }
+        _ => return Ok(None),
+      }
+      current = parent;
+    }
+    Ok(None)
+  }
+
+  /// Attempts to parse `name` as a value of a non-type template argument,
+  /// like `3` in `std::array<int, 3>` or `Qt::Horizontal` in `A<Qt::Horizontal>`.
+  fn parse_template_argument_value(&self, name: &str) -> Option<CppTypeBase> {
+    lazy_static! {
+      static ref INTEGER_REGEX: Regex = Regex::new(r"^(?:\(([\w:]+)\))?(-?\d+)[uUlL]*$").unwrap();
+    }
+    match name {
+      "true" | "false" => {
+        return Some(CppTypeBase::TemplateArgumentValue {
+                      value: if name == "true" { 1 } else { 0 },
+                      enum_name: None,
+                    });
+      }
+      _ => {}
+    }
+    if let Some(matches) = INTEGER_REGEX.captures(name) {
+      if let Ok(value) = matches[2].parse() {
+        let enum_name = matches
+          .at(1)
+          .and_then(|enum_name| {
+                      self
+                        .find_type(|x| &x.name == enum_name && !x.is_class())
+                        .map(|x| x.name.clone())
+                    });
+        return Some(CppTypeBase::TemplateArgumentValue {
+                      value: value,
+                      enum_name: enum_name,
+                    });
+      }
+    }
+    let (scope, value_name) = match name.rfind("::") {
+      Some(index) => (&name[0..index], &name[index + 2..]),
+      None => ("", name),
+    };
+    let enum_type = self.find_type(|x| if let CppTypeKind::Enum { ref values } = x.kind {
+                                     let enum_scope = x.name
+                                       .rfind("::")
+                                       .map_or("", |index| &x.name[0..index]);
+                                     (enum_scope == scope || x.name == scope) &&
+                                     values.iter().any(|v| v.name == value_name)
+                                   } else {
+                                     false
+                                   });
+    if let Some(enum_type) = enum_type {
+      if let CppTypeKind::Enum { ref values } = enum_type.kind {
+        if let Some(value) = values.iter().find(|v| v.name == value_name) {
+          return Some(CppTypeBase::TemplateArgumentValue {
+                        value: value.value,
+                        enum_name: Some(enum_type.name.clone()),
+                      });
+        }
+      }
+    }
+    None
+  }
+
   /// Checks if the typedef `name` has a special meaning for the parser.
   fn parse_special_typedef(&self, name: &str) -> Option<CppTypeBase> {
     match name {
@@ -905,7 +1701,8 @@ impl<'a> CppParser<'a> {
         match p.get_kind() {
           EntityKind::ClassDecl |
           EntityKind::ClassTemplate |
-          EntityKind::StructDecl => {
+          EntityKind::StructDecl |
+          EntityKind::UnionDecl => {
             match get_full_name(p) {
               Ok(class_name) => (Some(class_name), Some(p)),
               Err(msg) => {
@@ -935,13 +1732,28 @@ impl<'a> CppParser<'a> {
     } else {
       return Err(format!("failed to get function type: {:?}", entity).into());
     };
-    let return_type_parsed = match self.parse_type(return_type, class_entity, Some(entity)) {
-      Ok(x) => x,
-      Err(msg) => {
-        return Err(format!("Can't parse return type: {}: {}",
-                           return_type.get_display_name(),
-                           msg)
-                       .into());
+    // a conversion operator returning a member pointer implements the "safe bool" idiom
+    // (e.g. `QMetaObject::Connection::operator RestrictedBool()`),
+    // so it's treated as `operator bool`
+    let is_safe_bool_conversion = entity.get_kind() == EntityKind::ConversionFunction &&
+                                  return_type.get_canonical_type().get_kind() ==
+                                  TypeKind::MemberPointer;
+    let return_type_parsed = if is_safe_bool_conversion {
+      CppType {
+        base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool),
+        indirection: CppTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
+      }
+    } else {
+      match self.parse_type(return_type, class_entity, Some(entity)) {
+        Ok(x) => x,
+        Err(msg) => {
+          return Err(format!("Can't parse return type: {}: {}",
+                             return_type.get_display_name(),
+                             msg)
+                         .into());
+        }
       }
     };
     let mut arguments = Vec::new();
@@ -957,15 +1769,7 @@ impl<'a> CppParser<'a> {
       return Err(format!("failed to get function arguments: {:?}", entity).into());
     };
     let template_arguments = match entity.get_kind() {
-      EntityKind::FunctionTemplate => {
-        if entity
-             .get_children()
-             .into_iter()
-             .any(|c| c.get_kind() == EntityKind::NonTypeTemplateParameter) {
-          return Err("Non-type template parameter is not supported".into());
-        }
-        get_template_arguments(entity)
-      }
+      EntityKind::FunctionTemplate => get_template_arguments(entity),
       _ => None,
     };
     let mut is_signal = false;
@@ -1019,6 +1823,9 @@ impl<'a> CppParser<'a> {
     let mut name = entity
       .get_name()
       .chain_err(|| "failed to get function name")?;
+    if is_safe_bool_conversion {
+      name = "operator bool".to_string();
+    }
     if name.contains('<') {
       lazy_static! {
         static ref REGEX: Regex = Regex::new(r"^([\w~]+)<[^<>]+>$").unwrap();
@@ -1147,6 +1954,33 @@ impl<'a> CppParser<'a> {
       }
       Some(token_strings.join(" "))
     };
+    let is_explicit = declaration_code
+      .as_ref()
+      .map_or(false, |code| code.split_whitespace().any(|x| x == "explicit"));
+    // `= delete` and `= default` are not exposed by libclang,
+    // so they are detected based on the declaration code
+    let definition_specifier = declaration_code
+      .as_ref()
+      .and_then(|code| code.rfind('=').map(|index| code[index + 1..].trim().to_string()));
+    let is_deleted = definition_specifier
+      .as_ref()
+      .map_or(false, |x| x == "delete");
+    let is_defaulted = definition_specifier
+      .as_ref()
+      .map_or(false, |x| x == "default");
+    let origin_location = get_origin_location(entity)?;
+    let doc = get_doc_comment_html(entity).map(|html| {
+      CppMethodDoc {
+        // documentation extracted from the header has no page,
+        // so the declaration's location is used to identify it
+        anchor: format!("{}:{}", origin_location.include_file_path, origin_location.line),
+        html: html,
+        mismatched_declaration: None,
+        url: String::new(),
+        cross_references: Vec::new(),
+      }
+    });
+    let exception_specification = parse_exception_specification(entity);
     Ok(CppMethod {
          name: name_with_namespace,
          operator: method_operator,
@@ -1160,6 +1994,10 @@ impl<'a> CppParser<'a> {
              },
              is_virtual: entity.is_virtual_method(),
              is_pure_virtual: entity.is_pure_virtual_method(),
+             is_final: entity
+               .get_children()
+               .iter()
+               .any(|c| c.get_kind() == EntityKind::FinalAttr),
              is_const: entity.is_const_method(),
              is_static: entity.is_static_method(),
              visibility: match entity
@@ -1172,6 +2010,9 @@ impl<'a> CppParser<'a> {
              // not all signals are detected here! see CppData::detect_signals_and_slots
              is_signal: is_signal,
              is_slot: false,
+             is_explicit: is_explicit,
+             is_deleted: is_deleted,
+             is_defaulted: is_defaulted,
              class_type: match self.find_type(|x| &x.name == &class_name) {
                Some(info) => info.default_class_type()?,
                None => return Err(format!("Unknown class type: {}", class_name).into()),
@@ -1182,13 +2023,16 @@ impl<'a> CppParser<'a> {
          },
          arguments: arguments,
          allows_variadic_arguments: allows_variadic_arguments,
+         exception_specification: exception_specification,
+         deprecation: get_deprecation(entity),
+         condition: None,
          return_type: return_type_parsed,
          include_file: self.entity_include_file(entity)?,
-         origin_location: Some(get_origin_location(entity)?),
+         origin_location: Some(origin_location),
          template_arguments: template_arguments,
          template_arguments_values: None,
          declaration_code: declaration_code,
-         doc: None,
+         doc: doc,
          inheritance_chain: Vec::new(),
          //is_fake_inherited_method: false,
          is_ffi_whitelisted: false,
@@ -1215,7 +2059,7 @@ impl<'a> CppParser<'a> {
                         .get_name()
                         .chain_err(|| "failed to get name of enum variant")?,
                       value: val.0,
-                      doc: None,
+                      doc: get_doc_comment_html(child),
                     });
       }
     }
@@ -1224,7 +2068,11 @@ impl<'a> CppParser<'a> {
          include_file: include_file,
          origin_location: get_origin_location(entity)?,
          kind: CppTypeKind::Enum { values: values },
-         doc: None,
+         doc: get_type_doc(entity),
+         deprecation: get_deprecation(entity),
+         condition: None,
+         qt_properties: Vec::new(),
+         enclosing_template_class: None,
        })
   }
 
@@ -1260,7 +2108,30 @@ impl<'a> CppParser<'a> {
        })
   }
 
-  /// Parses a class or a struct `entity`.
+  /// Parses fields of a class, a struct or a union `entity`.
+  /// Fields of anonymous unions are accessed as members of
+  /// the enclosing type, so they are added to the result as well.
+  fn parse_class_fields(&self, entity: Entity) -> Vec<CppClassField> {
+    let mut fields = Vec::new();
+    for child in entity.get_children() {
+      if child.get_kind() == EntityKind::FieldDecl {
+        match self.parse_class_field(child) {
+          Ok(field) => fields.push(field),
+          Err(err) => {
+            log::llog(log::DebugParserSkips,
+                      || format!("failed to parse class field: {}", err));
+            err.discard_expected();
+          }
+        }
+      }
+      if child.get_kind() == EntityKind::UnionDecl && child.get_name().is_none() {
+        fields.append(&mut self.parse_class_fields(child));
+      }
+    }
+    fields
+  }
+
+  /// Parses a class, a struct or a union `entity`.
   fn parse_class(&self, entity: Entity) -> Result<CppTypeData> {
     let include_file = self
       .entity_include_file(entity)
@@ -1270,7 +2141,7 @@ impl<'a> CppParser<'a> {
                            entity)
                  })?;
     let full_name = get_full_name(entity)?;
-    let mut fields = Vec::new();
+    let fields = self.parse_class_fields(entity);
     let mut bases = Vec::new();
     let using_directives = entity
       .get_children()
@@ -1300,16 +2171,6 @@ impl<'a> CppParser<'a> {
       })
       .collect();
     for child in entity.get_children() {
-      if child.get_kind() == EntityKind::FieldDecl {
-        match self.parse_class_field(child) {
-          Ok(field) => fields.push(field),
-          Err(err) => {
-            log::llog(log::DebugParserSkips,
-                      || format!("failed to parse class field: {}", err));
-            err.discard_expected();
-          }
-        }
-      }
       if child.get_kind() == EntityKind::BaseSpecifier {
         let base_type = match self.parse_type(child.get_type().unwrap(), Some(entity), None) {
           Ok(r) => r,
@@ -1327,11 +2188,8 @@ impl<'a> CppParser<'a> {
                      },
                    });
       }
-      if child.get_kind() == EntityKind::NonTypeTemplateParameter {
-        return Err("Non-type template parameter is not supported".into());
-      }
     }
-    let template_arguments = get_template_arguments(entity);
+    let mut template_arguments = get_template_arguments(entity);
     if entity.get_kind() == EntityKind::ClassTemplate {
       if template_arguments.is_none() {
         return Err(unexpected("missing template arguments").into());
@@ -1339,6 +2197,15 @@ impl<'a> CppParser<'a> {
     } else if template_arguments.is_some() {
       return Err(unexpected("unexpected template arguments").into());
     }
+    let mut enclosing_template_class = None;
+    if let Some(enclosing_class) = get_enclosing_template_class(entity) {
+      if template_arguments.is_some() {
+        return Err("Template types nested into template types are not supported".into());
+      }
+      // nested type is instantiated along with the enclosing template type
+      template_arguments = get_template_arguments(enclosing_class);
+      enclosing_template_class = Some(get_full_name(enclosing_class)?);
+    }
     let size = match entity.get_type() {
       Some(type1) => type1.get_sizeof().ok(),
       None => None,
@@ -1346,22 +2213,278 @@ impl<'a> CppParser<'a> {
     if template_arguments.is_none() && size.is_none() {
       return Err("Failed to request size, but the class is not a template class".into());
     }
-    if let Some(parent) = entity.get_semantic_parent() {
-      if get_template_arguments(parent).is_some() {
-        return Err("Types nested into template types are not supported".into());
+    let qt_properties = if entity.get_kind() == EntityKind::UnionDecl {
+      Vec::new()
+    } else {
+      self.parse_qt_properties(entity)
+    };
+    let kind = if entity.get_kind() == EntityKind::UnionDecl {
+      if template_arguments.is_some() {
+        return Err("Template unions are not supported".into());
       }
-    }
+      CppTypeKind::Union { fields: fields }
+    } else {
+      CppTypeKind::Class {
+        bases: bases,
+        fields: fields,
+        using_directives: using_directives,
+        template_arguments: template_arguments,
+      }
+    };
     Ok(CppTypeData {
          name: full_name,
          include_file: include_file,
          origin_location: get_origin_location(entity).unwrap(),
-         kind: CppTypeKind::Class {
-           bases: bases,
-           fields: fields,
-           using_directives: using_directives,
-           template_arguments: template_arguments,
-         },
-         doc: None,
+         kind: kind,
+         doc: get_type_doc(entity),
+         deprecation: get_deprecation(entity),
+         condition: None,
+         qt_properties: qt_properties,
+         enclosing_template_class: enclosing_template_class,
+       })
+  }
+
+  /// Parses `Q_PROPERTY` declarations in the body of class `entity`.
+  /// The macro expands to nothing, so the declarations can only be found
+  /// in the class's tokens.
+  fn parse_qt_properties(&self, entity: Entity) -> Vec<CppQtProperty> {
+    let range = match entity.get_range() {
+      Some(range) => range,
+      None => return Vec::new(),
+    };
+    let tokens: Vec<_> = range
+      .tokenize()
+      .into_iter()
+      .map(|token| token.get_spelling())
+      .collect();
+    let mut properties = Vec::new();
+    let mut brace_depth = 0;
+    let mut index = 0;
+    while index < tokens.len() {
+      match tokens[index].as_str() {
+        "{" => brace_depth += 1,
+        "}" => brace_depth -= 1,
+        // properties of nested classes are parsed separately
+        "Q_PROPERTY" if brace_depth == 1 && tokens.get(index + 1).map(|x| x.as_str()) == Some("(") => {
+          let start = index + 2;
+          let mut end = start;
+          let mut paren_depth = 1;
+          while end < tokens.len() {
+            match tokens[end].as_str() {
+              "(" => paren_depth += 1,
+              ")" => paren_depth -= 1,
+              _ => {}
+            }
+            if paren_depth == 0 {
+              break;
+            }
+            end += 1;
+          }
+          match parse_qt_property(&tokens[start..end]) {
+            Ok(property) => properties.push(property),
+            Err(err) => {
+              log::llog(log::DebugParserSkips,
+                        || format!("failed to parse Q_PROPERTY: {}", err));
+              err.discard_expected();
+            }
+          }
+          index = end;
+        }
+        _ => {}
+      }
+      index += 1;
+    }
+    properties
+  }
+
+  /// Parses a typedef or a type alias `entity`.
+  fn parse_typedef(&self, entity: Entity) -> Result<CppTypedef> {
+    let include_file = self
+      .entity_include_file(entity)
+      .chain_err(|| {
+                   format!("Origin of typedef is unknown: {}; entity: {:?}",
+                           get_full_name(entity).unwrap_or("?".into()),
+                           entity)
+                 })?;
+    let full_name = get_full_name(entity)?;
+    if self.parse_special_typedef(&full_name).is_some() {
+      return Err("Special typedef is handled by the type parser".into());
+    }
+    let context_class = match entity.get_semantic_parent() {
+      Some(parent) => {
+        if get_template_arguments(parent).is_some() {
+          return Err("Typedefs nested into template types are not supported".into());
+        }
+        match parent.get_kind() {
+          EntityKind::ClassDecl | EntityKind::StructDecl => Some(parent),
+          _ => None,
+        }
+      }
+      None => None,
+    };
+    let clang_type = entity
+      .get_typedef_underlying_type()
+      .chain_err(|| "failed to get underlying type of typedef")?;
+    let target_type = self
+      .parse_type(clang_type, context_class, None)
+      .chain_err(|| format!("failed to parse underlying type of typedef: {}", full_name))?;
+    if target_type.base.is_or_contains_template_parameter() {
+      return Err("Typedefs of template parameter types are not supported".into());
+    }
+    match target_type.base {
+      CppTypeBase::Class(CppTypeClassBase { ref name, .. }) |
+      CppTypeBase::Enum { ref name } => {
+        if name == &full_name {
+          return Err("Typedef refers to a type with the same name".into());
+        }
+      }
+      _ => {}
+    }
+    Ok(CppTypedef {
+         name: full_name,
+         include_file: include_file,
+         origin_location: get_origin_location(entity)?,
+         target_type: target_type,
+         doc: get_type_doc(entity),
+       })
+  }
+
+  /// Parses a global variable or a static data member `entity`.
+  fn parse_variable(&self, entity: Entity) -> Result<CppVariable> {
+    let include_file = self
+      .entity_include_file(entity)
+      .chain_err(|| {
+                   format!("Origin of variable is unknown: {}; entity: {:?}",
+                           get_full_name(entity).unwrap_or("?".into()),
+                           entity)
+                 })?;
+    let full_name = get_full_name(entity)?;
+    let (class_type, context_class) = match entity.get_semantic_parent() {
+      Some(parent) => {
+        match parent.get_kind() {
+          EntityKind::ClassDecl |
+          EntityKind::ClassTemplate |
+          EntityKind::StructDecl |
+          EntityKind::UnionDecl => {
+            if get_class_template_arguments(parent).is_some() {
+              return Err("Static members of template classes are not supported".into());
+            }
+            let class_name = get_full_name(parent)?;
+            let class_type = match self.find_type(|x| x.name == class_name) {
+              Some(info) => info.default_class_type()?,
+              None => return Err(format!("Unknown class type: {}", class_name).into()),
+            };
+            (Some(class_type), Some(parent))
+          }
+          _ => (None, None),
+        }
+      }
+      None => (None, None),
+    };
+    let clang_type = entity
+      .get_type()
+      .chain_err(|| "failed to get variable type")?;
+    let variable_type = self
+      .parse_type(clang_type, context_class, None)
+      .chain_err(|| format!("failed to parse variable type: {}", full_name))?;
+    let tokens: Vec<String> = entity
+      .get_range()
+      .chain_err(|| "failed to get range of the variable")?
+      .tokenize()
+      .into_iter()
+      .map(|token| token.get_spelling())
+      .take_while(|text| text != ";")
+      .collect();
+    let (declaration_tokens, initializer_tokens) = match tokens.iter().position(|x| x == "=") {
+      Some(index) => (&tokens[0..index], &tokens[index + 1..]),
+      None => (&tokens[..], &tokens[0..0]),
+    };
+    let is_constexpr = declaration_tokens.iter().any(|x| x == "constexpr");
+    let is_const = is_constexpr || clang_type.is_const_qualified();
+    let value = if is_const {
+      let mut initializer_tokens = initializer_tokens;
+      if initializer_tokens.len() >= 2 && initializer_tokens[0] == "{" &&
+         initializer_tokens[initializer_tokens.len() - 1] == "}" {
+        initializer_tokens = &initializer_tokens[1..initializer_tokens.len() - 1];
+      }
+      match parse_literal(initializer_tokens) {
+        Some(ref value) if !literal_fits_type(value, &variable_type) => None,
+        value => value,
+      }
+    } else {
+      None
+    };
+    Ok(CppVariable {
+         name: full_name,
+         class_type: class_type,
+         variable_type: variable_type,
+         is_const: is_const,
+         is_constexpr: is_constexpr,
+         value: value,
+         include_file: include_file,
+         origin_location: get_origin_location(entity)?,
+       })
+  }
+
+  /// Returns true if macro `name` was allowed in `Config`.
+  fn is_macro_allowed(&self, name: &str) -> bool {
+    self
+      .config
+      .macro_whitelist
+      .iter()
+      .any(|x| if x.ends_with('*') {
+             name.starts_with(&x[0..x.len() - 1])
+           } else {
+             x == name
+           })
+  }
+
+  /// Parses a macro definition `entity`. Only object-like macros
+  /// expanding to a literal or to another known macro are supported.
+  fn parse_macro(&self, entity: Entity) -> Result<CppMacro> {
+    let name = entity.get_name().chain_err(|| "macro has no name")?;
+    let include_file = self
+      .entity_include_file(entity)
+      .chain_err(|| format!("Origin of macro is unknown: {}", name))?;
+    let range = entity
+      .get_range()
+      .chain_err(|| "failed to get range of the macro")?;
+    let end_offset = range.get_end().get_file_location().offset;
+    // clang may include the token following the definition into the range
+    let tokens: Vec<_> = range
+      .tokenize()
+      .into_iter()
+      .filter(|token| token.get_location().get_file_location().offset <= end_offset)
+      .collect();
+    if tokens.len() < 2 {
+      return Err("Empty macros are not supported".into());
+    }
+    let name_offset = tokens[0].get_location().get_file_location().offset;
+    if tokens[1].get_spelling() == "(" &&
+       tokens[1].get_location().get_file_location().offset == name_offset + name.len() as u32 {
+      return Err("Function-like macros are not supported".into());
+    }
+    let value_tokens: Vec<_> = tokens[1..].iter().map(|token| token.get_spelling()).collect();
+    let value = if value_tokens.len() == 1 {
+      self
+        .macros
+        .iter()
+        .find(|x| x.name == value_tokens[0])
+        .map(|x| x.value.clone())
+    } else {
+      None
+    };
+    let value = match value.or_else(|| parse_literal(&value_tokens)) {
+      Some(value) => value,
+      None => {
+        return Err(format!("Macro value is not a literal: {}", value_tokens.join(" ")).into())
+      }
+    };
+    Ok(CppMacro {
+         name: name,
+         value: value,
+         include_file: include_file,
+         origin_location: get_origin_location(entity)?,
        })
   }
 
@@ -1459,7 +2582,8 @@ impl<'a> CppParser<'a> {
       }
       EntityKind::ClassDecl |
       EntityKind::ClassTemplate |
-      EntityKind::StructDecl => {
+      EntityKind::StructDecl |
+      EntityKind::UnionDecl => {
         if entity.get_accessibility() == Some(Accessibility::Private) {
           return; // skipping private stuff
         }
@@ -1490,6 +2614,83 @@ impl<'a> CppParser<'a> {
           }
         }
       }
+      EntityKind::MacroDefinition => {
+        match entity.get_name() {
+          Some(ref name) if self.is_macro_allowed(name) => {}
+          _ => return,
+        }
+        match self.parse_macro(entity) {
+          Ok(r) => {
+            if let Some(index) = self.macros.iter().position(|x| x.name == r.name) {
+              log::llog(log::DebugParser,
+                        || format!("macro is redefined: {:?}", entity));
+              self.macros[index] = r;
+            } else {
+              self.macros.push(r);
+            }
+          }
+          Err(msg) => {
+            log::llog(log::DebugParserSkips, || {
+              format!("Failed to parse macro: {}\nentity: {:?}\nerror: {}\n",
+                      entity.get_name().unwrap_or("?".into()),
+                      entity,
+                      msg)
+            });
+            msg.discard_expected();
+          }
+        }
+      }
+      EntityKind::VarDecl => {
+        if entity
+             .get_accessibility()
+             .unwrap_or(Accessibility::Public) != Accessibility::Public {
+          return; // skipping private and protected stuff
+        }
+        match self.parse_variable(entity) {
+          Ok(r) => {
+            if self.variables.iter().any(|x| x.name == r.name) {
+              log::llog(log::DebugParser,
+                        || format!("repeating variable declaration: {:?}", entity));
+            } else {
+              self.variables.push(r);
+            }
+          }
+          Err(msg) => {
+            log::llog(log::DebugParserSkips, || {
+              format!("Failed to parse variable: {}\nentity: {:?}\nerror: {}\n",
+                      get_full_name(entity).unwrap_or("?".into()),
+                      entity,
+                      msg)
+            });
+            msg.discard_expected();
+          }
+        }
+      }
+      EntityKind::TypedefDecl |
+      EntityKind::TypeAliasDecl => {
+        if entity.get_accessibility() == Some(Accessibility::Private) {
+          return; // skipping private stuff
+        }
+        match self.parse_typedef(entity) {
+          Ok(r) => {
+            if self.typedefs.iter().any(|x| x.name == r.name) {
+              log::llog(log::DebugParser,
+                        || format!("repeating typedef declaration: {:?}", entity));
+            } else {
+              self.typedefs.push(r);
+            }
+          }
+          Err(msg) => {
+            log::llog(log::DebugParserSkips, || {
+              format!("Failed to parse typedef: {}\nentity: {:?}\nerror: {}\n",
+                      get_full_name(entity).unwrap_or("?".into()),
+                      entity,
+                      msg)
+            });
+            msg.discard_expected();
+          }
+        }
+      }
       _ => {}
     }
     match entity.get_kind() {
@@ -1497,6 +2698,7 @@ impl<'a> CppParser<'a> {
       EntityKind::Namespace |
       EntityKind::StructDecl |
       EntityKind::ClassDecl |
+      EntityKind::UnionDecl |
       EntityKind::UnexposedDecl |
       EntityKind::ClassTemplate => {
         for c in entity.get_children() {
@@ -1564,6 +2766,7 @@ impl<'a> CppParser<'a> {
       EntityKind::Namespace |
       EntityKind::StructDecl |
       EntityKind::ClassDecl |
+      EntityKind::UnionDecl |
       EntityKind::UnexposedDecl |
       EntityKind::ClassTemplate => {
         for c in entity.get_children() {
@@ -1585,6 +2788,13 @@ impl<'a> CppParser<'a> {
       CppTypeBase::SpecificNumeric { .. } |
       CppTypeBase::PointerSizedInteger { .. } |
       CppTypeBase::TemplateParameter { .. } => {}
+      CppTypeBase::TemplateArgumentValue { ref enum_name, .. } => {
+        if let Some(ref name) = *enum_name {
+          if self.find_type(|x| &x.name == name).is_none() {
+            return Err(format!("unknown type: {}", name).into());
+          }
+        }
+      }
       CppTypeBase::Enum { ref name } => {
         if self.find_type(|x| &x.name == name).is_none() {
           return Err(format!("unknown type: {}", name).into());
@@ -1593,6 +2803,7 @@ impl<'a> CppParser<'a> {
       CppTypeBase::Class(CppTypeClassBase {
                            ref name,
                            ref template_arguments,
+                           ..
                          }) => {
         if self.find_type(|x| &x.name == name).is_none() {
           return Err(format!("unknown type: {}", name).into());
@@ -1609,7 +2820,12 @@ impl<'a> CppParser<'a> {
                                      ref return_type,
                                      ref arguments,
                                      ..
-                                   }) => {
+                                   }) |
+      CppTypeBase::StdFunction(CppFunctionPointerType {
+                                 ref return_type,
+                                 ref arguments,
+                                 ..
+                               }) => {
         if let Err(msg) = self.check_type_integrity(return_type) {
           return Err(msg);
         }
@@ -1689,4 +2905,38 @@ impl<'a> CppParser<'a> {
     }
     (good_methods, good_types)
   }
+
+  /// Returns variables that don't refer to any unknown types.
+  fn check_variables_integrity(&self) -> Vec<CppVariable> {
+    self
+      .variables
+      .iter()
+      .filter(|variable| {
+                if let Err(msg) = self.check_type_integrity(&variable.variable_type) {
+                  log::llog(log::DebugParserSkips,
+                            || format!("Variable is removed: {}: {}", variable.name, msg));
+                  return false;
+                }
+                true
+              })
+      .cloned()
+      .collect()
+  }
+
+  /// Returns typedefs that don't refer to any unknown types.
+  fn check_typedefs_integrity(&self) -> Vec<CppTypedef> {
+    self
+      .typedefs
+      .iter()
+      .filter(|typedef| {
+                if let Err(msg) = self.check_type_integrity(&typedef.target_type) {
+                  log::llog(log::DebugParserSkips,
+                            || format!("Typedef is removed: {}: {}", typedef.name, msg));
+                  return false;
+                }
+                true
+              })
+      .cloned()
+      .collect()
+  }
 }
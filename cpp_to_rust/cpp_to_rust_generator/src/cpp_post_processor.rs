@@ -1,8 +1,10 @@
 use cpp_data::{CppDataWithDeps, CppData, ParserCppData, ProcessedCppData, CppTypeAllocationPlace,
                CppTypeKind, CppVisibility, CppTemplateInstantiations, CppTemplateInstantiation,
-               CppTypeData, CppBaseSpecifier};
-use cpp_method::{CppMethod, CppMethodKind, CppMethodClassMembership};
+               CppTypeData, CppBaseSpecifier, CppContainerIterator};
+use cpp_method::{CppMethod, CppMethodKind, CppMethodClassMembership, CppOperator,
+                 CppExceptionSpecification};
 use cpp_type::{CppType, CppTypeClassBase, CppTypeBase, CppTypeIndirection};
+use cpp_multi_target::and_conditions;
 use common::log;
 use common::errors::{Result, unexpected};
 
@@ -32,12 +34,18 @@ pub fn cpp_post_process<'a>(parser_data: ParserCppData,
     processor
       .choose_allocation_places(allocation_place_overrides, &inherited_methods)?;
 
+  let trivially_copyable_types = processor.detect_trivially_copyable_types(&inherited_methods);
+
+  let container_iterators = processor.detect_container_iterators(&inherited_methods);
+
   let result = ProcessedCppData {
     implicit_destructors: implicit_destructors,
     template_instantiations: processor.find_template_instantiations(),
     inherited_methods: inherited_methods,
     signal_argument_types: processor.detect_signal_argument_types()?,
     type_allocation_places: type_allocation_places,
+    trivially_copyable_types: trivially_copyable_types,
+    container_iterators: container_iterators,
   };
 
   Ok(CppDataWithDeps {
@@ -84,13 +92,290 @@ impl<'a> CppPostProcessor<'a> {
   }
 
 
+  /// Checks if specified class can be copied, i.e. its copy constructor
+  /// is not deleted or private, and all its bases and fields are copyable.
+  /// The copy constructor is implicitly deleted if the class declares
+  /// a move constructor or a move assignment operator.
+  pub fn is_copyable(&self, class_name: &str) -> bool {
+    let class_methods: Vec<_> = self
+      .parser_data
+      .methods
+      .iter()
+      .filter(|method| method.class_name().map_or(false, |name| name == class_name))
+      .collect();
+    let copy_constructor = class_methods
+      .iter()
+      .find(|method| method.is_copy_constructor());
+    match copy_constructor {
+      Some(method) => {
+        let is_usable = method
+          .class_membership
+          .as_ref()
+          .map_or(false, |info| !info.is_deleted && info.visibility == CppVisibility::Public);
+        if !is_usable {
+          return false;
+        }
+      }
+      None => {
+        if class_methods.iter().any(|method| method.is_move_operation()) {
+          return false;
+        }
+      }
+    }
+    let is_copyable_type = |cpp_type: &CppType| {
+      if cpp_type.indirection == CppTypeIndirection::None {
+        if let CppTypeBase::Class(ref class_type) = cpp_type.base {
+          return self.is_copyable(&class_type.name);
+        }
+      }
+      true
+    };
+    match self
+            .parser_data
+            .types
+            .iter()
+            .find(|t| &t.name == class_name)
+            .map(|t| &t.kind) {
+      Some(&CppTypeKind::Class {
+             ref bases,
+             ref fields,
+             ..
+           }) => {
+        bases
+          .iter()
+          .all(|base| is_copyable_type(&base.base_type)) &&
+        fields
+          .iter()
+          .all(|field| is_copyable_type(&field.field_type))
+      }
+      Some(&CppTypeKind::Union { ref fields }) => {
+        fields
+          .iter()
+          .all(|field| is_copyable_type(&field.field_type))
+      }
+      _ => true,
+    }
+  }
+
+  /// Detects classes that can be copied by copying their memory
+  /// (trivially copyable classes). A class is considered trivially copyable
+  /// if it's not a template, doesn't have virtual methods, virtual bases,
+  /// declared destructor, copy or move constructors or assignment operators
+  /// (except for public explicitly defaulted ones),
+  /// and all its bases and fields are trivially copyable.
+  fn detect_trivially_copyable_types(&self, inherited_methods: &[CppMethod]) -> Vec<String> {
+    let is_known = |name: &str, result: &[String]| {
+      result.iter().any(|x| x == name) ||
+      self
+        .dependencies
+        .iter()
+        .any(|data| {
+               data
+                 .processed
+                 .trivially_copyable_types
+                 .iter()
+                 .any(|x| x == name)
+             })
+    };
+    let is_trivial_type = |cpp_type: &CppType, result: &[String]| match cpp_type.indirection {
+      CppTypeIndirection::Ptr |
+      CppTypeIndirection::PtrPtr => true,
+      CppTypeIndirection::None => {
+        match cpp_type.base {
+          CppTypeBase::BuiltInNumeric(..) |
+          CppTypeBase::SpecificNumeric(..) |
+          CppTypeBase::PointerSizedInteger { .. } |
+          CppTypeBase::Enum { .. } |
+          CppTypeBase::FunctionPointer(..) => true,
+          CppTypeBase::Class(ref class_type) => {
+            class_type.template_arguments.is_none() && is_known(&class_type.name, result)
+          }
+          _ => false,
+        }
+      }
+      _ => false,
+    };
+    let is_copy_or_destroy_method = |method: &CppMethod, class_name: &str| {
+      if let Some(ref info) = method.class_membership {
+        if &info.class_type.name != class_name {
+          return false;
+        }
+        if info.is_defaulted && info.visibility == CppVisibility::Public {
+          return false;
+        }
+        if info.kind.is_destructor() {
+          return true;
+        }
+        let receives_same_class = method
+          .arguments
+          .get(0)
+          .map_or(false, |arg| {
+            (arg.argument_type.indirection == CppTypeIndirection::Ref ||
+             arg.argument_type.indirection == CppTypeIndirection::RValueRef) &&
+            arg.argument_type.base == CppTypeBase::Class(info.class_type.clone())
+          });
+        receives_same_class &&
+        (info.kind.is_constructor() || method.operator == Some(CppOperator::Assignment))
+      } else {
+        false
+      }
+    };
+
+    let mut result = Vec::new();
+    // repeat until no new types are found because classes
+    // may depend on each other
+    loop {
+      let mut found_new = false;
+      for type1 in &self.parser_data.types {
+        if result.contains(&type1.name) {
+          continue;
+        }
+        let (bases, fields) = match type1.kind {
+          CppTypeKind::Class {
+            ref bases,
+            ref fields,
+            ref template_arguments,
+            ..
+          } => {
+            if template_arguments.is_some() {
+              continue;
+            }
+            (&bases[..], fields)
+          }
+          CppTypeKind::Union { ref fields } => (&[][..], fields),
+          CppTypeKind::Enum { .. } => continue,
+        };
+        if !self.is_copyable(&type1.name) ||
+           self.has_virtual_methods(&type1.name, inherited_methods) ||
+           self
+             .parser_data
+             .methods
+             .iter()
+             .any(|m| is_copy_or_destroy_method(m, &type1.name)) {
+          continue;
+        }
+        let bases_are_trivial = bases
+          .iter()
+          .all(|base| !base.is_virtual && is_trivial_type(&base.base_type, &result));
+        let fields_are_trivial = fields
+          .iter()
+          .all(|field| is_trivial_type(&field.field_type, &result));
+        if bases_are_trivial && fields_are_trivial {
+          result.push(type1.name.clone());
+          found_new = true;
+        }
+      }
+      if !found_new {
+        break;
+      }
+    }
+    result
+  }
+
+  /// Detects container classes that have `constBegin` and `constEnd`
+  /// (or `begin` and `end`) methods returning an iterator type
+  /// that provides `operator*`, prefix `operator++` and
+  /// `operator!=` (or `operator==`) as public member functions.
+  fn detect_container_iterators(&self,
+                                inherited_methods: &[CppMethod])
+                                -> Vec<CppContainerIterator> {
+    let own_methods = || {
+      self
+        .parser_data
+        .methods
+        .iter()
+        .chain(inherited_methods.iter())
+    };
+    // returns name of the iterator class returned by
+    // method `method_name` of class `class_name`
+    let find_iterator_getter = |class_name: &str, method_name: &str| {
+      own_methods()
+        .filter_map(|method| {
+          if method.name != method_name || !method.arguments.is_empty() {
+            return None;
+          }
+          if let Some(ref info) = method.class_membership {
+            if &info.class_type.name != class_name || !info.is_const || info.is_static ||
+               info.visibility != CppVisibility::Public {
+              return None;
+            }
+            if method.return_type.indirection != CppTypeIndirection::None {
+              return None;
+            }
+            if let CppTypeBase::Class(ref iterator_type) = method.return_type.base {
+              return Some(iterator_type.name.clone());
+            }
+          }
+          None
+        })
+        .next()
+    };
+    let has_operator = |class_name: &str, operators: &[CppOperator], arguments_count: usize| {
+      once(&self.parser_data)
+        .chain(self.dependencies.iter().map(|data| &data.parser))
+        .flat_map(|data| data.methods.iter())
+        .chain(inherited_methods.iter())
+        .any(|method| {
+          if let Some(ref info) = method.class_membership {
+            &info.class_type.name == class_name && info.visibility == CppVisibility::Public &&
+            !info.is_static && method.arguments.len() == arguments_count &&
+            method
+              .operator
+              .as_ref()
+              .map_or(false, |op| operators.contains(op))
+          } else {
+            false
+          }
+        })
+    };
+
+    let mut result = Vec::new();
+    for type1 in &self.parser_data.types {
+      if !type1.is_class() {
+        continue;
+      }
+      for &(begin_method, end_method) in &[("constBegin", "constEnd"), ("begin", "end")] {
+        let iterator_class = match (find_iterator_getter(&type1.name, begin_method),
+                                    find_iterator_getter(&type1.name, end_method)) {
+          (Some(begin_type), Some(end_type)) => {
+            if begin_type != end_type {
+              continue;
+            }
+            begin_type
+          }
+          _ => continue,
+        };
+        if has_operator(&iterator_class, &[CppOperator::Indirection], 0) &&
+           has_operator(&iterator_class, &[CppOperator::PrefixIncrement], 0) &&
+           has_operator(&iterator_class,
+                        &[CppOperator::NotEqualTo, CppOperator::EqualTo],
+                        1) {
+          log::llog(log::DebugGeneral, || {
+            format!("Found container iterator: {}::{} ({})",
+                    type1.name,
+                    begin_method,
+                    iterator_class)
+          });
+          result.push(CppContainerIterator {
+                        container_class: type1.name.clone(),
+                        iterator_class: iterator_class,
+                        begin_method: begin_method.to_string(),
+                        end_method: end_method.to_string(),
+                      });
+          break;
+        }
+      }
+    }
+    result
+  }
+
   /// Adds destructors for every class that does not have explicitly
   /// defined destructor, allowing to create wrappings for
   /// destructors implicitly available in C++.
   fn ensure_explicit_destructors(&self, inherited_methods: &[CppMethod]) -> Result<Vec<CppMethod>> {
     let mut methods = Vec::new();
     for type1 in &self.parser_data.types {
-      if let CppTypeKind::Class { .. } = type1.kind {
+      if type1.is_class() {
         let class_name = &type1.name;
         let found_destructor = self
           .parser_data
@@ -105,17 +390,24 @@ impl<'a> CppPostProcessor<'a> {
                                                   class_type: type1.default_class_type()?,
                                                   is_virtual: is_virtual,
                                                   is_pure_virtual: false,
+                                                  is_final: false,
                                                   is_const: false,
                                                   is_static: false,
                                                   visibility: CppVisibility::Public,
                                                   is_signal: false,
                                                   is_slot: false,
+                                                  is_explicit: false,
+                                                  is_deleted: false,
+                                                  is_defaulted: false,
                                                   kind: CppMethodKind::Destructor,
                                                 }),
                          operator: None,
                          return_type: CppType::void(),
                          arguments: vec![],
                          allows_variadic_arguments: false,
+                         exception_specification: CppExceptionSpecification::NoThrow,
+                         deprecation: None,
+                         condition: type1.condition.clone(),
                          include_file: type1.include_file.clone(),
                          origin_location: None,
                          template_arguments: None,
@@ -143,6 +435,7 @@ impl<'a> CppPostProcessor<'a> {
       if let CppTypeBase::Class(CppTypeClassBase {
                                   ref name,
                                   ref template_arguments,
+                                  ..
                                 }) = type1.base {
         if let Some(ref template_arguments) = *template_arguments {
           if !template_arguments
@@ -223,10 +516,140 @@ impl<'a> CppPostProcessor<'a> {
           check_type(&field.field_type, &self.dependencies, &mut result);
         }
       }
+      if let CppTypeKind::Union { ref fields } = t.kind {
+        for field in fields {
+          check_type(&field.field_type, &self.dependencies, &mut result);
+        }
+      }
     }
+    for typedef in &self.parser_data.typedefs {
+      check_type(&typedef.target_type, &self.dependencies, &mut result);
+    }
+    for variable in &self.parser_data.variables {
+      check_type(&variable.variable_type, &self.dependencies, &mut result);
+    }
+    self.remove_mismatched_template_instantiations(&mut result);
+    self.add_nested_template_instantiations(&mut result);
     result
   }
 
+  /// Removes instantiations that don't match the template declaration of the class,
+  /// i.e. have too many arguments or have a type argument in place of
+  /// a non-type template parameter (or vice versa).
+  fn remove_mismatched_template_instantiations(&self,
+                                               result: &mut Vec<CppTemplateInstantiations>) {
+    let all_types: Vec<&CppTypeData> = once(&self.parser_data.types)
+      .chain(self.dependencies.iter().map(|d| &d.parser.types))
+      .flat_map(|types| types.iter())
+      .collect();
+    for item in result.iter_mut() {
+      let declaration = match all_types
+              .iter()
+              .find(|t| t.name == item.class_name) {
+        Some(type1) => {
+          if let CppTypeKind::Class { ref template_arguments, .. } = type1.kind {
+            template_arguments.clone()
+          } else {
+            None
+          }
+        }
+        None => None,
+      };
+      let declaration = match declaration {
+        Some(declaration) => declaration,
+        None => continue,
+      };
+      let class_name = &item.class_name;
+      item
+        .instantiations
+        .retain(|ins| {
+          let is_valid = ins.template_arguments.len() <= declaration.names.len() &&
+                         ins
+                           .template_arguments
+                           .iter()
+                           .enumerate()
+                           .all(|(index, arg)| {
+            let is_value = if let CppTypeBase::TemplateArgumentValue { .. } = arg.base {
+              true
+            } else {
+              false
+            };
+            is_value == declaration.value_parameters.contains(&index)
+          });
+          if !is_valid {
+            log::llog(log::DebugParser, || {
+              format!("Template instantiation doesn't match declaration: {}<{:?}>",
+                      class_name,
+                      ins.template_arguments)
+            });
+          }
+          is_valid
+        });
+    }
+    result.retain(|item| !item.instantiations.is_empty());
+  }
+
+  /// Adds instantiations of types nested into template classes
+  /// (like `QList<T>::iterator`) for each found instantiation
+  /// of the enclosing template class.
+  fn add_nested_template_instantiations(&self, result: &mut Vec<CppTemplateInstantiations>) {
+    let all_types: Vec<&CppTypeData> = once(&self.parser_data.types)
+      .chain(self.dependencies.iter().map(|d| &d.parser.types))
+      .flat_map(|types| types.iter())
+      .collect();
+    let mut nested_result = Vec::new();
+    for item in result.iter() {
+      let enclosing_arguments = match all_types
+              .iter()
+              .find(|t| t.name == item.class_name) {
+        Some(type1) => {
+          if let CppTypeKind::Class { ref template_arguments, .. } = type1.kind {
+            template_arguments.clone()
+          } else {
+            None
+          }
+        }
+        None => None,
+      };
+      if enclosing_arguments.is_none() {
+        continue;
+      }
+      let prefix = format!("{}::", item.class_name);
+      for type1 in &all_types {
+        if !type1.name.starts_with(&prefix) {
+          continue;
+        }
+        if let CppTypeKind::Class { ref template_arguments, .. } = type1.kind {
+          // nested types share template arguments with the enclosing type
+          if template_arguments == &enclosing_arguments {
+            log::llog(log::DebugParser, || {
+              format!("Found nested template instantiations: {} ({} instantiations)",
+                      type1.name,
+                      item.instantiations.len())
+            });
+            nested_result.push(CppTemplateInstantiations {
+                                 class_name: type1.name.clone(),
+                                 instantiations: item.instantiations.clone(),
+                               });
+          }
+        }
+      }
+    }
+    for nested_item in nested_result {
+      if let Some(existing_item) = result
+           .iter_mut()
+           .find(|x| x.class_name == nested_item.class_name) {
+        for ins in nested_item.instantiations {
+          if !existing_item.instantiations.contains(&ins) {
+            existing_item.instantiations.push(ins);
+          }
+        }
+        continue;
+      }
+      result.push(nested_item);
+    }
+  }
+
 
   fn detect_inherited_methods2(&self) -> Result<Vec<CppMethod>> {
     let mut remaining_classes: Vec<&CppTypeData> = self
@@ -352,6 +775,8 @@ impl<'a> CppPostProcessor<'a> {
                 new_method.include_file = class.include_file.clone();
                 new_method.origin_location = None;
                 new_method.declaration_code = None;
+                new_method.condition = and_conditions(method.condition.as_ref(),
+                                                      class.condition.as_ref());
                 new_method.inheritance_chain.push((*base).clone());
                 //new_method.is_fake_inherited_method = true;
                 log::llog(log::DebugInheritance,
@@ -459,6 +884,7 @@ impl<'a> CppPostProcessor<'a> {
       if let CppTypeBase::Class(CppTypeClassBase {
                                   ref name,
                                   ref template_arguments,
+                                  ..
                                 }) = cpp_type.base {
         if !data.contains_key(name) {
           data.insert(name.clone(), TypeStats::default());
@@ -515,6 +941,13 @@ impl<'a> CppPostProcessor<'a> {
       let name = &type1.name;
       let result = if overrides.contains_key(name) {
         overrides[name].clone()
+      } else if !self.is_copyable(name) {
+        log::llog(log::DebugAllocationPlace,
+                  || format!("Type '{}' is not copyable", name));
+        CppTypeAllocationPlace::Heap
+      } else if let CppTypeKind::Union { .. } = type1.kind {
+        // unions can't have virtual methods and are usually passed by value
+        CppTypeAllocationPlace::Stack
       } else if let Some(ref stats) = data.get(name) {
         if stats.has_virtual_methods {
           CppTypeAllocationPlace::Heap
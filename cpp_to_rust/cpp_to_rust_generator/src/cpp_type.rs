@@ -5,6 +5,7 @@ use cpp_ffi_data::{CppFfiType, CppIndirectionChange};
 use common::errors::{Result, ChainErr, Error, unexpected};
 use common::string_utils::JoinWithSeparator;
 use common::utils::MapIfOk;
+use std::hash::{Hash, Hasher};
 
 /// C++ type variants based on indirection
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
@@ -64,7 +65,7 @@ pub enum CppSpecificNumericTypeKind {
 }
 
 /// Information about base C++ class type
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Debug, Clone)]
 #[derive(Serialize, Deserialize)]
 pub struct CppTypeClassBase {
   /// Name, including namespaces and nested classes
@@ -73,6 +74,10 @@ pub struct CppTypeClassBase {
   /// arguments in this type,
   /// like [QString, int] in QHash<QString, int>
   pub template_arguments: Option<Vec<CppType>>,
+  /// For types nested into a template class (like `QList<T>::iterator`),
+  /// name of the enclosing template class (`QList`). Template arguments
+  /// of such types belong to the enclosing class.
+  pub enclosing_template_class: Option<String>,
 }
 
 /// Information about a C++ function pointer type
@@ -141,6 +146,17 @@ pub enum CppTypeBase {
   },
   /// Function pointer type
   FunctionPointer(CppFunctionPointerType),
+  /// `std::function` type with the specified signature,
+  /// like `std::function<void(int)>`
+  StdFunction(CppFunctionPointerType),
+  /// Value of a non-type template argument, like `3`
+  /// in `std::array<int, 3>`
+  TemplateArgumentValue {
+    /// Integral value of the argument
+    value: i64,
+    /// Name of the enum type if the value is an enum constant
+    enum_name: Option<String>,
+  },
 }
 
 /// Information about a C++ type
@@ -282,6 +298,19 @@ impl CppTypeClassBase {
         for arg in args {
           arg_texts.push(arg.to_cpp_code(None)?);
         }
+        if let Some(ref enclosing_name) = self.enclosing_template_class {
+          let prefix = format!("{}::", enclosing_name);
+          if !self.name.starts_with(&prefix) {
+            return Err(unexpected(format!("nested type name {} doesn't start with {}",
+                                          self.name,
+                                          prefix))
+                           .into());
+          }
+          return Ok(format!("typename {}< {} >::{}",
+                            enclosing_name,
+                            arg_texts.join(", "),
+                            &self.name[prefix.len()..]));
+        }
         Ok(format!("{}< {} >", self.name, arg_texts.join(", ")))
       }
       None => Ok(self.name.clone()),
@@ -323,6 +352,7 @@ impl CppTypeClassBase {
     }
            None => None,
          },
+         enclosing_template_class: self.enclosing_template_class.clone(),
        })
   }
 
@@ -385,6 +415,16 @@ impl CppTypeBase {
           false
         }
       }
+      CppTypeBase::StdFunction(CppFunctionPointerType {
+                                 ref return_type,
+                                 ref arguments,
+                                 ..
+                               }) => {
+        return_type.base.is_or_contains_template_parameter() ||
+        arguments
+          .iter()
+          .any(|arg| arg.base.is_or_contains_template_parameter())
+      }
       _ => false,
     }
   }
@@ -406,6 +446,15 @@ impl CppTypeBase {
       CppTypeBase::TemplateParameter { .. } => {
         Err("template parameters are not allowed in C++ code generator".into())
       }
+      CppTypeBase::TemplateArgumentValue {
+        ref value,
+        ref enum_name,
+      } => {
+        Ok(match *enum_name {
+             Some(ref enum_name) => format!("static_cast<{}>({})", enum_name, value),
+             None => value.to_string(),
+           })
+      }
       CppTypeBase::FunctionPointer(CppFunctionPointerType {
                                      ref return_type,
                                      ref arguments,
@@ -427,6 +476,22 @@ impl CppTypeBase {
           return Err("function_pointer_inner_text argument is missing".into());
         }
       }
+      CppTypeBase::StdFunction(CppFunctionPointerType {
+                                 ref return_type,
+                                 ref arguments,
+                                 ref allows_variadic_arguments,
+                               }) => {
+        if *allows_variadic_arguments {
+          return Err("std::function with variadic arguments is not supported".into());
+        }
+        let mut arg_texts = Vec::new();
+        for arg in arguments {
+          arg_texts.push(arg.to_cpp_code(None)?);
+        }
+        Ok(format!("std::function<{}({})>",
+                   return_type.as_ref().to_cpp_code(None)?,
+                   arg_texts.join(", ")))
+      }
     }
   }
 
@@ -443,6 +508,13 @@ impl CppTypeBase {
          CppTypeBase::TemplateParameter { .. } => {
       return Err("template parameters are not allowed to have captions".into());
     }
+         CppTypeBase::TemplateArgumentValue { ref value, .. } => {
+           if *value < 0 {
+             format!("minus{}", -value)
+           } else {
+             value.to_string()
+           }
+         }
          CppTypeBase::FunctionPointer(CppFunctionPointerType {
                                         ref return_type,
                                         ref arguments,
@@ -460,6 +532,23 @@ impl CppTypeBase {
              }
            }
          }
+         CppTypeBase::StdFunction(CppFunctionPointerType {
+                                    ref return_type,
+                                    ref arguments,
+                                    ..
+                                  }) => {
+           match strategy {
+             TypeCaptionStrategy::Short => "function".to_string(),
+             TypeCaptionStrategy::Full => {
+               format!("{}_function_{}",
+                       return_type.caption(strategy.clone())?,
+                       arguments
+                         .iter()
+                         .map_if_ok(|x| x.caption(strategy.clone()))?
+                         .join("_"))
+             }
+           }
+         }
 
        })
   }
@@ -488,6 +577,44 @@ impl CppTypeBase {
   }
 }
 
+/// Checks that arguments and return type of a function pointer or
+/// `std::function` type can be passed through FFI without conversions.
+fn check_ffi_function_type(function: &CppFunctionPointerType) -> Result<()> {
+  if function.allows_variadic_arguments {
+    return Err("function pointers with variadic arguments are not supported".into());
+  }
+  let mut all_types: Vec<&CppType> = function.arguments.iter().collect();
+  all_types.push(function.return_type.as_ref());
+  for arg in all_types {
+    match arg.base {
+      CppTypeBase::TemplateParameter { .. } => {
+        return Err("function pointers containing template parameters are not supported".into());
+      }
+      CppTypeBase::FunctionPointer(..) |
+      CppTypeBase::StdFunction(..) => {
+        return Err("function pointers containing nested function pointers are not supported"
+                     .into());
+      }
+      _ => {}
+    }
+    match arg.indirection {
+      CppTypeIndirection::Ref |
+      CppTypeIndirection::PtrRef |
+      CppTypeIndirection::RValueRef => {
+        return Err("Function pointers containing references are not supported".into());
+      }
+      CppTypeIndirection::Ptr |
+      CppTypeIndirection::PtrPtr => {}
+      CppTypeIndirection::None => {
+        if arg.base.is_class() {
+          return Err("Function pointers containing classes by value are not supported".into());
+        }
+      }
+    }
+  }
+  Ok(())
+}
+
 /// Context of usage for a C++ type
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CppTypeRole {
@@ -552,52 +679,38 @@ impl CppType {
       CppTypeBase::TemplateParameter { .. } => {
         return Err(Error::from("template parameters cannot be expressed in FFI")).chain_err(&err);
       }
-      CppTypeBase::FunctionPointer(CppFunctionPointerType {
-                                     ref return_type,
-                                     ref arguments,
-                                     ref allows_variadic_arguments,
-                                   }) => {
-        if *allows_variadic_arguments {
-          return Err(Error::from("function pointers with variadic arguments are not supported"))
-                   .chain_err(&err);
-        }
-        let mut all_types: Vec<&CppType> = arguments.iter().collect();
-        all_types.push(return_type.as_ref());
-        for arg in all_types {
-          match arg.base {
-            CppTypeBase::TemplateParameter { .. } => {
-              return Err(Error::from("function pointers containing template parameters are not \
-                                      supported"))
-                         .chain_err(&err);
-            }
-            CppTypeBase::FunctionPointer(..) => {
-              return Err(Error::from("function pointers containing nested function pointers are \
-                                      not supported"))
-                         .chain_err(&err);
-            }
-            _ => {}
-          }
-          match arg.indirection {
-            CppTypeIndirection::Ref |
-            CppTypeIndirection::PtrRef |
-            CppTypeIndirection::RValueRef => {
-              return Err(Error::from("Function pointers containing references are not supported"))
+      CppTypeBase::TemplateArgumentValue { .. } => {
+        return Err(Error::from("template argument values cannot be expressed in FFI"))
+                 .chain_err(&err);
+      }
+      CppTypeBase::FunctionPointer(ref function) => {
+        check_ffi_function_type(function).chain_err(&err)?;
+        return Ok(CppFfiType {
+                    ffi_type: self.clone(),
+                    conversion: CppIndirectionChange::NoChange,
+                    original_type: self.clone(),
+                  });
+      }
+      CppTypeBase::StdFunction(ref function) => {
+        check_ffi_function_type(function).chain_err(&err)?;
+        match self.indirection {
+          CppTypeIndirection::None |
+          CppTypeIndirection::RValueRef => {}
+          CppTypeIndirection::Ref if self.is_const => {}
+          _ => {
+            return Err(Error::from("std::function can only be passed by value, const reference \
+                                    or rvalue reference"))
                        .chain_err(&err);
-            }
-            CppTypeIndirection::Ptr |
-            CppTypeIndirection::PtrPtr => {}
-            CppTypeIndirection::None => {
-              if arg.base.is_class() {
-                return Err(Error::from("Function pointers containing classes by value are not \
-                                        supported"))
-                           .chain_err(&err);
-              }
-            }
           }
         }
         return Ok(CppFfiType {
-                    ffi_type: self.clone(),
-                    conversion: CppIndirectionChange::NoChange,
+                    ffi_type: CppType {
+                      base: self.base.clone(),
+                      indirection: CppTypeIndirection::None,
+                      is_const: false,
+                      is_const2: false,
+                    },
+                    conversion: CppIndirectionChange::StdFunctionToClosure,
                     original_type: self.clone(),
                   });
       }
@@ -758,6 +871,18 @@ impl CppType {
              CppTypeBase::Class(data
                                   .instantiate_class(nested_level1, template_arguments1)?)
            }
+           CppTypeBase::StdFunction(ref function) => {
+             CppTypeBase::StdFunction(CppFunctionPointerType {
+               return_type: Box::new(function
+                                       .return_type
+                                       .instantiate(nested_level1, template_arguments1)?),
+               arguments: function
+                 .arguments
+                 .iter()
+                 .map_if_ok(|arg| arg.instantiate(nested_level1, template_arguments1))?,
+               allows_variadic_arguments: function.allows_variadic_arguments,
+             })
+           }
            _ => self.base.clone(),
          },
        })
@@ -871,6 +996,22 @@ impl CppType {
   }
 }
 
+impl PartialEq for CppTypeClassBase {
+  fn eq(&self, other: &CppTypeClassBase) -> bool {
+    // enclosing_template_class field is ignored because it's determined by the name
+    // and is not filled in by all code paths that construct this type
+    self.name == other.name && self.template_arguments == other.template_arguments
+  }
+}
+impl Eq for CppTypeClassBase {}
+
+impl Hash for CppTypeClassBase {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.name.hash(state);
+    self.template_arguments.hash(state);
+  }
+}
+
 impl PartialEq for CppSpecificNumericType {
   fn eq(&self, other: &CppSpecificNumericType) -> bool {
     // name field is ignored
@@ -6,9 +6,10 @@ use rust_type::RustType;
 use rust_info::{RustMethodSelfArgKind, RustMethodArgumentsVariant, RustTypeDeclaration,
                 RustTypeDeclarationKind, RustMethodScope, RustEnumValue, RustMethod,
                 RustMethodArguments, RustMethodDocItem, RustTypeWrapperKind,
-                RustQtReceiverDeclaration, RustQtReceiverType};
+                RustQtReceiverDeclaration, RustQtReceiverType, RustQtPropertyDeclaration,
+                RustConstant};
 use cpp_type::{CppType, CppTypeBase, CppTypeClassBase, CppTypeIndirection};
-use common::string_utils::JoinWithSeparator;
+use common::string_utils::{JoinWithSeparator, CaseOperations};
 use common::log;
 use common::errors::{Result, unexpected};
 
@@ -25,7 +26,7 @@ pub fn rust_method_variant(args: &RustMethodArgumentsVariant,
     RustMethodSelfArgKind::MutRef => "&mut self, ",
     RustMethodSelfArgKind::Value => "self, ",
   };
-  let return_type_text = rust_type_to_code(&args.return_type.rust_api_type, crate_name);
+  let return_type_text = rust_type_to_code(&args.api_return_type(), crate_name);
   let arg_texts = args
     .arguments
     .iter()
@@ -53,7 +54,18 @@ pub fn wrap_cpp_doc_block(html: &str) -> String {
           html)
 }
 
-pub fn type_doc(type1: &RustTypeDeclaration) -> String {
+/// Returns caption of a C++ documentation block. The caption links to
+/// the online documentation page if `url` is not empty (documentation
+/// extracted from header files doesn't have a page).
+fn cpp_doc_caption(url: &str) -> String {
+  if url.is_empty() {
+    "C++ documentation:".to_string()
+  } else {
+    format!("<a href=\"{}\">C++ documentation:</a>", url)
+  }
+}
+
+pub fn type_doc(type1: &RustTypeDeclaration) -> Result<String> {
   let auto_doc = match type1.kind {
     RustTypeDeclarationKind::CppTypeWrapper {
       ref cpp_type_name,
@@ -65,6 +77,7 @@ pub fn type_doc(type1: &RustTypeDeclaration) -> String {
           base: CppTypeBase::Class(CppTypeClassBase {
                                      name: cpp_type_name.clone(),
                                      template_arguments: cpp_template_arguments.clone(),
+                                     enclosing_template_class: None,
                                    }),
           indirection: CppTypeIndirection::None,
           is_const: false,
@@ -73,8 +86,8 @@ pub fn type_doc(type1: &RustTypeDeclaration) -> String {
         .to_cpp_pseudo_code();
       let mut doc = format!("C++ type: {}", wrap_inline_cpp_code(&cpp_type_code));
       if let Some(ref cpp_doc) = *cpp_doc {
-        doc += &format!("\n\n<a href=\"{}\">C++ documentation:</a> {}",
-                        cpp_doc.url,
+        doc += &format!("\n\n{} {}",
+                        cpp_doc_caption(&cpp_doc.url),
                         wrap_cpp_doc_block(&cpp_doc.html));
       }
       doc
@@ -119,12 +132,45 @@ pub fn type_doc(type1: &RustTypeDeclaration) -> String {
               name = method_name_with_scope,
               link = method_link)
     }
+    RustTypeDeclarationKind::TypeAlias {
+      ref cpp_typedef_name,
+      ref cpp_doc,
+      ..
+    } => {
+      let mut doc = format!("C++ type alias: {}", wrap_inline_cpp_code(cpp_typedef_name));
+      if let Some(ref cpp_doc) = *cpp_doc {
+        doc += &format!("\n\n{} {}",
+                        cpp_doc_caption(&cpp_doc.url),
+                        wrap_cpp_doc_block(&cpp_doc.html));
+      }
+      doc
+    }
+    RustTypeDeclarationKind::IteratorAdapter { ref container_type, .. } => {
+      let container_name = if let RustType::Common { ref base, .. } = *container_type {
+        base.last_name()?
+      } else {
+        return Err(unexpected("RustType::Common expected").into());
+      };
+      format!("Iterator over items of [{name}](struct.{name}.html).\n\n\
+               This type is returned by `IntoIterator` implementation for `&{name}`.",
+              name = container_name)
+    }
   };
-  if let Some(ref doc) = type1.rust_doc {
-    format!("{}\n\n{}", doc, auto_doc)
-  } else {
-    auto_doc
-  }
+  Ok(if let Some(ref doc) = type1.rust_doc {
+       format!("{}\n\n{}", doc, auto_doc)
+     } else {
+       auto_doc
+     })
+}
+
+pub fn constant_doc(constant: &RustConstant) -> String {
+  format!("C++ {}: {}",
+          if constant.is_cpp_macro {
+            "macro"
+          } else {
+            "constant"
+          },
+          wrap_inline_cpp_code(&constant.cpp_name))
 }
 
 pub fn enum_value_doc(value: &RustEnumValue) -> String {
@@ -252,7 +298,7 @@ pub fn method_doc(method: &RustMethod) -> String {
                 result.url,
                 declaration)
       } else {
-        format!("<a href=\"{}\">C++ documentation:</a>", result.url)
+        cpp_doc_caption(&result.url)
       };
       doc.push(format!("{} {}", prefix, wrap_cpp_doc_block(&result.html)));
     }
@@ -312,6 +358,18 @@ pub fn doc_for_qt_builtin_receiver(cpp_type_name: &str,
           rust_method = receiver.method_name)
 }
 
+pub fn doc_for_qt_builtin_signal_connect_closure(cpp_type_name: &str,
+                                                receiver: &RustQtReceiverDeclaration)
+                                                -> String {
+  format!("Connects the built-in Qt signal `{cpp_type}::{cpp_method}` to closure `f`.\n\n\
+  The closure is called with the signal's arguments each time the signal is emitted. \
+  The closure is owned by the sender and dropped when the sender is deleted. \
+  Use the returned connection to disconnect the closure earlier. \
+  This function must be called from the sender's thread.",
+          cpp_type = cpp_type_name,
+          cpp_method = receiver.original_method_name)
+}
+
 pub fn doc_for_qt_builtin_receiver_method(cpp_type_name: &str,
                                           receiver: &RustQtReceiverDeclaration)
                                           -> String {
@@ -332,6 +390,38 @@ pub fn doc_for_qt_builtin_receivers_struct(rust_type_name: &str, receiver_type:
           rust_type_name)
 }
 
+pub fn doc_for_qt_properties_struct(rust_type_name: &str) -> String {
+  format!("Provides access to Qt properties of `{name}`.\n\n\
+  An object of this type can be created with `object.{prefix}_properties()` or \
+  `object.{prefix}_properties_mut()`. Properties can only be modified through the latter. \
+  Properties of base types are available through their own accessors.",
+          name = rust_type_name,
+          prefix = rust_type_name.to_snake_case())
+}
+
+pub fn doc_for_qt_property(cpp_type_name: &str, property: &RustQtPropertyDeclaration) -> String {
+  format!("Represents Qt property `{cpp_type}::{cpp_name}`.\n\n\
+  The property's value can be read with `get()`{extra}.",
+          cpp_type = cpp_type_name,
+          cpp_name = property.original_name,
+          extra = {
+            let mut items = Vec::new();
+            if property.setter.is_some() {
+              items.push("changed with `set()`");
+            }
+            if property.reset.is_some() {
+              items.push("reset with `reset()`");
+            }
+            if property.notify_signal.is_some() {
+              items.push("observed using the signal returned by `changed()`");
+            }
+            items
+              .into_iter()
+              .map(|x| format!(", {}", x))
+              .join("")
+          })
+}
+
 pub fn add_special_type_docs(data: &mut RustTypeDeclaration) -> Result<()> {
   let mut type_doc = None;
   if let RustTypeDeclarationKind::CppTypeWrapper {
@@ -339,7 +429,21 @@ pub fn add_special_type_docs(data: &mut RustTypeDeclaration) -> Result<()> {
            ref mut methods,
            ..
          } = data.kind {
-    if let RustTypeWrapperKind::Struct { ref slot_wrapper, .. } = *kind {
+    if let RustTypeWrapperKind::Struct {
+             ref slot_wrapper,
+             ref subclass,
+             ..
+           } = *kind {
+      if let Some(ref subclass) = *subclass {
+        type_doc = Some(format!("C++ subclass of `{base}` that allows to override \
+          its virtual methods in Rust.\n\n\
+          Create an object using one of the constructors and pass an implementation of \
+          `{trait_name}` trait to `set_virtuals()`. Virtual methods that are not \
+          implemented in Rust call the base class implementation. Calling a pure virtual \
+          method before `set_virtuals()` was called terminates the program.",
+                                base = subclass.base_class_name,
+                                trait_name = subclass.trait_name));
+      }
       if let Some(ref slot_wrapper) = *slot_wrapper {
         type_doc = Some(format!("Allows to bind Qt signals with arguments `({cpp_args})` to a \
           Rust extern function.\n\n\
@@ -351,7 +455,7 @@ pub fn add_special_type_docs(data: &mut RustTypeDeclaration) -> Result<()> {
           to this slot. The callback function will be executed each time the slot is invoked \
           until source signals are disconnected or the slot object is destroyed.\n\n\
           If `set()` was not called, slot invokation has no effect.",
-                                public_type_name = slot_wrapper.public_type_name,
+                                public_type_name = slot_wrapper.public_type_name.last_name()?,
                                 cpp_args = slot_wrapper
                                   .arguments
                                   .iter()
@@ -4,8 +4,10 @@ use config::{Config, DebugLoggingConfig};
 use cpp_code_generator::{CppCodeGenerator, generate_cpp_type_size_requester, CppTypeSizeRequest};
 use cpp_type::CppTypeClassBase;
 use cpp_data::{CppData, CppDataWithDeps, ParserCppData};
+use cpp_ffi_data::CppFfiHeaderData;
 use cpp_ffi_generator;
 use cpp_parser;
+use cpp_multi_target;
 use cpp_post_processor::cpp_post_process;
 use common::errors::{Result, ChainErr};
 use common::string_utils::CaseOperations;
@@ -13,6 +15,7 @@ use common::file_utils::{PathBufWithAdded, move_files, create_dir_all, save_json
                          save_bincode, canonicalize, remove_dir_all, remove_dir, read_dir,
                          create_file, path_to_str};
 use common::BuildScriptData;
+use common::target::Condition;
 use common::log;
 use rust_code_generator;
 use rust_generator;
@@ -113,6 +116,30 @@ fn check_all_paths(config: &Config) -> Result<()> {
   Ok(())
 }
 
+/// Returns all distinct target conditions of platform-specific types
+/// and wrapper functions of the C++ wrapper library.
+fn collect_cpp_conditions(cpp_data: &CppDataWithDeps,
+                          cpp_ffi_headers: &[CppFfiHeaderData])
+                          -> Vec<Condition> {
+  let type_conditions = cpp_data
+    .current
+    .parser
+    .types
+    .iter()
+    .filter_map(|t| t.condition.as_ref());
+  let method_conditions = cpp_ffi_headers
+    .iter()
+    .flat_map(|header| header.methods.iter())
+    .filter_map(|m| m.cpp_method.condition.as_ref());
+  let mut result = Vec::new();
+  for condition in type_conditions.chain(method_conditions) {
+    if !result.contains(condition) {
+      result.push(condition.clone());
+    }
+  }
+  result
+}
+
 /// Loads C++ data saved during a previous run of the generator
 /// from the cache directory if it's available and permitted by `config.cache_usage()`.
 /// Otherwise, performs necessary steps to parse and process C++ data.
@@ -141,18 +168,36 @@ fn load_or_create_cpp_data<'a>(config: &Config,
   let parser_cpp_data = if let Some(x) = loaded_parser_cpp_data {
     x
   } else {
-    log::status("Running C++ parser");
-    let parser_config = cpp_parser::CppParserConfig {
-      include_paths: Vec::from(config.include_paths()),
-      framework_paths: Vec::from(config.framework_paths()),
-      include_directives: Vec::from(config.include_directives()),
-      target_include_paths: Vec::from(config.target_include_paths()),
-      tmp_cpp_path: config.cache_dir_path().with_added("1.cpp"),
-      name_blacklist: Vec::from(config.cpp_parser_blocked_names()),
-      clang_arguments: Vec::from(config.cpp_parser_arguments()),
+    let make_parser_config = |extra_arguments: &[String]| {
+      let mut clang_arguments = Vec::from(config.cpp_parser_arguments());
+      clang_arguments.extend(extra_arguments.iter().cloned());
+      cpp_parser::CppParserConfig {
+        include_paths: Vec::from(config.include_paths()),
+        framework_paths: Vec::from(config.framework_paths()),
+        include_directives: Vec::from(config.include_directives()),
+        target_include_paths: Vec::from(config.target_include_paths()),
+        tmp_cpp_path: config.cache_dir_path().with_added("1.cpp"),
+        name_blacklist: Vec::from(config.cpp_parser_blocked_names()),
+        macro_whitelist: Vec::from(config.cpp_parser_allowed_macros()),
+        clang_arguments: clang_arguments,
+      }
+    };
+    let mut parser_cpp_data: ParserCppData = if config.cpp_parser_targets().is_empty() {
+      log::status("Running C++ parser");
+      cpp_parser::run(make_parser_config(&[]), &dependencies_cpp_data)
+        .chain_err(|| "C++ parser failed")?
+    } else {
+      let mut results = Vec::new();
+      for parser_target in config.cpp_parser_targets() {
+        log::status(format!("Running C++ parser for target: {:?}", parser_target.target));
+        let data = cpp_parser::run(make_parser_config(&parser_target.clang_arguments),
+                                   &dependencies_cpp_data)
+            .chain_err(|| format!("C++ parser failed for target: {:?}", parser_target.target))?;
+        results.push((parser_target.target.clone(), data));
+      }
+      log::status("Merging C++ parser data for all targets");
+      cpp_multi_target::merge(results)
     };
-    let mut parser_cpp_data: ParserCppData = cpp_parser::run(parser_config, &dependencies_cpp_data)
-      .chain_err(|| "C++ parser failed")?;
     parser_cpp_data
       .detect_signals_and_slots(&dependencies_cpp_data)?;
     // TODO: rename `cpp_data_filters` to `parser_cpp_data_filters`
@@ -339,11 +384,18 @@ pub fn exec<T: Iterator<Item = Config>>(configs: T) -> Result<()> {
 
       let cpp_ffi_headers = cpp_ffi_generator::run(&cpp_data,
                                                    cpp_ffi_lib_name.clone(),
-                                                   config.cpp_ffi_generator_filters())
+                                                   config.cpp_ffi_generator_filters(),
+                                                   config.catch_cpp_exceptions(),
+                                                   config.cpp_subclasses())
           .chain_err(|| "FFI generator failed")?;
 
+      let cpp_conditions = collect_cpp_conditions(&cpp_data, &cpp_ffi_headers);
+
       log::status(format!("Generating C++ wrapper code"));
-      let code_gen = CppCodeGenerator::new(cpp_ffi_lib_name.clone(), c_lib_tmp_path.clone());
+      let code_gen = CppCodeGenerator::new(cpp_ffi_lib_name.clone(),
+                                           c_lib_tmp_path.clone(),
+                                           config.cpp_exception_types().to_vec(),
+                                           cpp_conditions.clone());
       code_gen
         .generate_template_files(config.include_directives())?;
       code_gen.generate_files(&cpp_ffi_headers)?;
@@ -381,6 +433,9 @@ pub fn exec<T: Iterator<Item = Config>>(configs: T) -> Result<()> {
           // TODO: more universal prefix removal (#25)
           remove_qt_prefix: remove_qt_prefix,
           filtered_namespaces: config.cpp_filtered_namespaces().clone(),
+          cpp_callbacks: config.cpp_callbacks().to_vec(),
+          enum_representation: config.enum_representation().clone(),
+          cpp_enum_representations: config.cpp_enum_representations().clone(),
         }
         .run()
         .chain_err(|| "Rust data generator failed")?;
@@ -392,15 +447,30 @@ pub fn exec<T: Iterator<Item = Config>>(configs: T) -> Result<()> {
       for type1 in &rust_data.processed_types {
         if let RustTypeWrapperKind::Struct { ref size_const_name, .. } = type1.kind {
           if let Some(ref size_const_name) = *size_const_name {
+            let type_info = cpp_data
+              .current
+              .parser
+              .types
+              .iter()
+              .find(|t| t.name == type1.cpp_name);
+            let condition = type_info.and_then(|t| t.condition.as_ref());
+            let condition_macro = match condition {
+              Some(condition) => Some(code_gen.condition_macro(condition)?),
+              None => None,
+            };
             cpp_type_size_requests.push(CppTypeSizeRequest {
                                           cpp_code: CppTypeClassBase {
                                               name: type1.cpp_name.clone(),
                                               template_arguments: type1
                                                 .cpp_template_arguments
                                                 .clone(),
+                                              enclosing_template_class: type_info.and_then(|t| {
+                                                t.enclosing_template_class.clone()
+                                              }),
                                             }
                                             .to_cpp_code()?,
                                           size_const_name: size_const_name.clone(),
+                                          condition_macro: condition_macro,
                                         });
           }
         }
@@ -447,6 +517,7 @@ pub fn exec<T: Iterator<Item = Config>>(configs: T) -> Result<()> {
                    cpp_build_config: config.cpp_build_config().clone(),
                    cpp_wrapper_lib_name: cpp_ffi_lib_name,
                    cpp_lib_version: config.cpp_lib_version().map(|s| s.to_string()),
+                   cpp_conditions: cpp_conditions,
                  })?;
       if config.write_cache() {
         create_file(completed_marker_path(config.cache_dir_path()))?;
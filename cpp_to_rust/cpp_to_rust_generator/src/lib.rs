@@ -36,6 +36,7 @@ mod caption_strategy;
 pub mod config;
 pub mod cpp_data;
 mod cpp_post_processor;
+mod cpp_multi_target;
 mod cpp_ffi_data;
 pub mod cpp_method;
 pub mod cpp_type;
@@ -9,12 +9,18 @@ use rust_generator::RustGeneratorOutput;
 use rust_info::{RustTypeDeclarationKind, RustTypeWrapperKind, RustModule, RustMethod,
                 RustMethodArguments, RustMethodArgumentsVariant, RustMethodScope,
                 RustMethodArgument, TraitImpl, TraitImplExtra, RustQtReceiverType, DependencyInfo,
-                RustFFIFunction};
+                RustFFIFunction, RustSubclass, RustTypeDeclaration, RustQtReceiverDeclaration,
+                RustQtPropertyDeclaration, RustEnumRepresentation, RustEnumValue};
 use rust_type::{RustName, RustType, RustTypeIndirection, RustToCTypeConversion, CompleteType};
+use cpp_data::CppDeprecation;
+use cpp_multi_target::or_conditions;
+use common::target::Condition;
 use common::string_utils::{JoinWithSeparator, CaseOperations};
 use common::utils::MapIfOk;
 use doc_formatter;
 use std::path::{Path, PathBuf};
+use std::iter::once;
+use std::collections::HashSet;
 
 use common::toml;
 use rustfmt;
@@ -59,6 +65,97 @@ fn format_doc(doc: &str) -> String {
   }
 }
 
+/// Generates `#[deprecated]` attribute for a deprecated item.
+/// Returns an empty string if `deprecation` is `None`.
+fn deprecated_attribute(deprecation: Option<&CppDeprecation>) -> String {
+  match deprecation {
+    Some(&CppDeprecation { message: Some(ref message) }) => {
+      format!("#[deprecated(note = {:?})]\n", message)
+    }
+    Some(&CppDeprecation { message: None }) => "#[deprecated]\n".to_string(),
+    None => String::new(),
+  }
+}
+
+/// Generates `#[cfg]` attribute for a platform-specific item.
+/// Returns an empty string if `condition` is `None`.
+fn cfg_attribute(condition: Option<&Condition>) -> String {
+  match condition {
+    Some(condition) => format!("#[cfg({})]\n", condition.to_cfg()),
+    None => String::new(),
+  }
+}
+
+/// Returns Rust FFI type corresponding to C++ `void*` type.
+fn void_ptr_type() -> Result<RustType> {
+  Ok(RustType::Common {
+       base: RustName::new(vec!["libc".to_string(), "c_void".to_string()])?,
+       generic_arguments: None,
+       is_const: false,
+       is_const2: false,
+       indirection: RustTypeIndirection::Ptr,
+     })
+}
+
+/// Returns code of an expression evaluating to the integer value
+/// of the variant `value` of the enum `enum_name`.
+fn enum_value_int_code(enum_name: &str,
+                       value: &RustEnumValue,
+                       representation: &RustEnumRepresentation)
+                       -> String {
+  match *representation {
+    RustEnumRepresentation::Enum => format!("{}::{} as ::libc::c_int", enum_name, value.name),
+    RustEnumRepresentation::Newtype => format!("{}::{}.0", enum_name, value.name),
+  }
+}
+
+/// Returns match arms of `from_name` method of the enum `enum_name`.
+/// Both Rust names and original C++ names of the variants are accepted.
+fn enum_from_name_arms(enum_name: &str, values: &[RustEnumValue]) -> String {
+  let mut used_names = HashSet::new();
+  values
+    .iter()
+    .filter(|item| !item.is_dummy)
+    .filter_map(|item| {
+      let names: Vec<_> = once(&item.name)
+        .chain(item.cpp_docs.iter().map(|doc| &doc.variant_name))
+        .filter(|name| used_names.insert(name.to_string()))
+        .map(|name| format!("\"{}\"", name))
+        .collect();
+      if names.is_empty() {
+        None
+      } else {
+        Some(format!("{} => Some({}::{}),", names.join(" | "), enum_name, item.name))
+      }
+    })
+    .join("\n")
+}
+
+/// Returns true if `module` or any of its submodules
+/// contains platform-specific types.
+fn has_platform_specific_types(module: &RustModule) -> bool {
+  module.types.iter().any(|t| t.condition.is_some()) ||
+  module
+    .submodules
+    .iter()
+    .any(has_platform_specific_types)
+}
+
+/// Adds names of all lifetimes used in `rust_type` (including its
+/// generic arguments) to `output`, skipping duplicates and `'static`.
+fn collect_lifetimes(rust_type: &RustType, output: &mut Vec<String>) {
+  if let Some(lifetime) = rust_type.lifetime() {
+    if lifetime != "static" && !output.contains(lifetime) {
+      output.push(lifetime.clone());
+    }
+  }
+  if let RustType::Common { generic_arguments: Some(ref args), .. } = *rust_type {
+    for arg in args {
+      collect_lifetimes(arg, output);
+    }
+  }
+}
+
 /// Generates Rust code representing type `rust_type` inside crate `crate_name`.
 /// Same as `RustCodeGenerator::rust_type_to_code`, but accessible by other modules.
 pub fn rust_type_to_code(rust_type: &RustType, crate_name: &str) -> String {
@@ -135,6 +232,38 @@ pub fn rust_type_to_code(rust_type: &RustType, crate_name: &str) -> String {
                 return_type => format!(" -> {}", rust_type_to_code(return_type, crate_name)),
               })
     }
+    RustType::Closure {
+      ref return_type,
+      ref arguments,
+      ref is_boxed,
+      ref is_send,
+      ref lifetime,
+    } => {
+      let trait_code = format!("{}({}){}",
+                               if *is_boxed { "Fn" } else { "FnMut" },
+                               arguments
+                                 .iter()
+                                 .map(|arg| rust_type_to_code(arg, crate_name))
+                                 .join(", "),
+                               match return_type.as_ref() {
+                                 &RustType::EmptyTuple => String::new(),
+                                 return_type => {
+                                   format!(" -> {}", rust_type_to_code(return_type, crate_name))
+                                 }
+                               });
+      if *is_boxed {
+        if *is_send {
+          format!("Box<{} + Send + Sync>", trait_code)
+        } else {
+          format!("Box<{}>", trait_code)
+        }
+      } else {
+        match *lifetime {
+          Some(ref lifetime) => format!("&'{} mut {}", lifetime, trait_code),
+          None => format!("&mut {}", trait_code),
+        }
+      }
+    }
   }
 }
 
@@ -388,7 +517,8 @@ impl<'a> RustCodeGenerator<'a> {
                      arg.name,
                      self.rust_type_to_code(&arg.argument_type))
            });
-    format!("  pub fn {}({}){};\n",
+    format!("{}  pub fn {}({}){};\n",
+            cfg_attribute(func.condition.as_ref()),
             func.name,
             args.join(", "),
             match func.return_type {
@@ -478,10 +608,76 @@ impl<'a> RustCodeGenerator<'a> {
                 self.rust_type_to_code(&qflags_type),
                 source_expr)
       }
+      RustToCTypeConversion::ClosureToCallback { .. } => {
+        return Err(unexpected("closures can't be returned from FFI functions").into());
+      }
+      RustToCTypeConversion::BoxToCppClosure => {
+        let (return_type, arguments) = match type1.rust_api_type {
+          RustType::Closure {
+            ref return_type,
+            ref arguments,
+            ..
+          } => (return_type, arguments),
+          _ => return Err(unexpected("closure type expected").into()),
+        };
+        let callback_type = RustType::FunctionPointer {
+          return_type: return_type.clone(),
+          arguments: once(void_ptr_type()?)
+            .chain(arguments.iter().cloned())
+            .collect(),
+        };
+        format!("{{\nlet closure = {source_expr};\n\
+                 let callback: {callback_type} = \
+                 {unsafe_start}::std::mem::transmute(closure.callback()){unsafe_end};\n\
+                 Box::new(move |{params}| callback(closure.data(){call_args})) as {api_type}\n}}",
+                source_expr = source_expr,
+                callback_type = self.rust_type_to_code(&callback_type),
+                unsafe_start = unsafe_start,
+                unsafe_end = unsafe_end,
+                params = arguments
+                  .iter()
+                  .enumerate()
+                  .map(|(index, t)| format!("arg{}: {}", index, self.rust_type_to_code(t)))
+                  .join(", "),
+                call_args = (0..arguments.len())
+                  .map(|index| format!(", arg{}", index))
+                  .join(""),
+                api_type = self.rust_type_to_code(&type1.rust_api_type))
+      }
     };
     Ok(code1 + &code2)
   }
 
+  /// Generates code that converts `expression` of Rust API type of `type1`
+  /// to its Rust FFI type. If `type1` is a `CppBox`, the ownership
+  /// of the object is passed to the C++ side.
+  fn convert_type_to_ffi(&self, type1: &CompleteType, expression: String) -> Result<String> {
+    Ok(match type1.rust_api_to_c_conversion {
+         RustToCTypeConversion::None => expression,
+         RustToCTypeConversion::RefToPtr => {
+           if type1.rust_api_type.is_const()? && !type1.rust_ffi_type.is_const()? {
+             let mut intermediate_type = type1.rust_ffi_type.clone();
+             intermediate_type.set_const(true)?;
+             format!("{} as {} as {}",
+                     expression,
+                     self.rust_type_to_code(&intermediate_type),
+                     self.rust_type_to_code(&type1.rust_ffi_type))
+           } else {
+             format!("{} as {}",
+                     expression,
+                     self.rust_type_to_code(&type1.rust_ffi_type))
+           }
+         }
+         RustToCTypeConversion::CppBoxToPtr => format!("{}.into_raw()", expression),
+         RustToCTypeConversion::QFlagsToUInt => {
+           format!("{}.to_int() as ::libc::c_uint", expression)
+         }
+         ref conversion => {
+           return Err(format!("conversion is not supported here: {:?}", conversion).into());
+         }
+       })
+  }
+
   /// Generates Rust code for calling an FFI function from a wrapper function.
   /// If `in_unsafe_context` is `true`, the output code will be placed inside
   /// an `unsafe` block.
@@ -501,6 +697,7 @@ impl<'a> RustCodeGenerator<'a> {
     for arg in &variant.arguments {
       all_args.push(arg.clone());
     }
+    let mut closure_preludes = Vec::new();
     for arg in &all_args {
       assert!(arg.ffi_index < final_args.len());
       let mut code = arg.name.clone();
@@ -509,21 +706,9 @@ impl<'a> RustCodeGenerator<'a> {
         RustToCTypeConversion::OptionRefToPtr => {
           return Err("OptionRefToPtr is not supported here yet".into());
         }
-        RustToCTypeConversion::RefToPtr => {
-          if arg.argument_type.rust_api_type.is_const()? &&
-             !arg.argument_type.rust_ffi_type.is_const()? {
-            let mut intermediate_type = arg.argument_type.rust_ffi_type.clone();
-            intermediate_type.set_const(true)?;
-            code = format!("{} as {} as {}",
-                           code,
-                           self.rust_type_to_code(&intermediate_type),
-                           self.rust_type_to_code(&arg.argument_type.rust_ffi_type));
-
-          } else {
-            code = format!("{} as {}",
-                           code,
-                           self.rust_type_to_code(&arg.argument_type.rust_ffi_type));
-          }
+        RustToCTypeConversion::RefToPtr |
+        RustToCTypeConversion::QFlagsToUInt => {
+          code = self.convert_type_to_ffi(&arg.argument_type, code)?;
         }
         RustToCTypeConversion::ValueToPtr |
         RustToCTypeConversion::CppBoxToPtr => {
@@ -551,12 +736,73 @@ impl<'a> RustCodeGenerator<'a> {
                            self.rust_type_to_code(&arg.argument_type.rust_ffi_type));
           }
         }
-        RustToCTypeConversion::QFlagsToUInt => {
-          code = format!("{}.to_int() as ::libc::c_uint", code);
+        RustToCTypeConversion::ClosureToCallback {
+          user_data_ffi_index,
+          callback_user_data_index,
+        } => {
+          assert!(user_data_ffi_index < final_args.len());
+          let (return_type, arguments) = match arg.argument_type.rust_ffi_type {
+            RustType::FunctionPointer {
+              ref return_type,
+              ref arguments,
+            } => (return_type, arguments),
+            _ => return Err(unexpected("function pointer expected for a closure argument").into()),
+          };
+          let mut closure_type = arg.argument_type.rust_api_type.clone();
+          if let RustType::Closure { ref mut lifetime, .. } = closure_type {
+            *lifetime = None;
+          } else {
+            return Err(unexpected("closure type expected").into());
+          }
+          closure_preludes.push(self.closure_trampoline(&arg.name,
+                                                        &closure_type,
+                                                        return_type,
+                                                        arguments,
+                                                        callback_user_data_index));
+          closure_preludes.push(format!("let mut {name}_ref: {closure_type} = {name};\n",
+                                        name = arg.name,
+                                        closure_type = self.rust_type_to_code(&closure_type)));
+          code = format!("{}_trampoline", arg.name);
+          final_args[user_data_ffi_index] = Some(format!("&mut {}_ref as *mut _ as *mut \
+                                                          ::libc::c_void",
+                                                         arg.name));
+        }
+        RustToCTypeConversion::BoxToCppClosure => {
+          let (return_type, arguments) = match arg.argument_type.rust_api_type {
+            RustType::Closure {
+              ref return_type,
+              ref arguments,
+              ..
+            } => (return_type, arguments),
+            _ => return Err(unexpected("closure type expected").into()),
+          };
+          let callback_arguments: Vec<_> = once(void_ptr_type()?)
+            .chain(arguments.iter().cloned())
+            .collect();
+          closure_preludes.push(self.closure_trampoline(&arg.name,
+                                                        &arg.argument_type.rust_api_type,
+                                                        return_type,
+                                                        &callback_arguments,
+                                                        0));
+          let callback_type = RustType::FunctionPointer {
+            return_type: return_type.clone(),
+            arguments: callback_arguments,
+          };
+          code = format!("{unsafe_start}::cpp_utils::CppClosure::new(Box::new({name}), \
+                          ::std::mem::transmute({name}_trampoline as {callback_type})){unsafe_end}",
+                         name = arg.name,
+                         callback_type = self.rust_type_to_code(&callback_type),
+                         unsafe_start = unsafe_start,
+                         unsafe_end = unsafe_end);
         }
       }
       final_args[arg.ffi_index] = Some(code);
     }
+    let exception_slot_index = variant.exception_slot_ffi_index();
+    if let Some(index) = exception_slot_index {
+      final_args[index] = Some("&mut exception_slot as *mut ::cpp_utils::CppExceptionSlot as *mut _"
+                                 .to_string());
+    }
 
     let mut result = Vec::new();
     let mut maybe_result_var_name = None;
@@ -611,21 +857,364 @@ impl<'a> RustCodeGenerator<'a> {
                         unsafe_start = unsafe_start,
                         unsafe_end = unsafe_end));
     if let Some(ref name) = maybe_result_var_name {
-      result.push(format!("{}\n}}", name));
+      if exception_slot_index.is_some() {
+        // the object was not constructed, so its destructor must not be called
+        result.push(format!("\nif let Some(exception) = exception_slot.take() {{\n\
+                             ::std::mem::forget({name});\nErr(exception)\n\
+                             }} else {{\nOk({name})\n}}\n}}",
+                            name = name));
+      } else {
+        result.push(format!("{}\n}}", name));
+      }
     }
     let code = result.join("");
-    if maybe_result_var_name.is_none() {
-      self.convert_type_from_ffi(&variant.return_type, code, in_unsafe_context, true)
+    let code = if maybe_result_var_name.is_some() {
+      code
+    } else if exception_slot_index.is_some() {
+      let (call_code, result_code) = if variant.return_type.rust_api_type ==
+                                        RustType::EmptyTuple {
+        // FFI function's result is discarded, if any
+        (format!("{};\n", code), "()".to_string())
+      } else {
+        (format!("let ffi_result = {};\n", code),
+         self
+           .convert_type_from_ffi(&variant.return_type,
+                                  "ffi_result".to_string(),
+                                  in_unsafe_context,
+                                  false)?)
+      };
+      format!("{}if let Some(exception) = exception_slot.take() {{\nErr(exception)\n\
+               }} else {{\nOk({})\n}}",
+              call_code,
+              result_code)
     } else {
+      let code = self
+        .convert_type_from_ffi(&variant.return_type, code, in_unsafe_context, true)?;
+      if variant.return_type.rust_api_type == RustType::EmptyTuple &&
+         variant.return_type.rust_ffi_type != RustType::EmptyTuple {
+        // FFI function's result is discarded
+        format!("{};", code)
+      } else {
+        code
+      }
+    };
+    let code = if exception_slot_index.is_some() {
+      format!("{{\nlet mut exception_slot = ::cpp_utils::CppExceptionSlot::new();\n{}\n}}",
+              code)
+    } else {
+      code
+    };
+    if closure_preludes.is_empty() {
       Ok(code)
+    } else {
+      Ok(format!("{{\n{}{}\n}}", closure_preludes.join(""), code))
+    }
+  }
+
+  /// Generates Rust code that declares a trampoline function
+  /// for the closure argument `name` of type `closure_type`.
+  /// The trampoline is an FFI callback with `arguments` and `return_type`.
+  /// It invokes the closure passed to it through the argument
+  /// with index `data_index` as a pointer to `closure_type`, forwarding
+  /// other arguments to the closure.
+  /// A panic in the closure aborts the process because it can't
+  /// unwind through the C++ code.
+  fn closure_trampoline(&self,
+                        name: &str,
+                        closure_type: &RustType,
+                        return_type: &RustType,
+                        arguments: &[RustType],
+                        data_index: usize)
+                        -> String {
+    let params = arguments
+      .iter()
+      .enumerate()
+      .map(|(index, t)| format!("arg{}: {}", index, self.rust_type_to_code(t)))
+      .join(", ");
+    let call_args = (0..arguments.len())
+      .filter(|index| *index != data_index)
+      .map(|index| format!("arg{}", index))
+      .join(", ");
+    // boxed closures may be shared by copies of a `std::function`,
+    // so they are only accessed through a shared reference
+    let (func_ref, ptr_kind) = match *closure_type {
+      RustType::Closure { is_boxed: true, .. } => ("&*", "const"),
+      _ => ("&mut *", "mut"),
+    };
+    format!("extern \"C\" fn {name}_trampoline({params}){return_type} {{\n\
+             let func = unsafe {{ {func_ref}(arg{data} as *{ptr_kind} {closure_type}) }};\n\
+             match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(\
+             || func({call_args}))) {{\n\
+             Ok(result) => result,\n\
+             Err(_) => ::std::process::abort(),\n\
+             }}\n\
+             }}\n",
+            name = name,
+            params = params,
+            return_type = match *return_type {
+              RustType::EmptyTuple => String::new(),
+              ref return_type => format!(" -> {}", self.rust_type_to_code(return_type)),
+            },
+            func_ref = func_ref,
+            data = data_index,
+            ptr_kind = ptr_kind,
+            closure_type = self.rust_type_to_code(closure_type),
+            call_args = call_args)
+  }
+
+  /// Generates the `properties` module containing accessors of Qt properties
+  /// of `type1` and methods of `type1` that create the accessors.
+  fn qt_properties_code(&self,
+                        type1: &RustTypeDeclaration,
+                        cpp_type_name: &str,
+                        qt_properties: &[RustQtPropertyDeclaration],
+                        qt_receivers: &[RustQtReceiverDeclaration])
+                        -> Result<String> {
+    let obj_name = type1
+      .name
+      .full_name(Some(&self.config.crate_properties.name()));
+    let deref_bound = format!("R: ::std::ops::Deref<Target = {}>", obj_name);
+    let deref_mut_bound = format!("R: ::std::ops::DerefMut<Target = {}>", obj_name);
+    let mut content = Vec::new();
+    let mut struct_content = Vec::new();
+    content.push(format!("{}pub struct Properties<R>(R);\n",
+                         format_doc(&doc_formatter::doc_for_qt_properties_struct(type1
+                                                                                    .name
+                                                                                    .last_name()?))));
+    for property in qt_properties {
+      struct_content.push(format!("\
+{doc}pub fn {method_name}(self) -> {type_name}<R> {{
+  {type_name}(self.0)
+}}\n",
+                                  doc = format_doc(&format!("Returns accessor of Qt property `{}`.",
+                                                            property.original_name)),
+                                  method_name = property.method_name,
+                                  type_name = property.type_name));
+      content.push(format!("{}pub struct {}<R>(R);\n",
+                           format_doc(&doc_formatter::doc_for_qt_property(cpp_type_name, property)),
+                           property.type_name));
+      let mut read_methods = vec![format!("\
+/// Returns value of the property.
+pub fn get(&self) -> {value_type} {{
+  self.0.{getter}()
+}}\n",
+                                          value_type =
+                                            self.rust_type_to_code(&property.value_type),
+                                          getter = property.getter)];
+      if let Some(ref notify_signal) = property.notify_signal {
+        if let Some(receiver) = qt_receivers
+             .iter()
+             .find(|r| &r.method_name == notify_signal) {
+          read_methods.push(format!("\
+/// Returns the signal emitted when the property's value changes.
+pub fn changed(&self) -> super::connection::{signal_type} {{
+  self.0.signals().{signal_method}()
+}}\n",
+                                    signal_type = receiver.type_name,
+                                    signal_method = receiver.method_name));
+        }
+      }
+      content.push(format!("impl<{}> {}<R> {{\n{}}}\n",
+                           deref_bound,
+                           property.type_name,
+                           read_methods.join("")));
+      let mut write_methods = Vec::new();
+      if let Some(ref setter) = property.setter {
+        let value_type = property
+          .setter_argument_type
+          .as_ref()
+          .chain_err(|| unexpected("setter argument type is missing"))?;
+        write_methods.push(format!("\
+/// Sets value of the property.
+pub fn set(&mut self, value: {value_type}) {{
+  self.0.{setter}(value)
+}}\n",
+                                   value_type = self.rust_type_to_code(value_type),
+                                   setter = setter));
+      }
+      if let Some(ref reset) = property.reset {
+        write_methods.push(format!("\
+/// Resets the property to its default value.
+pub fn reset(&mut self) {{
+  self.0.{reset}()
+}}\n",
+                                   reset = reset));
+      }
+      if !write_methods.is_empty() {
+        content.push(format!("impl<{}> {}<R> {{\n{}}}\n",
+                             deref_mut_bound,
+                             property.type_name,
+                             write_methods.join("")));
+      }
     }
+    content.push(format!("impl<{}> Properties<R> {{\n{}}}\n",
+                         deref_bound,
+                         struct_content.join("")));
+    // accessors are named after the type, so they don't hide accessors of base types
+    // available through `Deref`
+    content.push(format!("\
+impl {obj_name} {{
+{doc}pub fn {prefix}_properties(&self) -> Properties<&{obj_name}> {{
+  Properties(self)
+}}
+
+{doc_mut}pub fn {prefix}_properties_mut(&mut self) -> Properties<&mut {obj_name}> {{
+  Properties(self)
+}}
+}}\n",
+                         obj_name = obj_name,
+                         prefix = type1.name.last_name()?.to_snake_case(),
+                         doc = format_doc("Provides read-only access to Qt properties of this type"),
+                         doc_mut = format_doc("Provides access to Qt properties of this type")));
+    Ok(format!("/// Types for accessing Qt properties of types in this module\n\
+                pub mod properties {{\n{}\n}}\n\n",
+               content.join("")))
+  }
+
+  /// Generates the trait containing virtual methods of a generated subclass,
+  /// the subclass's `set_virtuals` method and callbacks that forward
+  /// calls of the virtual methods to the trait object.
+  fn subclass_code(&self, type_name: &str, subclass: &RustSubclass) -> Result<String> {
+    let mut trait_methods = Vec::new();
+    let mut set_callbacks = Vec::new();
+    let mut callbacks = Vec::new();
+    for method in &subclass.virtual_methods {
+      let returns_ref = method.return_type.rust_api_type.is_ref();
+      let (lifetimes, lifetime) = if returns_ref { ("<'a>", "'a ") } else { ("", "") };
+      let return_type = if returns_ref {
+        method
+          .return_type
+          .rust_api_type
+          .with_lifetime("a".to_string())
+      } else {
+        method.return_type.rust_api_type.clone()
+      };
+      let return_type_code = if return_type == RustType::EmptyTuple {
+        String::new()
+      } else {
+        format!(" -> {}", self.rust_type_to_code(&return_type))
+      };
+      let args = method
+        .argument_names
+        .iter()
+        .zip(&method.arguments)
+        .map(|(name, t)| format!(", {}: {}", name, self.rust_type_to_code(&t.rust_api_type)))
+        .join("");
+      let maybe_mut = if method.is_const { "" } else { "mut " };
+      let declaration = format!("fn {name}{lifetimes}(&{lifetime}self, \
+                                 this: &{lifetime}{maybe_mut}{type_name}{args}){return_type}",
+                                name = method.name,
+                                lifetimes = lifetimes,
+                                lifetime = lifetime,
+                                maybe_mut = maybe_mut,
+                                type_name = type_name,
+                                args = args,
+                                return_type = return_type_code);
+      trait_methods.push(match method.base_method_name {
+                           Some(ref base_method_name) => {
+                             format!("  /// Overrides `{cpp}`. Calls the base class \
+                                      implementation by default.\n  \
+                                      #[allow(unused_unsafe)]\n  \
+                                      {declaration} {{\n    \
+                                      unsafe {{ this.{base}({arg_names}){expect} }}\n  \
+                                      }}\n",
+                                     cpp = method.cpp_declaration,
+                                     declaration = declaration,
+                                     base = base_method_name,
+                                     arg_names = method.argument_names.join(", "),
+                                     expect = if method.base_method_returns_result {
+                                       ".expect(\"C++ exception in base class implementation\")"
+                                     } else {
+                                       ""
+                                     })
+                           }
+                           None => {
+                             format!("  /// Overrides pure virtual method `{}`.\n  {};\n",
+                                     method.cpp_declaration,
+                                     declaration)
+                           }
+                         });
+      set_callbacks.push(format!("      self.{}({});\n", method.setter_name, method.callback_name));
+
+      let (callback_return_type, callback_arguments) = match method.callback_type {
+        RustType::FunctionPointer {
+          ref return_type,
+          ref arguments,
+        } => (return_type, arguments),
+        _ => return Err(unexpected("function pointer expected for a callback type").into()),
+      };
+      let params = callback_arguments
+        .iter()
+        .enumerate()
+        .map(|(index, t)| format!("arg{}: {}", index, self.rust_type_to_code(t)))
+        .join(", ");
+      // the first two arguments of the callback are the trait object and the object
+      let call_args = once("this".to_string())
+        .chain(method
+                 .arguments
+                 .iter()
+                 .enumerate()
+                 .map_if_ok(|(index, t)| {
+                              self.convert_type_from_ffi(t, format!("arg{}", index + 2), true, false)
+                            })?)
+        .join(", ");
+      let call = self
+        .convert_type_to_ffi(&method.return_type,
+                             format!("virtuals.{}({})", method.name, call_args))?;
+      callbacks.push(format!("#[allow(unused_unsafe)]\n\
+                              extern \"C\" fn {name}({params}){return_type} {{\n\
+                              let virtuals = unsafe {{ \
+                              &*(arg0 as *const ::std::cell::RefCell<Box<{trait_name}>>) }};\n\
+                              let this = unsafe {{ &{maybe_mut}*arg1 }};\n\
+                              match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {{\n\
+                              // the borrow is shared, so the base class implementation \
+                              may call other virtual methods\n\
+                              let virtuals = virtuals.borrow();\n\
+                              unsafe {{ {call} }}\n\
+                              }})) {{\n\
+                              Ok(result) => result,\n\
+                              Err(_) => ::std::process::abort(),\n\
+                              }}\n\
+                              }}\n\n",
+                             name = method.callback_name,
+                             params = params,
+                             return_type = match **callback_return_type {
+                               RustType::EmptyTuple => String::new(),
+                               ref return_type => {
+                                 format!(" -> {}", self.rust_type_to_code(return_type))
+                               }
+                             },
+                             trait_name = subclass.trait_name,
+                             maybe_mut = maybe_mut,
+                             call = call));
+    }
+    Ok(format!(include_str!("../templates/crate/subclass.rs.in"),
+               base_class_name = subclass.base_class_name,
+               type_name = type_name,
+               trait_name = subclass.trait_name,
+               trait_methods = trait_methods.join(""),
+               set_callbacks = set_callbacks.join(""),
+               deleter_name = subclass.deleter_name,
+               callbacks = callbacks.join("")))
   }
 
   /// Generates Rust code for declaring a function's arguments.
-  fn arg_texts(&self, args: &[RustMethodArgument], lifetime: Option<&String>) -> Vec<String> {
+  /// `impl_lifetimes` contains lifetimes declared on the `impl` block
+  /// containing the function. If `self` is a reference with one of
+  /// these lifetimes, it's declared as `self`.
+  fn arg_texts(&self,
+               args: &[RustMethodArgument],
+               lifetime: Option<&String>,
+               impl_lifetimes: &[String])
+               -> Vec<String> {
     args
       .iter()
       .map(|arg| if &arg.name == "self" {
+             if let Some(lifetime) = arg.argument_type.rust_api_type.lifetime() {
+               if impl_lifetimes.contains(lifetime) {
+                 return "self".to_string();
+               }
+             }
              let self_type = match lifetime {
                Some(lifetime) => {
                  arg
@@ -685,27 +1274,37 @@ impl<'a> RustCodeGenerator<'a> {
 
 
   /// Generates complete code of a Rust wrapper function.
-  fn generate_rust_final_function(&self, func: &RustMethod) -> Result<String> {
+  /// `impl_lifetimes` contains lifetimes declared on the `impl` block
+  /// containing the function.
+  fn generate_rust_final_function(&self,
+                                  func: &RustMethod,
+                                  impl_lifetimes: &[String])
+                                  -> Result<String> {
     let maybe_pub = match func.scope {
       RustMethodScope::TraitImpl => "",
       _ => "pub ",
     };
     let maybe_unsafe = if func.is_unsafe { "unsafe " } else { "" };
+    // trait implementations are guarded as a whole in `generate_trait_impls`
+    let cfg = match func.scope {
+      RustMethodScope::TraitImpl => String::new(),
+      _ => cfg_attribute(func.condition.as_ref()),
+    };
     Ok(match func.arguments {
          RustMethodArguments::SingleVariant(ref variant) => {
       let body = self
         .generate_ffi_call(variant, &Vec::new(), func.is_unsafe)?;
-      let return_type_for_signature = if variant.return_type.rust_api_type ==
-                                         RustType::EmptyTuple {
+      let api_return_type = variant.api_return_type();
+      let return_type_for_signature = if api_return_type == RustType::EmptyTuple {
         String::new()
       } else {
-        format!(" -> {}",
-                self.rust_type_to_code(&variant.return_type.rust_api_type))
+        format!(" -> {}", self.rust_type_to_code(&api_return_type))
       };
       let all_lifetimes: Vec<_> = variant
         .arguments
         .iter()
         .filter_map(|x| x.argument_type.rust_api_type.lifetime())
+        .filter(|x| !impl_lifetimes.contains(*x))
         .collect();
       let lifetimes_text = if all_lifetimes.is_empty() {
         String::new()
@@ -719,12 +1318,15 @@ impl<'a> RustCodeGenerator<'a> {
 
       format!("{doc}{maybe_pub}{maybe_unsafe}fn {name}{lifetimes_text}({args}){return_type} \
                  {{\n{body}}}\n\n",
-              doc = format_doc(&doc_formatter::method_doc(&func)),
+              doc = format_doc(&doc_formatter::method_doc(&func)) +
+                    &deprecated_attribute(func.deprecation.as_ref()) + &cfg,
               maybe_pub = maybe_pub,
               maybe_unsafe = maybe_unsafe,
               lifetimes_text = lifetimes_text,
               name = func.name.last_name()?,
-              args = self.arg_texts(&variant.arguments, None).join(", "),
+              args = self
+                .arg_texts(&variant.arguments, None, impl_lifetimes)
+                .join(", "),
               return_type = return_type_for_signature,
               body = body)
     }
@@ -762,7 +1364,7 @@ impl<'a> RustCodeGenerator<'a> {
         Some(ref lifetime) => format!("<'{}>", lifetime),
         None => String::new(),
       };
-      let mut args = self.arg_texts(shared_arguments, None);
+      let mut args = self.arg_texts(shared_arguments, None, impl_lifetimes);
       args.push(format!("{}: {}", variant_argument_name, tpl_type));
       let return_type_string = if let Some(ref t) = *common_return_type {
         self.rust_type_to_code(t)
@@ -770,7 +1372,8 @@ impl<'a> RustCodeGenerator<'a> {
         format!("{}::ReturnType", tpl_type)
       };
       format!(include_str!("../templates/crate/overloaded_function.rs.in"),
-              doc = format_doc(&doc_formatter::method_doc(&func)),
+              doc = format_doc(&doc_formatter::method_doc(&func)) +
+                    &deprecated_attribute(func.deprecation.as_ref()) + &cfg,
               maybe_pub = maybe_pub,
               maybe_unsafe = maybe_unsafe,
               tpl_decl = tpl_decl,
@@ -797,6 +1400,13 @@ impl<'a> RustCodeGenerator<'a> {
       code.push_str(&format!("pub extern crate {};\n\n", &dep.rust_export_info.crate_name));
     }
 
+    if modules.iter().any(has_platform_specific_types) {
+      code.push_str("// applies a `cfg` attribute to each of the items\n\
+                     macro_rules! cfg_items {\n\
+                     (#[$meta:meta] $($item:item)*) => { $(#[$meta] $item)* }\n\
+                     }\n\n");
+    }
+
     // some ffi functions are not used because
     // some Rust methods are filtered
     code.push_str("\
@@ -828,7 +1438,9 @@ impl<'a> RustCodeGenerator<'a> {
         .as_ref()
         .map(|d| format_doc(d))
         .unwrap_or_default();
-      code.push_str(&format!("{}pub mod {};\n", doc, &module.name));
+      // deprecated C++ items are wrapped by deprecated Rust items,
+      // and the generated code uses them internally
+      code.push_str(&format!("{}#[allow(deprecated)]\npub mod {};\n", doc, &module.name));
     }
 
     let src_path = self.config.output_path.with_added("src");
@@ -849,18 +1461,59 @@ impl<'a> RustCodeGenerator<'a> {
         .map(|t| format!("type {} = {};", t.name, self.rust_type_to_code(&t.value)))
         .join("\n");
 
-      let trait_content = if let Some(TraitImplExtra::CppDeletable { ref deleter_name }) =
-        trait1.extra {
-        format!("fn deleter() -> ::cpp_utils::Deleter<Self> {{\n  ::ffi::{}\n}}\n",
-                deleter_name)
+      let mut impl_lifetimes = Vec::new();
+      collect_lifetimes(&trait1.target_type, &mut impl_lifetimes);
+      collect_lifetimes(&trait1.trait_type, &mut impl_lifetimes);
+      for t in &trait1.associated_types {
+        collect_lifetimes(&t.value, &mut impl_lifetimes);
+      }
+      let impl_lifetimes_text = if impl_lifetimes.is_empty() {
+        String::new()
+      } else {
+        format!("<{}>",
+                impl_lifetimes
+                  .iter()
+                  .map(|x| format!("'{}", x))
+                  .join(", "))
+      };
+
+      let mut trait_content = trait1
+        .methods
+        .iter()
+        .map_if_ok(|method| self.generate_rust_final_function(method, &impl_lifetimes))?
+        .join("");
+      match trait1.extra {
+        Some(TraitImplExtra::CppDeletable { ref deleter_name }) => {
+          trait_content = format!("fn deleter() -> ::cpp_utils::Deleter<Self> {{\n  \
+                                   ::ffi::{}\n}}\n",
+                                  deleter_name);
+        }
+        Some(TraitImplExtra::PartialOrd) => {
+          trait_content.push_str(&format!("fn partial_cmp(&self, other: &{}) -> \
+                                           Option<::std::cmp::Ordering> {{\n\
+                                           if ::std::cmp::PartialOrd::lt(self, other) {{\n\
+                                           Some(::std::cmp::Ordering::Less)\n\
+                                           }} else if ::std::cmp::PartialOrd::lt(other, self) {{\n\
+                                           Some(::std::cmp::Ordering::Greater)\n\
+                                           }} else {{\n\
+                                           Some(::std::cmp::Ordering::Equal)\n\
+                                           }}\n}}\n\n",
+                                          self.rust_type_to_code(&trait1.target_type)));
+        }
+        Some(TraitImplExtra::CopyClone) => {
+          trait_content = format!("fn clone(&self) -> {} {{\n*self\n}}\n",
+                                  self.rust_type_to_code(&trait1.target_type));
+        }
+        None => {}
+      }
+      let condition = if trait1.methods.is_empty() {
+        None
       } else {
-        trait1
-          .methods
-          .iter()
-          .map_if_ok(|method| self.generate_rust_final_function(method))?
-          .join("")
+        or_conditions(trait1.methods.iter().map(|m| m.condition.as_ref()))
       };
-      results.push(format!("impl {} for {} {{\n{}{}}}\n\n",
+      results.push(format!("{}impl{} {} for {} {{\n{}{}}}\n\n",
+                           cfg_attribute(condition.as_ref()),
+                           impl_lifetimes_text,
                            self.rust_type_to_code(&trait1.trait_type),
                            self.rust_type_to_code(&trait1.target_type),
                            associated_types_text,
@@ -875,7 +1528,9 @@ impl<'a> RustCodeGenerator<'a> {
   fn generate_module_code(&self, data: &RustModule) -> Result<String> {
     let mut results = Vec::new();
     for type1 in &data.types {
-      results.push(format_doc(&doc_formatter::type_doc(type1)));
+      let type_code_start = results.len();
+      results.push(format_doc(&doc_formatter::type_doc(type1)?));
+      results.push(deprecated_attribute(type1.deprecation.as_ref()));
       let maybe_pub = if type1.is_public { "pub " } else { "" };
       match type1.kind {
         RustTypeDeclarationKind::CppTypeWrapper {
@@ -884,29 +1539,95 @@ impl<'a> RustCodeGenerator<'a> {
           ref methods,
           ref trait_impls,
           ref qt_receivers,
+          ref qt_properties,
           ..
         } => {
           let r = match *kind {
             RustTypeWrapperKind::Enum {
               ref values,
               ref is_flaggable,
+              ref representation,
             } => {
-              let mut r = format!(include_str!("../templates/crate/enum_declaration.rs.in"),
-                                  maybe_pub = maybe_pub,
-                                  name = type1.name.last_name()?,
-                                  variants = values
-                                    .iter()
-                                    .map(|item| {
-                                           format!("{}  {} = {}",
-                                              format_doc(&doc_formatter::enum_value_doc(&item)),
-                                              item.name,
-                                              item.value)
-                                         })
-                                    .join(", \n"));
+              let name = type1.name.last_name()?;
+              let mut r = match *representation {
+                RustEnumRepresentation::Enum => {
+                  format!(include_str!("../templates/crate/enum_declaration.rs.in"),
+                          maybe_pub = maybe_pub,
+                          name = name,
+                          variants = values
+                            .iter()
+                            .map(|item| {
+                                   format!("{}  {} = {}",
+                                           format_doc(&doc_formatter::enum_value_doc(&item)),
+                                           item.name,
+                                           item.value)
+                                 })
+                            .join(", \n"),
+                          name_arms = values
+                            .iter()
+                            .map(|item| {
+                                   format!("{}::{} => Some(\"{}\"),", name, item.name, item.name)
+                                 })
+                            .join("\n"),
+                          from_name_arms = enum_from_name_arms(name, values),
+                          try_from_arms = values
+                            .iter()
+                            .filter(|item| !item.is_dummy)
+                            .map(|item| {
+                                   format!("x if x == {} => Ok({}::{}),",
+                                           enum_value_int_code(name, item, representation),
+                                           name,
+                                           item.name)
+                                 })
+                            .join("\n"))
+                }
+                RustEnumRepresentation::Newtype => {
+                  format!(include_str!("../templates/crate/enum_newtype_declaration.rs.in"),
+                          maybe_pub = maybe_pub,
+                          name = name,
+                          constants = values
+                            .iter()
+                            .filter(|item| !item.is_dummy)
+                            .map(|item| {
+                                   let value = if item.value < i32::min_value() as i64 ||
+                                                  item.value > i32::max_value() as i64 {
+                                     format!("{}i64 as ::libc::c_int", item.value)
+                                   } else {
+                                     item.value.to_string()
+                                   };
+                                   format!("{}  pub const {}: {} = {}({});",
+                                           format_doc(&doc_formatter::enum_value_doc(&item)),
+                                           item.name,
+                                           name,
+                                           name,
+                                           value)
+                                 })
+                            .join("\n"),
+                          name_arms = values
+                            .iter()
+                            .filter(|item| !item.is_dummy)
+                            .map(|item| {
+                                   format!("x if x == {} => Some(\"{}\"),",
+                                           enum_value_int_code(name, item, representation),
+                                           item.name)
+                                 })
+                            .join("\n"),
+                          from_name_arms = enum_from_name_arms(name, values))
+                }
+              };
               if *is_flaggable {
                 r = r +
                     &format!(include_str!("../templates/crate/impl_flaggable.rs.in"),
-                             name = type1.name.last_name()?,
+                             name = name,
+                             named_values = values
+                               .iter()
+                               .filter(|item| !item.is_dummy)
+                               .map(|item| {
+                                      format!("(\"{}\", {})",
+                                              item.name,
+                                              enum_value_int_code(name, item, representation))
+                                    })
+                               .join(", "),
                              trait_type =
                                RustName::new(vec!["qt_core".to_string(),
                                                   "flags".to_string(),
@@ -918,6 +1639,7 @@ impl<'a> RustCodeGenerator<'a> {
             RustTypeWrapperKind::Struct {
               ref size_const_name,
               ref slot_wrapper,
+              ref subclass,
               ..
             } => {
               let mut r = if let Some(ref size_const_name) = *size_const_name {
@@ -956,6 +1678,9 @@ impl<'a> RustCodeGenerator<'a> {
                                     connections_mod = connections_mod,
                                     object_type_name = object_type_name));
               }
+              if let Some(ref subclass) = *subclass {
+                r.push_str(&self.subclass_code(type1.name.last_name()?, subclass)?);
+              }
               r
             }
           };
@@ -966,7 +1691,7 @@ impl<'a> RustCodeGenerator<'a> {
                                  methods
                                    .iter()
                                    .map_if_ok(|method| {
-                                                self.generate_rust_final_function(method)
+                                                self.generate_rust_final_function(method, &[])
                                               })?
                                    .join("")));
           }
@@ -979,6 +1704,10 @@ impl<'a> RustCodeGenerator<'a> {
                                                       "object".to_string(),
                                                       "Object".to_string()])?
                 .full_name(Some(&self.config.crate_properties.name()));
+            let connection_type = RustName::new(vec!["qt_core".to_string(),
+                                                     "meta_object".to_string(),
+                                                     "Connection".to_string()])?
+                .full_name(Some(&self.config.crate_properties.name()));
             let mut content = Vec::new();
             let obj_name = type1
               .name
@@ -1032,6 +1761,24 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
                       content.push(format!("impl<'a> {connections_mod}::Signal for {}<'a> {{}}\n",
                                            receiver.type_name,
                                            connections_mod = connections_mod));
+                      if let Some(ref closure_slot_type) = receiver.closure_slot_type {
+                        content.push(format!("\
+impl<'a> {type_name}<'a> {{
+{doc}pub fn connect_closure<F: FnMut({arguments}) + 'static>(&self, f: F) -> {connection_type} {{
+  let slot = {slot_type}::new(f);
+  let connection = {connections_mod}::Signal::connect(self, &slot);
+  // the sender owns the slot object, so the closure lives as long as the sender
+  slot.set_parent({connections_mod}::Receiver::object(self));
+  connection
+}}
+}}\n",
+                                             type_name = receiver.type_name,
+                                             arguments = arg_texts.join(", "),
+                                             connections_mod = connections_mod,
+                                             connection_type = connection_type,
+                                             slot_type = closure_slot_type.full_name(Some(&self.config.crate_properties.name())),
+                                             doc = format_doc(&doc_formatter::doc_for_qt_builtin_signal_connect_closure(cpp_type_name, receiver))));
+                      }
                     }
                     struct_content.push(format!("\
 {doc}pub fn {method_name}(&self) -> {type_name} {{
@@ -1060,6 +1807,9 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
             results.push(format!("/// Types for accessing built-in Qt signals and slots present in this module\n\
               pub mod connection {{\n{}\n}}\n\n", content.join("")));
           }
+          if !qt_properties.is_empty() {
+            results.push(self.qt_properties_code(type1, cpp_type_name, qt_properties, qt_receivers)?);
+          }
         }
         RustTypeDeclarationKind::MethodParametersTrait {
           ref shared_arguments,
@@ -1070,7 +1820,7 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
           ..
         } => {
           let arg_list = self
-            .arg_texts(shared_arguments, lifetime.as_ref())
+            .arg_texts(shared_arguments, lifetime.as_ref(), &[])
             .join(", ");
           let trait_lifetime_specifier = match *lifetime {
             Some(ref lf) => format!("<'{}>", lf),
@@ -1116,7 +1866,7 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
               None => String::new(),
             };
             let final_arg_list = self
-              .arg_texts(shared_arguments, final_lifetime.as_ref())
+              .arg_texts(shared_arguments, final_lifetime.as_ref(), &[])
               .join(", ");
             let tuple_item_types: Vec<_> = variant
               .arguments
@@ -1139,18 +1889,20 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
             }
             let return_type_string = match final_lifetime {
               Some(ref lifetime) => {
-                self.rust_type_to_code(&variant
-                                          .return_type
-                                          .rust_api_type
-                                          .with_lifetime(lifetime.to_string()))
+                let return_type = variant
+                  .return_type
+                  .rust_api_type
+                  .with_lifetime(lifetime.to_string());
+                self.rust_type_to_code(&variant.wrap_api_return_type(return_type))
               }
-              None => self.rust_type_to_code(&variant.return_type.rust_api_type),
+              None => self.rust_type_to_code(&variant.api_return_type()),
             };
             let return_type_decl = if common_return_type.is_some() {
               String::new()
             } else {
               format!("type ReturnType = {};", return_type_string)
             };
+            results.push(cfg_attribute(variant.cpp_method.cpp_method.condition.as_ref()));
             results.push(format!(include_str!("../templates/crate/impl_overloading_trait.rs.in"),
                                  maybe_unsafe = maybe_unsafe,
                                  lifetime_specifier = lifetime_specifier,
@@ -1171,10 +1923,99 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
 
           }
         }
+        RustTypeDeclarationKind::TypeAlias { ref target_type, .. } => {
+          results.push(format!("{maybe_pub}type {name} = {target_type};\n\n",
+                               maybe_pub = maybe_pub,
+                               name = type1.name.last_name()?,
+                               target_type = self.rust_type_to_code(target_type)));
+        }
+        RustTypeDeclarationKind::IteratorAdapter {
+          ref container_type,
+          ref iterator_type,
+          ref item_type,
+          ref begin,
+          ref end,
+          ref deref,
+          ref increment,
+          ref comparison,
+          ref comparison_is_equal,
+        } => {
+          // generates an FFI call with `self` and other arguments
+          // replaced with `arg_names`
+          let call = |variant: &RustMethodArgumentsVariant, arg_names: &[&str]| -> Result<String> {
+            let mut variant = variant.clone();
+            if variant.arguments.len() != arg_names.len() {
+              return Err(unexpected("unexpected arguments count of iterator method").into());
+            }
+            for (arg, arg_name) in variant.arguments.iter_mut().zip(arg_names) {
+              arg.name = arg_name.to_string();
+            }
+            Ok(format!("{{ {} }}", self.generate_ffi_call(&variant, &[], false)?))
+          };
+          let comparison_code = call(comparison, &["(&self.current)", "(&self.end)"])?;
+          let at_end_code = if *comparison_is_equal {
+            comparison_code
+          } else {
+            format!("!{}", comparison_code)
+          };
+          let name = type1.name.last_name()?;
+          let container_type_code = self.rust_type_to_code(container_type);
+          let item_type_code = self.rust_type_to_code(item_type);
+          results.push(format!("{maybe_pub}struct {name}<'a> {{\n\
+                                current: {iterator_type},\n\
+                                end: {iterator_type},\n\
+                                _container: ::std::marker::PhantomData<&'a {container_type}>,\n\
+                                }}\n\n\
+                                impl<'a> ::std::iter::Iterator for {name}<'a> {{\n\
+                                type Item = {item_type};\n\
+                                fn next(&mut self) -> Option<{item_type}> {{\n\
+                                if {at_end} {{\n\
+                                return None;\n\
+                                }}\n\
+                                let item = {deref};\n\
+                                {increment};\n\
+                                Some(item)\n\
+                                }}\n\
+                                }}\n\n\
+                                impl<'a> ::std::iter::IntoIterator for &'a {container_type} {{\n\
+                                type Item = {item_type};\n\
+                                type IntoIter = {name}<'a>;\n\
+                                fn into_iter(self) -> {name}<'a> {{\n\
+                                {name} {{\n\
+                                current: {begin},\n\
+                                end: {end},\n\
+                                _container: ::std::marker::PhantomData,\n\
+                                }}\n\
+                                }}\n\
+                                }}\n\n",
+                               maybe_pub = maybe_pub,
+                               name = name,
+                               iterator_type = self.rust_type_to_code(iterator_type),
+                               container_type = container_type_code,
+                               item_type = item_type_code,
+                               at_end = at_end_code,
+                               deref = call(deref, &["(&self.current)"])?,
+                               increment = call(increment, &["(&mut self.current)"])?,
+                               begin = call(begin, &["self"])?,
+                               end = call(end, &["self"])?));
+        }
       };
+      if let Some(ref condition) = type1.condition {
+        // all items generated for a platform-specific type
+        // receive the same `cfg` attribute
+        let code = results.split_off(type_code_start).join("");
+        results.push(format!("cfg_items! {{\n#[cfg({})]\n{}}}\n\n", condition.to_cfg(), code));
+      }
+    }
+    for constant in &data.constants {
+      results.push(format!("{doc}pub const {name}: {value_type} = {value};\n\n",
+                           doc = format_doc(&doc_formatter::constant_doc(constant)),
+                           name = constant.name.last_name()?,
+                           value_type = self.rust_type_to_code(&constant.value_type),
+                           value = constant.value));
     }
     for method in &data.functions {
-      results.push(self.generate_rust_final_function(method)?);
+      results.push(self.generate_rust_final_function(method, &[])?);
     }
     results.push(self.generate_trait_impls(&data.trait_impls)?);
     for submodule in &data.submodules {
@@ -1231,8 +2072,9 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
                                      type1
                                        .name
                                        .full_name(Some(&self.config.crate_properties.name())),
-                                   pub_type_name = slot_wrapper.public_type_name,
+                                   pub_type_name = slot_wrapper.public_type_name.last_name()?,
                                    callback_name = slot_wrapper.callback_name,
+                                   deleter_name = format!("{}_deleter", slot_wrapper.callback_name),
                                    args = args,
                                    args_tuple = args_tuple,
                                    connections_mod = connections_mod,
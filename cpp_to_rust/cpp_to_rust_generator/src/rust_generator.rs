@@ -1,9 +1,11 @@
 //! Generates Rust public API and FFI functions
 
 use caption_strategy::TypeCaptionStrategy;
-use cpp_data::{CppTypeKind, CppEnumValue, CppTypeAllocationPlace, CppDataWithDeps};
+use cpp_data::{CppTypeKind, CppEnumValue, CppTypeAllocationPlace, CppDataWithDeps, CppTypedef,
+               CppVariable, CppLiteralValue, CppMacro, CppVisibility, CppDeprecation};
 use cpp_ffi_data::{CppAndFfiMethod, CppFfiArgumentMeaning, CppFfiType, CppIndirectionChange,
-                   CppFfiMethodKind, CppCast, CppFfiHeaderData};
+                   CppFfiMethodKind, CppCast, CppFfiHeaderData, CppSubclass,
+                   CppSubclassVirtualMethod};
 use cpp_method::{CppMethod, ReturnValueAllocationPlace};
 use cpp_operator::CppOperator;
 use cpp_type::{CppType, CppTypeBase, CppBuiltInNumericType, CppTypeIndirection,
@@ -11,12 +13,17 @@ use cpp_type::{CppType, CppTypeBase, CppBuiltInNumericType, CppTypeIndirection,
                CppFunctionPointerType};
 use common::errors::{Result, ChainErr, unexpected};
 use common::log;
+use common::target::Condition;
+use config::CppCallback;
+use cpp_multi_target::{and_conditions, or_conditions};
 use rust_info::{RustTypeDeclaration, RustTypeDeclarationKind, RustTypeWrapperKind, RustModule,
                 RustMethod, RustMethodScope, RustMethodArgument, RustMethodArgumentsVariant,
                 RustMethodArguments, TraitImpl, TraitImplExtra, RustEnumValue,
                 RustProcessedTypeInfo, RustMethodDocItem, RustQtReceiverDeclaration,
-                RustQtReceiverType, RustQtSlotWrapper, RustMethodSelfArgKind,
-                RustMethodCaptionStrategy, RustFFIFunction, RustFFIArgument, TraitAssociatedType};
+                RustQtReceiverType, RustQtSlotWrapper, RustQtPropertyDeclaration,
+                RustMethodSelfArgKind,
+                RustMethodCaptionStrategy, RustFFIFunction, RustFFIArgument, TraitAssociatedType,
+                RustConstant, RustSubclass, RustSubclassVirtualMethod, RustEnumRepresentation};
 use rust_type::{RustName, RustType, CompleteType, RustTypeIndirection, RustToCTypeConversion};
 use common::string_utils::{CaseOperations, WordIterator};
 use common::utils::{add_to_multihash, MapIfOk};
@@ -62,8 +69,9 @@ enum Case {
 }
 
 /// Returns name of the Rust function that will provide access
-/// to a C++ operator. Most of these functions should be replaced
-/// with trait implementations in the future.
+/// to a C++ operator. This name is used for operators that
+/// don't have a corresponding trait (see `operator_trait`)
+/// or can't be represented as a trait implementation.
 fn operator_rust_name(operator: &CppOperator) -> Result<String> {
   Ok(match *operator {
        CppOperator::Conversion(ref type1) => {
@@ -76,6 +84,154 @@ fn operator_rust_name(operator: &CppOperator) -> Result<String> {
      })
 }
 
+/// Shape of a trait corresponding to a C++ operator.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum OperatorTraitKind {
+  /// `fn op(self) -> Self::Output` (e.g. `Neg`)
+  Unary,
+  /// `fn op(self, rhs: Rhs) -> Self::Output` (e.g. `Add`)
+  Binary,
+  /// `fn op(&mut self, rhs: Rhs)` (e.g. `AddAssign`)
+  Assign,
+  /// `Index` or `IndexMut`, depending on constness of the method.
+  Index,
+  /// `fn op(&self, other: &Rhs) -> bool` (e.g. `PartialEq::eq`)
+  Comparison,
+}
+
+impl OperatorTraitKind {
+  /// Returns total number of arguments of the operator, including `self`.
+  fn arguments_count(&self) -> usize {
+    match *self {
+      OperatorTraitKind::Unary => 1,
+      _ => 2,
+    }
+  }
+}
+
+/// Returns name of the Rust trait corresponding to a C++ operator,
+/// name of the trait's method implemented by the operator and
+/// kind of the trait. Returns `None` if the operator doesn't have
+/// a corresponding trait. `operator!` is not mapped to `Not` because
+/// its C++ semantics (logical negation returning `bool`) differs
+/// from the usual semantics of `Not` for non-boolean types.
+fn operator_trait(operator: &CppOperator)
+                  -> Option<(&'static str, &'static str, &'static str, OperatorTraitKind)> {
+  use self::OperatorTraitKind::*;
+  Some(match *operator {
+         CppOperator::Addition => ("ops", "Add", "add", Binary),
+         CppOperator::Subtraction => ("ops", "Sub", "sub", Binary),
+         CppOperator::Multiplication => ("ops", "Mul", "mul", Binary),
+         CppOperator::Division => ("ops", "Div", "div", Binary),
+         CppOperator::Modulo => ("ops", "Rem", "rem", Binary),
+         CppOperator::BitwiseAnd => ("ops", "BitAnd", "bitand", Binary),
+         CppOperator::BitwiseOr => ("ops", "BitOr", "bitor", Binary),
+         CppOperator::BitwiseXor => ("ops", "BitXor", "bitxor", Binary),
+         CppOperator::UnaryMinus => ("ops", "Neg", "neg", Unary),
+         CppOperator::BitwiseNot => ("ops", "Not", "not", Unary),
+         CppOperator::AdditionAssignment => ("ops", "AddAssign", "add_assign", Assign),
+         CppOperator::SubtractionAssignment => ("ops", "SubAssign", "sub_assign", Assign),
+         CppOperator::MultiplicationAssignment => ("ops", "MulAssign", "mul_assign", Assign),
+         CppOperator::DivisionAssignment => ("ops", "DivAssign", "div_assign", Assign),
+         CppOperator::ModuloAssignment => ("ops", "RemAssign", "rem_assign", Assign),
+         CppOperator::BitwiseAndAssignment => ("ops", "BitAndAssign", "bitand_assign", Assign),
+         CppOperator::BitwiseOrAssignment => ("ops", "BitOrAssign", "bitor_assign", Assign),
+         CppOperator::BitwiseXorAssignment => ("ops", "BitXorAssign", "bitxor_assign", Assign),
+         CppOperator::Subscript => ("ops", "Index", "index", Index),
+         CppOperator::EqualTo => ("cmp", "PartialEq", "eq", Comparison),
+         CppOperator::NotEqualTo => ("cmp", "PartialEq", "ne", Comparison),
+         CppOperator::LessThan => ("cmp", "PartialOrd", "lt", Comparison),
+         CppOperator::LessThanOrEqualTo => ("cmp", "PartialOrd", "le", Comparison),
+         CppOperator::GreaterThan => ("cmp", "PartialOrd", "gt", Comparison),
+         CppOperator::GreaterThanOrEqualTo => ("cmp", "PartialOrd", "ge", Comparison),
+         _ => return None,
+       })
+}
+
+/// Returns an error if a trait implementation generated from C++ operators
+/// can't be compiled because it lacks some required methods or
+/// required implementations of supertraits. `all` contains all trait implementations
+/// generated from C++ operators in the same scope.
+fn check_operator_trait_impl(trait_impl: &TraitImpl, all: &[TraitImpl]) -> Result<()> {
+  let (trait_name, generic_arguments) = if let RustType::Common {
+           ref base,
+           ref generic_arguments,
+           ..
+         } = trait_impl.trait_type {
+    (base.last_name()?, generic_arguments)
+  } else {
+    return Err(unexpected("invalid trait type").into());
+  };
+  let find_impl = |name: &str| {
+    all
+      .iter()
+      .find(|x| {
+        x.target_type == trait_impl.target_type &&
+        if let RustType::Common {
+                 ref base,
+                 generic_arguments: ref other_generic_arguments,
+                 ..
+               } = x.trait_type {
+          base.last_name().ok().map_or(false, |n| n == name) &&
+          other_generic_arguments == generic_arguments
+        } else {
+          false
+        }
+      })
+  };
+  let has_method = |trait_impl: &TraitImpl, name: &str| {
+    trait_impl
+      .methods
+      .iter()
+      .any(|m| m.name.last_name().ok().map_or(false, |n| n == name))
+  };
+  match trait_name.as_str() {
+    "PartialEq" => {
+      if !has_method(trait_impl, "eq") {
+        return Err("PartialEq requires operator==".into());
+      }
+    }
+    "PartialOrd" => {
+      if !has_method(trait_impl, "lt") {
+        return Err("PartialOrd requires operator<".into());
+      }
+      if let Some(ref generic_arguments) = *generic_arguments {
+        if generic_arguments != &vec![trait_impl.target_type.clone()] {
+          return Err("PartialOrd is only supported for operator< with the same argument types"
+                       .into());
+        }
+      }
+      match find_impl("PartialEq") {
+        Some(partial_eq) => {
+          if !has_method(partial_eq, "eq") {
+            return Err("PartialOrd requires PartialEq".into());
+          }
+        }
+        None => return Err("PartialOrd requires PartialEq".into()),
+      }
+    }
+    "IndexMut" => {
+      let index = find_impl("Index").chain_err(|| "IndexMut requires Index")?;
+      let output_type = |trait_impl: &TraitImpl| -> Result<RustType> {
+        let method = trait_impl
+          .methods
+          .get(0)
+          .chain_err(|| "no methods in Index impl")?;
+        if let RustMethodArguments::SingleVariant(ref variant) = method.arguments {
+          variant.return_type.rust_api_type.ref_to_value()
+        } else {
+          Err(unexpected("unexpected overloaded method").into())
+        }
+      };
+      if output_type(index)? != output_type(trait_impl)? {
+        return Err("Index and IndexMut have different output types".into());
+      }
+    }
+    _ => {}
+  }
+  Ok(())
+}
+
 /// If `remove_qt_prefix` is true, removes "Q" or "Qt"
 /// if it is first word of the string and not the only one word.
 /// Also converts case of the words.
@@ -253,6 +409,12 @@ pub struct RustGeneratorInputData<'a> {
   pub remove_qt_prefix: bool,
   /// List of namespaces to filter out during code generation
   pub filtered_namespaces: Vec<String>,
+  /// Callback arguments configured with `Config::add_cpp_callback`
+  pub cpp_callbacks: Vec<CppCallback>,
+  /// Default representation of enums
+  pub enum_representation: RustEnumRepresentation,
+  /// Representations of enums overridden with `Config::set_cpp_enum_representation`
+  pub cpp_enum_representations: HashMap<String, RustEnumRepresentation>,
 }
 
 impl<'a> RustGeneratorInputData<'a> {
@@ -300,6 +462,28 @@ impl<'a> RustGeneratorInputData<'a> {
           true
         })
         .collect();
+      for typedef in &generator.input_data.cpp_data.current.parser.typedefs {
+        let rust_name =
+          generator
+            .calculate_rust_name(&typedef.name, &typedef.include_file, false, None)?;
+        if !module_names_set.contains(&rust_name.parts[1]) {
+          module_names_set.insert(rust_name.parts[1].clone());
+        }
+      }
+      for variable in &generator.input_data.cpp_data.current.parser.variables {
+        if variable.value.is_some() {
+          let rust_name = generator.constant_rust_name(variable)?;
+          if !module_names_set.contains(&rust_name.parts[1]) {
+            module_names_set.insert(rust_name.parts[1].clone());
+          }
+        }
+      }
+      for cpp_macro in &generator.input_data.cpp_data.current.parser.macros {
+        let rust_name = generator.macro_rust_name(cpp_macro)?;
+        if !module_names_set.contains(&rust_name.parts[1]) {
+          module_names_set.insert(rust_name.parts[1].clone());
+        }
+      }
       for method in cpp_methods.clone() {
         if method.cpp_method.class_membership.is_none() {
           let rust_name = generator.free_function_rust_name(&method.cpp_method)?;
@@ -369,6 +553,9 @@ struct GenerateTypeResult {
   main_type: RustTypeDeclaration,
   /// Rust declarations of the types created for overloading emulation.
   overloading_types: Vec<RustTypeDeclaration>,
+  /// Rust declaration of the iterator type created for the type
+  /// passed to the function, if any.
+  iterator_adapter: Option<RustTypeDeclaration>,
 }
 
 /// Output data of `RustGenerator::process_all_sibling_functions` function.
@@ -382,6 +569,42 @@ struct ProcessFunctionsResult {
   overloading_types: Vec<RustTypeDeclaration>,
 }
 
+/// Converts a C callback type to a closure type that is
+/// passed to the callback through the user data argument.
+/// `user_data_ffi_index` is index of the user data argument in the FFI function's
+/// arguments, and `callback_user_data_index` is its index in the callback's arguments.
+fn closure_callback_type(callback_type: &CompleteType,
+                         user_data_ffi_index: usize,
+                         callback_user_data_index: usize)
+                         -> Result<CompleteType> {
+  let closure_type = if let RustType::FunctionPointer {
+           ref return_type,
+           ref arguments,
+         } = callback_type.rust_ffi_type {
+    RustType::Closure {
+      return_type: return_type.clone(),
+      arguments: arguments
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| index != callback_user_data_index)
+        .map(|(_, arg)| arg.clone())
+        .collect(),
+      is_boxed: false,
+      is_send: false,
+      lifetime: None,
+    }
+  } else {
+    return Err(unexpected("function pointer expected for a callback argument").into());
+  };
+  let mut r = callback_type.clone();
+  r.rust_api_type = closure_type;
+  r.rust_api_to_c_conversion = RustToCTypeConversion::ClosureToCallback {
+    user_data_ffi_index: user_data_ffi_index,
+    callback_user_data_index: callback_user_data_index,
+  };
+  Ok(r)
+}
+
 /// Generates `CompleteType` from `CppFfiType`, adding
 /// Rust API type, Rust FFI type and conversion between them.
 fn complete_type(processed_types: &[RustProcessedTypeInfo],
@@ -480,7 +703,26 @@ fn complete_type(processed_types: &[RustProcessedTypeInfo],
         }
         rust_api_to_c_conversion = RustToCTypeConversion::RefToPtr;
       }
-      CppIndirectionChange::QFlagsToUInt => {}
+      CppIndirectionChange::QFlagsToUInt |
+      CppIndirectionChange::StdFunctionToClosure => {}
+    }
+  }
+  if cpp_ffi_type.conversion == CppIndirectionChange::StdFunctionToClosure {
+    if let CppTypeBase::StdFunction(ref function) = cpp_ffi_type.original_type.base {
+      let mut arguments = Vec::new();
+      for arg in &function.arguments {
+        arguments.push(ffi_type(processed_types, dependency_types, arg)?);
+      }
+      rust_api_type = RustType::Closure {
+        return_type: Box::new(ffi_type(processed_types, dependency_types, &function.return_type)?),
+        arguments: arguments,
+        is_boxed: true,
+        is_send: argument_meaning != &CppFfiArgumentMeaning::ReturnValue,
+        lifetime: None,
+      };
+      rust_api_to_c_conversion = RustToCTypeConversion::BoxToCppClosure;
+    } else {
+      return Err(unexpected("invalid original type for StdFunctionToClosure").into());
     }
   }
   if cpp_ffi_type.conversion == CppIndirectionChange::QFlagsToUInt {
@@ -551,6 +793,94 @@ fn find_type_info<'a, F>(processed_types: &'a [RustProcessedTypeInfo],
   }
 }
 
+/// Returns `&'static str` type used for string constants.
+fn static_str_type() -> Result<RustType> {
+  Ok(RustType::Common {
+       base: RustName::new(vec!["str".to_string()])?,
+       generic_arguments: None,
+       is_const: true,
+       is_const2: false,
+       indirection: RustTypeIndirection::Ref { lifetime: Some("static".to_string()) },
+     })
+}
+
+/// Generates a Rust constant for C++ macro `cpp_macro`.
+/// Integer macros are typed as `i32` if the value fits into it
+/// and as `i64` otherwise.
+fn generate_macro_constant(cpp_macro: &CppMacro, rust_name: RustName) -> Result<RustConstant> {
+  let common_type = |name: &str| -> Result<RustType> {
+    Ok(RustType::Common {
+         base: RustName::new(vec![name.to_string()])?,
+         generic_arguments: None,
+         is_const: false,
+         is_const2: false,
+         indirection: RustTypeIndirection::None,
+       })
+  };
+  let (value_type, value) = match cpp_macro.value {
+    CppLiteralValue::Bool(value) => (common_type("bool")?, value.to_string()),
+    CppLiteralValue::Integer(value) => {
+      let type_name = if value >= i32::min_value() as i64 && value <= i32::max_value() as i64 {
+        "i32"
+      } else {
+        "i64"
+      };
+      (common_type(type_name)?, value.to_string())
+    }
+    CppLiteralValue::Float(ref value) => (common_type("f64")?, value.clone()),
+    CppLiteralValue::String(ref value) => (static_str_type()?, format!("{:?}", value)),
+  };
+  Ok(RustConstant {
+       name: rust_name,
+       value_type: value_type,
+       value: value,
+       cpp_name: cpp_macro.name.clone(),
+       is_cpp_macro: true,
+     })
+}
+
+/// Returns minimal and maximal values of integer type `base`
+/// that can be represented on all supported platforms.
+fn integer_range(base: &CppTypeBase) -> Result<(i64, i64)> {
+  use cpp_type::CppBuiltInNumericType::*;
+  let (bits, is_signed) = match *base {
+    CppTypeBase::BuiltInNumeric(ref numeric) => {
+      match *numeric {
+        // signedness of `char` depends on the platform
+        Char => return Ok((0, i8::max_value() as i64)),
+        SChar => (8, true),
+        UChar => (8, false),
+        // `wchar_t` is 16-bit on Windows
+        WChar | Char16 | UShort => (16, false),
+        Short => (16, true),
+        // `long` is 32-bit on Windows
+        Int | Long => (32, true),
+        Char32 | UInt | ULong => (32, false),
+        LongLong | Int128 => (64, true),
+        ULongLong | UInt128 => (64, false),
+        Bool | Float | Double | LongDouble => {
+          return Err(unexpected("integer type expected").into());
+        }
+      }
+    }
+    CppTypeBase::SpecificNumeric(CppSpecificNumericType {
+                                   ref bits,
+                                   kind: CppSpecificNumericTypeKind::Integer { ref is_signed },
+                                   ..
+                                 }) => (*bits, *is_signed),
+    // pointer sized integers are 32-bit on some platforms
+    CppTypeBase::PointerSizedInteger { ref is_signed, .. } => (32, *is_signed),
+    _ => return Err(unexpected("integer type expected").into()),
+  };
+  Ok(if bits >= 64 {
+       (if is_signed { i64::min_value() } else { 0 }, i64::max_value())
+     } else if is_signed {
+       (-(1 << (bits - 1)), (1 << (bits - 1)) - 1)
+     } else {
+       (0, (1 << bits) - 1)
+     })
+}
+
 /// Converts `CppType` to its exact Rust equivalent (FFI-compatible)
 fn ffi_type(processed_types: &[RustProcessedTypeInfo],
             dependency_types: &[&[RustProcessedTypeInfo]],
@@ -631,7 +961,13 @@ fn ffi_type(processed_types: &[RustProcessedTypeInfo],
                   return_type: Box::new(rust_return_type),
                 });
     }
-    CppTypeBase::TemplateParameter { .. } => return Err(unexpected("invalid cpp type").into()),
+    CppTypeBase::StdFunction(..) => {
+      RustName::new(vec!["cpp_utils".to_string(), "CppClosure".to_string()])?
+    }
+    CppTypeBase::TemplateParameter { .. } |
+    CppTypeBase::TemplateArgumentValue { .. } => {
+      return Err(unexpected("invalid cpp type").into())
+    }
   };
   Ok(RustType::Common {
        base: rust_name,
@@ -699,11 +1035,15 @@ impl<'aa> RustGenerator<'aa> {
                   methods: Vec::new(),
                   trait_impls: Vec::new(),
                   qt_receivers: Vec::new(),
+                  qt_properties: Vec::new(),
                 },
                 is_public: info.is_public,
                 rust_doc: None,
+                deprecation: self.type_deprecation(info),
+                condition: self.type_condition(info),
               },
               overloading_types: Vec::new(),
+              iterator_adapter: None,
             },
             cpp_methods)
          }
@@ -720,6 +1060,11 @@ impl<'aa> RustGenerator<'aa> {
       let class_type = CppTypeClassBase {
         name: info.cpp_name.clone(),
         template_arguments: info.cpp_template_arguments.clone(),
+        enclosing_template_class: self
+          .input_data
+          .cpp_data
+          .find_type_info(|x| x.name == info.cpp_name)
+          .and_then(|x| x.enclosing_template_class.clone()),
       };
       let mut good_methods = Vec::new();
       let mut tmp_cpp_methods = Vec::new();
@@ -733,9 +1078,49 @@ impl<'aa> RustGenerator<'aa> {
         tmp_cpp_methods.push(method);
       }
       cpp_methods = tmp_cpp_methods;
-      let functions_result =
+      let mut functions_result =
         self
-          .process_all_sibling_functions(good_methods.into_iter(), &methods_scope)?;
+          .process_all_sibling_functions(good_methods.iter().cloned(), &methods_scope)?;
+      let iterator_adapter = if let RustMethodScope::Impl { ref target_type } = methods_scope {
+        match self.generate_iterator_adapter(info, target_type, &good_methods) {
+          Ok(r) => r,
+          Err(err) => {
+            log::llog(log::DebugRustSkips, || {
+              format!("Can't generate iterator adapter for {}: {}",
+                      info.cpp_name,
+                      err)
+            });
+            err.discard_expected();
+            None
+          }
+        }
+      } else {
+        None
+      };
+      if info.cpp_template_arguments.is_none() &&
+         self.input_data.cpp_data.is_trivially_copyable(&info.cpp_name) &&
+         self
+           .input_data
+           .cpp_data
+           .type_allocation_place(&info.cpp_name)? == CppTypeAllocationPlace::Stack {
+        // `Copy` types can't implement `Drop`, and the trivial destructor
+        // doesn't need to be called anyway. `Clone` generated from
+        // a public copy constructor is replaced with the `Copy`-based one.
+        functions_result
+          .trait_impls
+          .retain(|x| if let RustType::Common { ref base, .. } = x.trait_type {
+                    base.parts != vec!["Drop".to_string()] &&
+                    base.parts !=
+                    vec!["std".to_string(), "clone".to_string(), "Clone".to_string()]
+                  } else {
+                    true
+                  });
+        if let RustMethodScope::Impl { ref target_type } = methods_scope {
+          functions_result
+            .trait_impls
+            .append(&mut self.copy_trait_impls(target_type)?);
+        }
+      }
 
       let mut qt_receivers_by_name: HashMap<String, Vec<_>> = HashMap::new();
       if self
@@ -785,6 +1170,7 @@ impl<'aa> RustGenerator<'aa> {
                        .rust_api_type
                        .with_lifetime("static".to_string()))
               })?,
+                                 closure_slot_type: None,
                                });
             }
           }
@@ -815,7 +1201,16 @@ impl<'aa> RustGenerator<'aa> {
           })
                       .collect()
                   })
+        .map(|mut r: RustQtReceiverDeclaration| {
+               if r.receiver_type == RustQtReceiverType::Signal {
+                 r.closure_slot_type = self.find_closure_slot_type(&r.arguments);
+               }
+               r
+             })
         .collect();
+      let qt_properties =
+        self
+          .generate_qt_properties(info, &functions_result.methods, &qt_receivers)?;
 
       (GenerateTypeResult {
          main_type: RustTypeDeclaration {
@@ -828,17 +1223,189 @@ impl<'aa> RustGenerator<'aa> {
              methods: functions_result.methods,
              trait_impls: functions_result.trait_impls,
              qt_receivers: qt_receivers,
+             qt_properties: qt_properties,
            },
            is_public: info.is_public,
            rust_doc: None,
+           deprecation: self.type_deprecation(info),
+           condition: self.type_condition(info),
          },
          overloading_types: functions_result.overloading_types,
+         iterator_adapter: iterator_adapter,
        },
        cpp_methods)
     }
        })
   }
 
+  /// Generates an iterator type for the container type `info` if
+  /// its C++ class provides a pair of iterators
+  /// (see `CppDataWithDeps::container_iterator`).
+  /// `methods` are FFI methods of the container class.
+  fn generate_iterator_adapter(&self,
+                               info: &RustProcessedTypeInfo,
+                               container_type: &RustType,
+                               methods: &[&CppAndFfiMethod])
+                               -> Result<Option<RustTypeDeclaration>> {
+    let container_iterator = match self.input_data.cpp_data.container_iterator(&info.cpp_name) {
+      Some(r) => r,
+      None => return Ok(None),
+    };
+    let scope = RustMethodScope::Impl { target_type: container_type.clone() };
+    let find_getter = |name: &str| -> Result<RustMethodArgumentsVariant> {
+      let method = methods
+        .iter()
+        .find(|m| {
+                m.cpp_method.name == name && m.cpp_method.arguments.is_empty() &&
+                m.allocation_place == ReturnValueAllocationPlace::Stack
+              })
+        .chain_err(|| format!("stack-allocated {} method not found", name))?;
+      Ok(self.generate_rust_single_method(method, &scope, false)?.arguments)
+    };
+    let begin = find_getter(&container_iterator.begin_method)?;
+    let end = find_getter(&container_iterator.end_method)?;
+    if begin.return_type.rust_api_type != end.return_type.rust_api_type {
+      return Err("begin and end methods return different types".into());
+    }
+    let iterator_class_type = match begin.cpp_method.cpp_method.return_type.base {
+      CppTypeBase::Class(ref class_type) => class_type.clone(),
+      _ => return Err(unexpected("iterator must be a class type").into()),
+    };
+    let iterator_type = begin.return_type.rust_api_type.clone();
+    let iterator_scope = RustMethodScope::Impl { target_type: iterator_type.clone() };
+
+    let find_operator = |operators: &[CppOperator],
+                         arguments_count: usize,
+                         must_be_const: bool|
+                         -> Result<RustMethodArgumentsVariant> {
+      for header in &self.input_data.cpp_ffi_headers {
+        for method in &header.methods {
+          if method.allocation_place == ReturnValueAllocationPlace::Heap ||
+             method.cpp_method.arguments.len() != arguments_count {
+            continue;
+          }
+          if let Some(ref membership) = method.cpp_method.class_membership {
+            if membership.class_type != iterator_class_type ||
+               membership.visibility != CppVisibility::Public ||
+               (must_be_const && !membership.is_const) {
+              continue;
+            }
+          } else {
+            continue;
+          }
+          if let Some(ref operator) = method.cpp_method.operator {
+            if operators.contains(operator) {
+              return Ok(self
+                          .generate_rust_single_method(method, &iterator_scope, false)?
+                          .arguments);
+            }
+          }
+        }
+      }
+      Err(format!("operator not found in iterator class {}",
+                  iterator_class_type.to_cpp_pseudo_code())
+              .into())
+    };
+    let deref = find_operator(&[CppOperator::Indirection], 0, true)?;
+    let increment = find_operator(&[CppOperator::PrefixIncrement], 0, false)?;
+    let comparison = find_operator(&[CppOperator::NotEqualTo, CppOperator::EqualTo], 1, true)?;
+    let comparison_is_equal = comparison.cpp_method.cpp_method.operator ==
+                              Some(CppOperator::EqualTo);
+    if [&begin, &end, &deref, &increment, &comparison]
+         .iter()
+         .any(|v| v.exception_slot_ffi_index().is_some()) {
+      return Err("iterator methods may throw C++ exceptions".into());
+    }
+    if comparison.return_type.rust_api_type !=
+       (RustType::Common {
+          base: RustName::new(vec!["bool".to_string()])?,
+          indirection: RustTypeIndirection::None,
+          is_const: false,
+          is_const2: false,
+          generic_arguments: None,
+        }) {
+      return Err("iterator comparison operator must return bool".into());
+    }
+    if !comparison
+          .arguments
+          .iter()
+          .all(|arg| arg.argument_type.rust_api_type.is_ref()) {
+      return Err("iterator comparison operator must receive iterators by reference".into());
+    }
+    let item_type = if deref.return_type.rust_api_type.is_ref() {
+      if !deref.return_type.rust_api_type.is_const()? {
+        // a mutable reference can't be produced from an immutable
+        // borrow of the container
+        return Err("iterator returns a mutable reference".into());
+      }
+      deref
+        .return_type
+        .rust_api_type
+        .with_lifetime("a".to_string())
+    } else {
+      deref.return_type.rust_api_type.clone()
+    };
+    let condition = [&begin, &end, &deref, &increment, &comparison]
+      .iter()
+      .fold(None, |condition, v| {
+        and_conditions(condition.as_ref(),
+                       v.cpp_method.cpp_method.condition.as_ref())
+      });
+    let name = {
+      let mut name = info.rust_name.clone();
+      let last_name = format!("{}Iter", info.rust_name.last_name()?);
+      *name.parts.last_mut().chain_err(|| "empty RustName")? = last_name;
+      name
+    };
+    if self.processed_types.iter().any(|t| t.rust_name == name) {
+      return Err(format!("name conflict: {}", name.full_name(None)).into());
+    }
+    Ok(Some(RustTypeDeclaration {
+              name: name,
+              kind: RustTypeDeclarationKind::IteratorAdapter {
+                container_type: container_type.clone(),
+                iterator_type: iterator_type,
+                item_type: item_type,
+                begin: begin,
+                end: end,
+                deref: deref,
+                increment: increment,
+                comparison: comparison,
+                comparison_is_equal: comparison_is_equal,
+              },
+              is_public: info.is_public,
+              rust_doc: None,
+              deprecation: None,
+              condition: condition,
+            }))
+  }
+
+  /// Returns target condition of the C++ type corresponding to `info`.
+  fn type_condition(&self, info: &RustProcessedTypeInfo) -> Option<Condition> {
+    self
+      .input_data
+      .cpp_data
+      .current
+      .parser
+      .types
+      .iter()
+      .find(|t| t.name == info.cpp_name)
+      .and_then(|t| t.condition.clone())
+  }
+
+  /// Returns deprecation information of the C++ type corresponding to `info`.
+  fn type_deprecation(&self, info: &RustProcessedTypeInfo) -> Option<CppDeprecation> {
+    self
+      .input_data
+      .cpp_data
+      .current
+      .parser
+      .types
+      .iter()
+      .find(|t| t.name == info.cpp_name)
+      .and_then(|t| t.deprecation.clone())
+  }
+
   /// Returns full name of the Rust method corresponding to `cpp_method`.
   fn free_function_rust_name(&self, cpp_method: &CppMethod) -> Result<RustName> {
     self.calculate_rust_name(&cpp_method.name,
@@ -867,21 +1434,93 @@ impl<'aa> RustGenerator<'aa> {
   }
 
 
+  /// Finds a callback argument of `method` that should be converted to a closure
+  /// according to `Config::add_cpp_callback`.
+  /// Returns indexes of the callback and its user data argument
+  /// in the FFI function's arguments and index of the user data argument
+  /// in the callback's arguments.
+  fn find_closure_callback(&self, method: &CppAndFfiMethod) -> Option<(usize, usize, usize)> {
+    let method_name = method.cpp_method.full_name();
+    let callback = match self
+            .input_data
+            .cpp_callbacks
+            .iter()
+            .find(|x| x.method_name == method_name) {
+      Some(callback) => callback,
+      None => return None,
+    };
+    let arguments = &method.c_signature.arguments;
+    let is_user_data = |t: &CppType| {
+      t.base == CppTypeBase::Void && t.indirection == CppTypeIndirection::Ptr && !t.is_const
+    };
+    let callback_user_data_index = |t: &CppType| -> Option<usize> {
+      if let CppTypeBase::FunctionPointer(ref function) = t.base {
+        if t.indirection == CppTypeIndirection::None && !function.allows_variadic_arguments {
+          let indexes: Vec<_> = function
+            .arguments
+            .iter()
+            .enumerate()
+            .filter(|&(_, arg)| is_user_data(arg))
+            .map(|(index, _)| index)
+            .collect();
+          if indexes.len() == 1 {
+            return Some(indexes[0]);
+          }
+        }
+      }
+      None
+    };
+    let find_argument = |name: &str| {
+      arguments
+        .iter()
+        .position(|arg| arg.meaning.is_argument() && arg.name == name)
+    };
+    if let (Some(callback_index), Some(user_data_index)) =
+      (find_argument(callback.callback_argument.as_str()),
+       find_argument(callback.user_data_argument.as_str())) {
+      if is_user_data(&arguments[user_data_index].argument_type.ffi_type) {
+        if let Some(index) =
+          callback_user_data_index(&arguments[callback_index].argument_type.ffi_type) {
+          return Some((callback_index, user_data_index, index));
+        }
+      }
+    }
+    log::llog(log::DebugRustSkips, || {
+      format!("Configured callback arguments are not applicable to method: {}",
+              method.short_text())
+    });
+    None
+  }
+
   /// Converts one function to a `RustSingleMethod`.
   fn generate_rust_single_method(&self,
                                  method: &CppAndFfiMethod,
                                  scope: &RustMethodScope,
                                  generate_doc: bool)
                                  -> Result<RustSingleMethod> {
+    let closure_callback = self.find_closure_callback(method);
     let mut arguments = Vec::new();
     for (arg_index, arg) in method.c_signature.arguments.iter().enumerate() {
-      if arg.meaning != CppFfiArgumentMeaning::ReturnValue {
-        let arg_type = complete_type(&self.processed_types,
-                                     &self.input_data.dependency_types,
-                                     &arg.argument_type,
-                                     &arg.meaning,
-                                     false,
-                                     &method.allocation_place)?;
+      if arg.meaning != CppFfiArgumentMeaning::ReturnValue &&
+         arg.meaning != CppFfiArgumentMeaning::ExceptionSlot {
+        let mut arg_type = complete_type(&self.processed_types,
+                                         &self.input_data.dependency_types,
+                                         &arg.argument_type,
+                                         &arg.meaning,
+                                         false,
+                                         &method.allocation_place)?;
+        if let Some((callback_index, user_data_index, callback_user_data_index)) =
+          closure_callback {
+          if arg_index == user_data_index {
+            // user data is passed by the generated wrapper
+            continue;
+          }
+          if arg_index == callback_index {
+            arg_type = closure_callback_type(&arg_type,
+                                             user_data_index,
+                                             callback_user_data_index)?;
+          }
+        }
         arguments.push(RustMethodArgument {
                          ffi_index: arg_index,
                          argument_type: arg_type,
@@ -1033,11 +1672,493 @@ impl<'aa> RustGenerator<'aa> {
     }
   }
 
+  /// Generates a trait implementation (e.g. `std::ops::Add` or `std::cmp::PartialEq`)
+  /// from a C++ operator. The operator may be a class member or a free function
+  /// taking the target type as the first argument.
+  /// Returns `Ok(None)` if the operator doesn't have a corresponding trait
+  /// and should be wrapped as a regular method.
+  fn process_operator(&self, method: &RustSingleMethod) -> Result<Option<TraitImpl>> {
+    let (trait_module, trait_name, method_name, kind) =
+      match method.arguments.cpp_method.cpp_method.operator {
+        Some(ref operator) => {
+          match operator_trait(operator) {
+            Some(r) => r,
+            None => return Ok(None),
+          }
+        }
+        None => return Ok(None),
+      };
+    if method.is_unsafe {
+      return Err("unsafe operators can't be trait implementations".into());
+    }
+    if method.arguments.exception_slot_ffi_index().is_some() {
+      return Err("throwing operators can't be trait implementations".into());
+    }
+    if method.arguments.arguments.len() != kind.arguments_count() {
+      return Err(unexpected("unexpected number of operator arguments").into());
+    }
+    let mut method = method.clone();
+    method.arguments.arguments[0].name = "self".to_string();
+    let self_type = method.arguments.arguments[0]
+      .argument_type
+      .rust_api_type
+      .clone();
+    let return_type = method.arguments.return_type.rust_api_type.clone();
+    let mut trait_name = trait_name.to_string();
+    let mut method_name = method_name.to_string();
+    let mut associated_types = Vec::new();
+    let (target_type, generic_argument) = match kind {
+      OperatorTraitKind::Unary |
+      OperatorTraitKind::Binary => {
+        if return_type.is_ref() || return_type == RustType::EmptyTuple {
+          return Err("operator must return a value".into());
+        }
+        associated_types.push(TraitAssociatedType {
+                                name: "Output".to_string(),
+                                value: return_type,
+                              });
+        // lifetimes of reference types are declared on the `impl` block
+        let target_type = self_type.with_lifetime("l0".to_string());
+        method.arguments.arguments[0].argument_type.rust_api_type = target_type.clone();
+        let generic_argument = if kind == OperatorTraitKind::Binary {
+          let rhs_type = method.arguments.arguments[1]
+            .argument_type
+            .rust_api_type
+            .with_lifetime("l1".to_string());
+          method.arguments.arguments[1].argument_type.rust_api_type = rhs_type.clone();
+          Some(rhs_type)
+        } else {
+          None
+        };
+        (target_type, generic_argument)
+      }
+      OperatorTraitKind::Assign => {
+        if !self_type.is_ref() || self_type.is_const()? {
+          return Err("assignment operator must receive self by mutable reference".into());
+        }
+        if method.arguments.return_type_ffi_index.is_some() {
+          return Err("assignment operator returning a class by value is not supported".into());
+        }
+        // the result of the operator (usually a reference to self) is discarded
+        method.arguments.return_type.rust_api_type = RustType::EmptyTuple;
+        method.arguments.return_type.rust_api_to_c_conversion = RustToCTypeConversion::None;
+        let rhs_type = method.arguments.arguments[1]
+          .argument_type
+          .rust_api_type
+          .with_lifetime("l0".to_string());
+        method.arguments.arguments[1].argument_type.rust_api_type = rhs_type.clone();
+        (self_type.ref_to_value()?, Some(rhs_type))
+      }
+      OperatorTraitKind::Index => {
+        if !self_type.is_ref() || !return_type.is_ref() ||
+           self_type.lifetime() != return_type.lifetime() {
+          return Err("operator[] must return a reference bound to self".into());
+        }
+        let is_const = self_type.is_const()?;
+        if return_type.is_const()? != is_const {
+          return Err("constness of operator[] return type doesn't match constness of self"
+                       .into());
+        }
+        let index_type = method.arguments.arguments[1]
+          .argument_type
+          .rust_api_type
+          .clone();
+        if index_type.is_ref() && index_type.lifetime() == self_type.lifetime() {
+          return Err("operator[] index must not share lifetime with self".into());
+        }
+        if is_const {
+          associated_types.push(TraitAssociatedType {
+                                  name: "Output".to_string(),
+                                  value: return_type.ref_to_value()?,
+                                });
+        } else {
+          trait_name = "IndexMut".to_string();
+          method_name = "index_mut".to_string();
+        }
+        (self_type.ref_to_value()?, Some(index_type))
+      }
+      OperatorTraitKind::Comparison => {
+        let returns_bool = if let RustType::Common {
+                 ref base,
+                 ref indirection,
+                 ..
+               } = return_type {
+          base.parts == vec!["bool".to_string()] && *indirection == RustTypeIndirection::None
+        } else {
+          false
+        };
+        if !returns_bool {
+          return Err("comparison operator must return bool".into());
+        }
+        let rhs_type = method.arguments.arguments[1]
+          .argument_type
+          .rust_api_type
+          .clone();
+        if !self_type.is_ref() || !self_type.is_const()? || !rhs_type.is_ref() ||
+           !rhs_type.is_const()? {
+          return Err("comparison operator must receive arguments by const reference".into());
+        }
+        (self_type.ref_to_value()?, Some(rhs_type.ref_to_value()?))
+      }
+    };
+    if !self.is_local_type(&target_type) &&
+       !generic_argument
+          .as_ref()
+          .map_or(false, |t| self.is_local_type(t)) {
+      return Err("can't implement a trait for types from other crates".into());
+    }
+    method.scope = RustMethodScope::TraitImpl;
+    method.name = RustName::new(vec![method_name])?;
+    method.doc = Some(RustMethodDocItem {
+                        cpp_fn: method.arguments.cpp_method.short_text(),
+                        rust_fns: Vec::new(),
+                        doc: method.arguments.cpp_method.cpp_method.doc.clone(),
+                      });
+    Ok(Some(TraitImpl {
+              target_type: target_type,
+              associated_types: associated_types,
+              extra: if trait_name == "PartialOrd" {
+                Some(TraitImplExtra::PartialOrd)
+              } else {
+                None
+              },
+              trait_type: RustType::Common {
+                base: RustName::new(vec!["std".to_string(),
+                                         trait_module.to_string(),
+                                         trait_name])?,
+                indirection: RustTypeIndirection::None,
+                is_const: false,
+                is_const2: false,
+                generic_arguments: generic_argument.map(|t| vec![t]),
+              },
+              methods: vec![method.to_rust_method()],
+            }))
+  }
+
+  /// Returns true if `rust_type` is declared in the crate being generated.
+  /// Trait implementations for traits from other crates are only allowed
+  /// if the target type or one of the trait's type parameters is local.
+  fn is_local_type(&self, rust_type: &RustType) -> bool {
+    if let RustType::Common { ref base, .. } = *rust_type {
+      base.crate_name() == Some(&self.input_data.crate_name)
+    } else {
+      false
+    }
+  }
+
+  /// Generates `From` trait implementation from a converting constructor
+  /// (`Target(const Source&)`) or a non-explicit conversion operator
+  /// (`Source::operator Target()`). Returns `Ok(None)` if the method
+  /// doesn't represent an implicit conversion.
+  fn process_conversion(&self, method: &RustSingleMethod) -> Result<Option<TraitImpl>> {
+    {
+      let cpp_method = &method.arguments.cpp_method.cpp_method;
+      if cpp_method.is_implicit_conversion_operator() {
+        if let Some(CppOperator::Conversion(ref target)) = cpp_method.operator {
+          if let CppTypeBase::Class(ref target_class) = target.base {
+            let info = cpp_method
+              .class_membership
+              .as_ref()
+              .chain_err(|| "conversion operator must be a class member")?;
+            // the same implementation will be generated from the constructor
+            if self
+                 .input_data
+                 .cpp_data
+                 .has_converting_constructor(target_class, &info.class_type) {
+              return Ok(None);
+            }
+          }
+        }
+      } else if !cpp_method.is_converting_constructor() {
+        return Ok(None);
+      }
+    }
+    if method.arguments.arguments.len() != 1 {
+      // variant of the constructor with omitted arguments will be used instead
+      return Ok(None);
+    }
+    if method.is_unsafe {
+      return Err("unsafe conversions can't be trait implementations".into());
+    }
+    if method.arguments.exception_slot_ffi_index().is_some() {
+      return Err("throwing conversions can't be trait implementations".into());
+    }
+    let mut method = method.clone();
+    let return_type = method.arguments.return_type.rust_api_type.clone();
+    if return_type.is_ref() || return_type == RustType::EmptyTuple {
+      return Err("conversion must return a value".into());
+    }
+    // lifetime of the source type is declared on the `impl` block
+    let source_type = method.arguments.arguments[0]
+      .argument_type
+      .rust_api_type
+      .with_lifetime("l0".to_string());
+    method.arguments.arguments[0].argument_type.rust_api_type = source_type.clone();
+    method.arguments.arguments[0].name = "value".to_string();
+    if !self.is_local_type(&return_type) && !self.is_local_type(&source_type) {
+      return Err("can't implement a trait for types from other crates".into());
+    }
+    method.scope = RustMethodScope::TraitImpl;
+    method.name = RustName::new(vec!["from".to_string()])?;
+    method.doc = Some(RustMethodDocItem {
+                        cpp_fn: method.arguments.cpp_method.short_text(),
+                        rust_fns: Vec::new(),
+                        doc: method.arguments.cpp_method.cpp_method.doc.clone(),
+                      });
+    Ok(Some(TraitImpl {
+              target_type: return_type,
+              associated_types: Vec::new(),
+              extra: None,
+              trait_type: RustType::Common {
+                base: RustName::new(vec!["std".to_string(),
+                                         "convert".to_string(),
+                                         "From".to_string()])?,
+                indirection: RustTypeIndirection::None,
+                is_const: false,
+                is_const2: false,
+                generic_arguments: Some(vec![source_type]),
+              },
+              methods: vec![method.to_rust_method()],
+            }))
+  }
+
+  /// Generates `Clone` trait implementation from a copy constructor or
+  /// `Default` trait implementation from a default constructor.
+  /// Only types with `Stack` allocation place are supported because
+  /// these traits require returning `Self` by value.
+  /// Returns `Ok(None)` if the method is not a public copy constructor
+  /// or a public default constructor.
+  fn process_clone_or_default(&self, method: &RustSingleMethod) -> Result<Option<TraitImpl>> {
+    let target_type = if let RustMethodScope::Impl { ref target_type } = method.scope {
+      target_type.clone()
+    } else {
+      return Ok(None);
+    };
+    let (trait_name, method_name) = {
+      let cpp_method = &method.arguments.cpp_method.cpp_method;
+      let is_public_constructor = cpp_method
+        .class_membership
+        .as_ref()
+        .map_or(false, |info| {
+          info.kind.is_constructor() && info.visibility == CppVisibility::Public
+        });
+      if !is_public_constructor {
+        return Ok(None);
+      }
+      if cpp_method.is_copy_constructor() && method.arguments.arguments.len() == 1 {
+        (vec!["std", "clone", "Clone"], "clone")
+      } else if method.arguments.arguments.is_empty() {
+        (vec!["std", "default", "Default"], "default")
+      } else {
+        return Ok(None);
+      }
+    };
+    if method.arguments.return_type.rust_api_type != target_type {
+      return Err(format!("{} can only be implemented for types allocated on stack",
+                         trait_name.join("::"))
+                     .into());
+    }
+    if method.is_unsafe {
+      return Err("unsafe constructors can't be trait implementations".into());
+    }
+    if method.arguments.exception_slot_ffi_index().is_some() {
+      return Err("throwing constructors can't be trait implementations".into());
+    }
+    let mut method = method.clone();
+    if method_name == "clone" {
+      if !method.arguments.arguments[0]
+            .argument_type
+            .rust_api_type
+            .is_const()? {
+        return Err("copy constructor must receive a const reference".into());
+      }
+      method.arguments.arguments[0].name = "self".to_string();
+    }
+    method.scope = RustMethodScope::TraitImpl;
+    method.name = RustName::new(vec![method_name.to_string()])?;
+    method.doc = Some(RustMethodDocItem {
+                        cpp_fn: method.arguments.cpp_method.short_text(),
+                        rust_fns: Vec::new(),
+                        doc: method.arguments.cpp_method.cpp_method.doc.clone(),
+                      });
+    Ok(Some(TraitImpl {
+              target_type: target_type,
+              associated_types: Vec::new(),
+              extra: None,
+              trait_type: RustType::Common {
+                base: RustName::new(trait_name.into_iter().map(|x| x.to_string()).collect())?,
+                indirection: RustTypeIndirection::None,
+                is_const: false,
+                is_const2: false,
+                generic_arguments: None,
+              },
+              methods: vec![method.to_rust_method()],
+            }))
+  }
+
+  /// Generates `Clone` and `Copy` trait implementations for a trivially copyable
+  /// type with `Stack` allocation place.
+  fn copy_trait_impls(&self, target_type: &RustType) -> Result<Vec<TraitImpl>> {
+    let mut results = Vec::new();
+    for (trait_name, extra) in vec![(vec!["std", "clone", "Clone"], Some(TraitImplExtra::CopyClone)),
+                                    (vec!["std", "marker", "Copy"], None)] {
+      results.push(TraitImpl {
+                     target_type: target_type.clone(),
+                     associated_types: Vec::new(),
+                     extra: extra,
+                     trait_type: RustType::Common {
+                       base: RustName::new(trait_name
+                                             .iter()
+                                             .map(|x| x.to_string())
+                                             .collect())?,
+                       indirection: RustTypeIndirection::None,
+                       is_const: false,
+                       is_const2: false,
+                       generic_arguments: None,
+                     },
+                     methods: Vec::new(),
+                   });
+    }
+    Ok(results)
+  }
+
+  /// Generates accessors of Qt properties of type `info` declared with `Q_PROPERTY`.
+  /// `methods` and `qt_receivers` are the methods and the receivers
+  /// generated for the type. Properties that can't be accessed through
+  /// these methods are skipped.
+  fn generate_qt_properties(&self,
+                            info: &RustProcessedTypeInfo,
+                            methods: &[RustMethod],
+                            qt_receivers: &[RustQtReceiverDeclaration])
+                            -> Result<Vec<RustQtPropertyDeclaration>> {
+    if info.cpp_template_arguments.is_some() {
+      return Ok(Vec::new());
+    }
+    let type_data = match self
+            .input_data
+            .cpp_data
+            .current
+            .parser
+            .types
+            .iter()
+            .find(|t| t.name == info.cpp_name) {
+      Some(r) => r,
+      None => return Ok(Vec::new()),
+    };
+    // returns method with C++ name `cpp_name` and `arguments_count` arguments
+    // (including `self`) if it's a safe method without overloading
+    let find_method = |cpp_name: &str, arguments_count: usize| {
+      methods
+        .iter()
+        .filter(|method| !method.is_unsafe)
+        .filter_map(|method| if let RustMethodArguments::SingleVariant(ref variant) =
+          method.arguments {
+                      Some((method, variant))
+                    } else {
+                      None
+                    })
+        .find(|&(_, variant)| {
+                variant.cpp_method.cpp_method.name == cpp_name &&
+                variant.arguments.len() == arguments_count &&
+                variant
+                  .arguments
+                  .first()
+                  .map_or(false, |arg| arg.name == "self")
+              })
+    };
+    let mut results = Vec::new();
+    for property in &type_data.qt_properties {
+      // the getter must be const because the accessor may only have a const reference
+      let getter = property
+        .read
+        .as_ref()
+        .and_then(|x| find_method(x.as_str(), 1))
+        .and_then(|(method, variant)| if variant
+                    .cpp_method
+                    .cpp_method
+                    .class_membership
+                    .as_ref()
+                    .map_or(false, |info| info.is_const) {
+                    Some((method, variant))
+                  } else {
+                    None
+                  });
+      let (getter, getter_variant) = match getter {
+        Some(r) => r,
+        None => {
+          log::llog(log::DebugRustSkips, || {
+            format!("Can't generate accessor for property {}::{}: no suitable getter",
+                    info.cpp_name,
+                    property.name)
+          });
+          continue;
+        }
+      };
+      let setter = property.write.as_ref().and_then(|x| find_method(x.as_str(), 2));
+      let reset = property.reset.as_ref().and_then(|x| find_method(x.as_str(), 1));
+      let notify_signal = property
+        .notify
+        .as_ref()
+        .and_then(|notify| {
+                    qt_receivers
+                      .iter()
+                      .find(|r| {
+                              r.receiver_type == RustQtReceiverType::Signal &&
+                              &r.original_method_name == notify
+                            })
+                  });
+      results.push(RustQtPropertyDeclaration {
+                     type_name: sanitize_rust_identifier(&property.name.to_class_case()),
+                     method_name: sanitize_rust_identifier(&property.name.to_snake_case()),
+                     original_name: property.name.clone(),
+                     value_type: getter_variant.api_return_type(),
+                     getter: getter.name.last_name()?.clone(),
+                     setter: match setter {
+                       Some((method, _)) => Some(method.name.last_name()?.clone()),
+                       None => None,
+                     },
+                     setter_argument_type: setter.map(|(_, variant)| {
+                                                        variant.arguments[1]
+                                                          .argument_type
+                                                          .rust_api_type
+                                                          .clone()
+                                                      }),
+                     reset: match reset {
+                       Some((method, _)) => Some(method.name.last_name()?.clone()),
+                       None => None,
+                     },
+                     notify_signal: notify_signal.map(|r| r.method_name.clone()),
+                   });
+    }
+    Ok(results)
+  }
+
+  /// Returns name of the closure slot type (e.g. `qt_core::slots::SlotVariantRef`)
+  /// with `arguments`, if it's available in this crate or its dependencies.
+  fn find_closure_slot_type(&self, arguments: &[RustType]) -> Option<RustName> {
+    for info in self
+          .processed_types
+          .iter()
+          .chain(self.input_data.dependency_types.iter().flat_map(|x| x.iter())) {
+      if let RustTypeWrapperKind::Struct { slot_wrapper: Some(ref slot_wrapper), .. } =
+        info.kind {
+        if slot_wrapper.arguments.len() == arguments.len() &&
+           slot_wrapper
+             .arguments
+             .iter()
+             .zip(arguments.iter())
+             .all(|(a, b)| &a.rust_api_type == b) {
+          return Some(slot_wrapper.public_type_name.clone());
+        }
+      }
+    }
+    None
+  }
+
   /// Generates trait implementations from `static_cast`, `dynamic_cast`
-  /// or `qobject_cast` (to be implemented) C++ function wrappers.
+  /// or `qobject_cast` C++ function wrappers.
   fn process_cpp_cast(&self, method: RustSingleMethod) -> Result<Vec<TraitImpl>> {
     let mut results = Vec::new();
-    // TODO: qobject_cast
     let mut final_methods = vec![(method.clone(), false), (method.clone(), true)];
     let args = &method.arguments;
     let cpp_cast = if let CppFfiMethodKind::Cast(ref cast) = args.cpp_method.kind {
@@ -1270,14 +2391,20 @@ impl<'aa> RustGenerator<'aa> {
         .iter()
         .any(|x| x.argument_type.rust_api_type.is_ref());
       let first_return_type = args_variants[0].return_type.rust_api_type.clone();
+      let first_may_throw = args_variants[0].exception_slot_ffi_index().is_some();
       let common_return_type = if args_variants
            .iter()
-           .all(|x| &x.return_type.rust_api_type == &first_return_type) {
+           .all(|x| {
+                  &x.return_type.rust_api_type == &first_return_type &&
+                  x.exception_slot_ffi_index().is_some() == first_may_throw
+                }) {
         if first_return_type.is_ref() {
           has_trait_lifetime = true;
-          Some(first_return_type.with_lifetime(trait_lifetime_name.to_string()))
+          Some(args_variants[0]
+                 .wrap_api_return_type(first_return_type
+                                         .with_lifetime(trait_lifetime_name.to_string())))
         } else {
-          Some(first_return_type)
+          Some(args_variants[0].wrap_api_return_type(first_return_type))
         }
       } else {
         None
@@ -1297,6 +2424,18 @@ impl<'aa> RustGenerator<'aa> {
       } else {
         None
       };
+      // the overloaded method is available for a target if any of its variants is available
+      let condition = or_conditions(args_variants
+                                      .iter()
+                                      .map(|v| v.cpp_method.cpp_method.condition.as_ref()));
+      // the overloaded method is deprecated only if all its variants are deprecated
+      let deprecation = if args_variants
+           .iter()
+           .all(|v| v.cpp_method.cpp_method.deprecation.is_some()) {
+        args_variants[0].cpp_method.cpp_method.deprecation.clone()
+      } else {
+        None
+      };
       type_declaration = Some(RustTypeDeclaration {
                                 name: {
                                   let mut name = first_method.name.clone();
@@ -1316,6 +2455,8 @@ impl<'aa> RustGenerator<'aa> {
                                 },
                                 is_public: true,
                                 rust_doc: None,
+                                deprecation: deprecation.clone(),
+                                condition: condition.clone(),
                               });
 
       RustMethod {
@@ -1332,6 +2473,8 @@ impl<'aa> RustGenerator<'aa> {
         variant_docs: doc_items,
         common_doc: None,
         is_unsafe: first_method.is_unsafe,
+        deprecation: deprecation,
+        condition: condition,
       }
     } else {
       let mut method = filtered_methods
@@ -1439,6 +2582,9 @@ impl<'aa> RustGenerator<'aa> {
     // Step 1: convert all methods to SingleVariant Rust methods and
     // split them by last name.
     let mut single_rust_methods: HashMap<String, Vec<RustSingleMethod>> = HashMap::new();
+    // Trait implementations generated from operators, along with
+    // original methods that are used if the implementation is not possible.
+    let mut operator_impls: Vec<(TraitImpl, Vec<RustSingleMethod>)> = Vec::new();
     let mut result = ProcessFunctionsResult::default();
     for method in methods {
       if method.cpp_method.is_destructor() {
@@ -1465,6 +2611,82 @@ impl<'aa> RustGenerator<'aa> {
               }
             }
           } else {
+            match self.process_clone_or_default(&rust_method) {
+              Ok(Some(trait_impl)) => {
+                if result
+                     .trait_impls
+                     .iter()
+                     .any(|x| x.trait_type == trait_impl.trait_type) {
+                  log::llog(log::DebugRustSkips, || {
+                    format!("Trait is already implemented by another constructor: {:?}",
+                            method)
+                  });
+                } else {
+                  result.trait_impls.push(trait_impl);
+                }
+              }
+              Ok(None) => {}
+              Err(msg) => {
+                log::llog(log::DebugRustSkips,
+                          || format!("Failed to generate trait implementation: {}\n{:?}\n", msg, method))
+              }
+            }
+            match self.process_conversion(&rust_method) {
+              Ok(Some(trait_impl)) => {
+                if result
+                     .trait_impls
+                     .iter()
+                     .any(|x| {
+                            x.target_type == trait_impl.target_type &&
+                            x.trait_type == trait_impl.trait_type
+                          }) {
+                  log::llog(log::DebugRustSkips, || {
+                    format!("Conversion is already implemented by another method: {:?}",
+                            method)
+                  });
+                } else {
+                  result.trait_impls.push(trait_impl);
+                }
+              }
+              Ok(None) => {}
+              Err(msg) => {
+                log::llog(log::DebugRustSkips,
+                          || format!("Failed to generate From implementation: {}\n{:?}\n", msg, method))
+              }
+            }
+            match self.process_operator(&rust_method) {
+              Ok(Some(trait_impl)) => {
+                let existing_index = operator_impls
+                  .iter()
+                  .position(|&(ref x, _)| {
+                              x.target_type == trait_impl.target_type &&
+                              x.trait_type == trait_impl.trait_type
+                            });
+                if let Some(index) = existing_index {
+                  if operator_impls[index]
+                       .0
+                       .methods
+                       .iter()
+                       .all(|m| m.name != trait_impl.methods[0].name) {
+                    operator_impls[index].0.methods.extend(trait_impl.methods);
+                    operator_impls[index].1.push(rust_method);
+                    continue;
+                  }
+                  log::llog(log::DebugRustSkips, || {
+                    format!("Trait method is already implemented by another operator: {:?}",
+                            method)
+                  });
+                } else {
+                  operator_impls.push((trait_impl, vec![rust_method]));
+                  continue;
+                }
+              }
+              Ok(None) => {}
+              Err(msg) => {
+                log::llog(log::DebugRustSkips,
+                          || format!("Failed to generate operator trait: {}\n{:?}\n", msg, method))
+              }
+            }
             let name = rust_method.name.last_name()?.clone();
             add_to_multihash(&mut single_rust_methods, name, rust_method);
           }
@@ -1475,6 +2697,26 @@ impl<'aa> RustGenerator<'aa> {
         }
       }
     }
+    // Trait implementations that can't be compiled are replaced
+    // with regular methods.
+    let all_operator_impls: Vec<_> = operator_impls
+      .iter()
+      .map(|&(ref trait_impl, _)| trait_impl.clone())
+      .collect();
+    for (trait_impl, rust_methods) in operator_impls {
+      match check_operator_trait_impl(&trait_impl, &all_operator_impls) {
+        Ok(()) => result.trait_impls.push(trait_impl),
+        Err(msg) => {
+          log::llog(log::DebugRustSkips, || {
+            format!("Failed to generate operator trait: {}\n{:?}\n", msg, trait_impl)
+          });
+          for rust_method in rust_methods {
+            let name = rust_method.name.last_name()?.clone();
+            add_to_multihash(&mut single_rust_methods, name, rust_method);
+          }
+        }
+      }
+    }
     for (_, current_methods) in single_rust_methods {
       assert!(!current_methods.is_empty());
 
@@ -1527,6 +2769,7 @@ impl<'aa> RustGenerator<'aa> {
       name: module_name.last_name()?.clone(),
       types: Vec::new(),
       functions: Vec::new(),
+      constants: Vec::new(),
       submodules: Vec::new(),
       trait_impls: Vec::new(),
       doc: if module_name.parts.len() >= 2 && module_name.parts[1] == "slots" {
@@ -1584,10 +2827,76 @@ impl<'aa> RustGenerator<'aa> {
           }
           doc_formatter::add_special_type_docs(&mut result.main_type)?;
           module.types.push(result.main_type);
+          if let Some(iterator_adapter) = result.iterator_adapter {
+            module.types.push(iterator_adapter);
+          }
           rust_overloading_types.append(&mut result.overloading_types);
         }
       }
 
+      for typedef in &self.input_data.cpp_data.current.parser.typedefs {
+        let rust_name = self
+          .calculate_rust_name(&typedef.name, &typedef.include_file, false, None)?;
+        if check_name(&rust_name) {
+          if module.types.iter().any(|t| t.name == rust_name) {
+            log::llog(log::DebugRustSkips, || {
+              format!("Type alias is skipped because of name conflict: {} ({})",
+                      typedef.name,
+                      rust_name.full_name(None))
+            });
+            continue;
+          }
+          match self.generate_type_alias(typedef, rust_name) {
+            Ok(r) => module.types.push(r),
+            Err(err) => {
+              log::llog(log::DebugRustSkips,
+                        || format!("Can't generate type alias: {}: {}", typedef.name, err));
+              err.discard_expected();
+            }
+          }
+        }
+      }
+
+      for variable in &self.input_data.cpp_data.current.parser.variables {
+        if variable.value.is_none() {
+          continue;
+        }
+        let rust_name = self.constant_rust_name(variable)?;
+        if check_name(&rust_name) {
+          if module.constants.iter().any(|c| c.name == rust_name) {
+            log::llog(log::DebugRustSkips, || {
+              format!("Constant is skipped because of name conflict: {} ({})",
+                      variable.name,
+                      rust_name.full_name(None))
+            });
+            continue;
+          }
+          match self.generate_constant(variable, rust_name) {
+            Ok(r) => module.constants.push(r),
+            Err(err) => {
+              log::llog(log::DebugRustSkips,
+                        || format!("Can't generate constant: {}: {}", variable.name, err));
+              err.discard_expected();
+            }
+          }
+        }
+      }
+
+      for cpp_macro in &self.input_data.cpp_data.current.parser.macros {
+        let rust_name = self.macro_rust_name(cpp_macro)?;
+        if check_name(&rust_name) {
+          if module.constants.iter().any(|c| c.name == rust_name) {
+            log::llog(log::DebugRustSkips, || {
+              format!("Macro is skipped because of name conflict: {} ({})",
+                      cpp_macro.name,
+                      rust_name.full_name(None))
+            });
+            continue;
+          }
+          module.constants.push(generate_macro_constant(cpp_macro, rust_name)?);
+        }
+      }
+
       let mut tmp_cpp_methods = Vec::new();
       for method in cpp_methods {
         if method.cpp_method.class_membership.is_none() {
@@ -1625,14 +2934,17 @@ impl<'aa> RustGenerator<'aa> {
                 name: "overloading".to_string(),
                 types: rust_overloading_types,
                 functions: Vec::new(),
+                constants: Vec::new(),
                 submodules: Vec::new(),
                 trait_impls: Vec::new(),
                 doc: Some(doc_formatter::overloading_module_doc()),
               });
     }
     module.types.sort_by(|a, b| a.name.cmp(&b.name));
+    module.constants.sort_by(|a, b| a.name.cmp(&b.name));
     module.submodules.sort_by(|a, b| a.name.cmp(&b.name));
-    if module.types.is_empty() && module.functions.is_empty() && module.submodules.is_empty() {
+    if module.types.is_empty() && module.functions.is_empty() && module.constants.is_empty() &&
+       module.trait_impls.is_empty() && module.submodules.is_empty() {
       log::llog(log::DebugRustSkips,
                 || format!("Skipping empty module: {}", module.name));
       return Ok((None, cpp_methods));
@@ -1641,6 +2953,179 @@ impl<'aa> RustGenerator<'aa> {
   }
 
 
+  /// Generates a Rust type alias for C++ typedef `typedef`.
+  fn generate_type_alias(&self,
+                         typedef: &CppTypedef,
+                         rust_name: RustName)
+                         -> Result<RustTypeDeclaration> {
+    if find_type_info(&self.processed_types,
+                      &self.input_data.dependency_types,
+                      |x| x.rust_name == rust_name)
+           .is_some() {
+      return Err(format!("name is already used by another type: {}",
+                         rust_name.full_name(None))
+                     .into());
+    }
+    let cpp_ffi_type = typedef
+      .target_type
+      .to_cpp_ffi_type(CppTypeRole::NotReturnType)?;
+    let rust_type = complete_type(&self.processed_types,
+                                  &self.input_data.dependency_types,
+                                  &cpp_ffi_type,
+                                  &CppFfiArgumentMeaning::Argument(0),
+                                  true,
+                                  &ReturnValueAllocationPlace::NotApplicable)?;
+    if rust_type.rust_api_type.is_ref() {
+      return Err("reference types can't be used in type aliases".into());
+    }
+    Ok(RustTypeDeclaration {
+         name: rust_name,
+         kind: RustTypeDeclarationKind::TypeAlias {
+           cpp_typedef_name: typedef.name.clone(),
+           target_type: rust_type.rust_api_type,
+           cpp_doc: typedef.doc.clone(),
+         },
+         is_public: true,
+         rust_doc: None,
+         deprecation: None,
+         condition: None,
+       })
+  }
+
+  /// Calculates Rust name of the constant generated for `variable`.
+  /// Static data members are prefixed with the class name,
+  /// so that `QFoo::maxValue` becomes `FOO_MAX_VALUE`.
+  fn constant_rust_name(&self, variable: &CppVariable) -> Result<RustName> {
+    let cpp_name = match variable.class_type {
+      Some(ref class_type) => format!("{}_{}", class_type.name, variable.short_name()),
+      None => variable.name.clone(),
+    };
+    let mut rust_name = self
+      .calculate_rust_name(&cpp_name, &variable.include_file, true, None)?;
+    let last_name = rust_name
+      .parts
+      .pop()
+      .chain_err(|| "name.parts can't be empty")?;
+    rust_name.parts.push(last_name.as_str().to_upper_case_words());
+    Ok(rust_name)
+  }
+
+  /// Calculates Rust name of the constant generated for `cpp_macro`.
+  /// Macro names are usually upper-case already, so they are kept as is.
+  fn macro_rust_name(&self, cpp_macro: &CppMacro) -> Result<RustName> {
+    let module_name = self
+      .top_module_names
+      .get(&cpp_macro.include_file)
+      .chain_err(|| {
+                   format!("no top level module generated for header: {}",
+                           cpp_macro.include_file)
+                 })?;
+    let mut parts = module_name.parts.clone();
+    parts.push(cpp_macro.name.clone());
+    RustName::new(parts)
+  }
+
+  /// Generates a Rust constant for C++ variable `variable`
+  /// initialized with a literal.
+  fn generate_constant(&self, variable: &CppVariable, rust_name: RustName) -> Result<RustConstant> {
+    let value = variable
+      .value
+      .as_ref()
+      .chain_err(|| "variable has no value")?;
+    let cpp_type = &variable.variable_type;
+    if cpp_type.indirection != CppTypeIndirection::None {
+      // only `const char*` strings are accepted by the parser
+      if let CppLiteralValue::String(ref string) = *value {
+        return Ok(RustConstant {
+                    name: rust_name,
+                    value_type: static_str_type()?,
+                    value: format!("{:?}", string),
+                    cpp_name: variable.name.clone(),
+                    is_cpp_macro: false,
+                  });
+      }
+      return Err(unexpected("string literal expected for pointer constant").into());
+    }
+    let (is_float, allows_negative) = match cpp_type.base {
+      CppTypeBase::BuiltInNumeric(ref numeric) => {
+        (numeric.is_float(), numeric.is_float() || numeric.is_signed_integer())
+      }
+      CppTypeBase::SpecificNumeric(CppSpecificNumericType { ref kind, .. }) => {
+        match *kind {
+          CppSpecificNumericTypeKind::Integer { ref is_signed } => (false, *is_signed),
+          CppSpecificNumericTypeKind::FloatingPoint => (true, true),
+        }
+      }
+      CppTypeBase::PointerSizedInteger { ref is_signed, .. } => (false, *is_signed),
+      _ => return Err(format!("unsupported constant type: {:?}", cpp_type).into()),
+    };
+    let value_text = match *value {
+      CppLiteralValue::Bool(value) => value.to_string(),
+      CppLiteralValue::Integer(value) => {
+        if value < 0 && !allows_negative {
+          return Err(format!("negative value for unsigned type: {}", value).into());
+        }
+        if !is_float {
+          let (min, max) = integer_range(&cpp_type.base)?;
+          if value < min || value > max {
+            return Err(format!("value {} doesn't fit in type {}",
+                               value,
+                               cpp_type.to_cpp_pseudo_code())
+                           .into());
+          }
+        }
+        if is_float {
+          format!("{}.0", value)
+        } else {
+          value.to_string()
+        }
+      }
+      CppLiteralValue::Float(ref value) => value.clone(),
+      CppLiteralValue::String(..) => {
+        return Err(unexpected("string literal for non-pointer constant").into())
+      }
+    };
+    let mut value_type = cpp_type.clone();
+    value_type.is_const = false;
+    Ok(RustConstant {
+         name: rust_name,
+         value_type: ffi_type(&self.processed_types,
+                              &self.input_data.dependency_types,
+                              &value_type)?,
+         value: value_text,
+         cpp_name: variable.name.clone(),
+         is_cpp_macro: false,
+       })
+  }
+
+  /// Calculates Rust name of template class `cpp_name` before names of
+  /// template arguments are added to it. Types nested into a template class
+  /// are prefixed with the enclosing type's name, so that
+  /// `QList<int>::iterator` becomes `ListIteratorInt`.
+  fn template_class_rust_name(&self, cpp_name: &str, include_file: &str) -> Result<RustName> {
+    let rust_name = self
+      .calculate_rust_name(cpp_name, include_file, false, None)?;
+    if let Some(index) = cpp_name.rfind("::") {
+      let enclosing_name = &cpp_name[0..index];
+      if let Some(info) = self
+           .input_data
+           .cpp_data
+           .find_type_info(|x| &x.name == enclosing_name) {
+        if let CppTypeKind::Class { template_arguments: Some(..), .. } = info.kind {
+          let mut name = self
+            .template_class_rust_name(&info.name, &info.include_file)?;
+          let last_name = name
+            .parts
+            .pop()
+            .chain_err(|| "name.parts can't be empty")?;
+          name.parts.push(last_name + rust_name.last_name()?);
+          return Ok(name);
+        }
+      }
+    }
+    Ok(rust_name)
+  }
+
   /// Generates exact (FFI-compatible) Rust equivalent of `CppAndFfiMethod` object.
   fn generate_ffi_function(&self, data: &CppAndFfiMethod) -> Result<RustFFIFunction> {
     let mut args = Vec::new();
@@ -1659,6 +3144,7 @@ impl<'aa> RustGenerator<'aa> {
                                &data.c_signature.return_type.ffi_type)?,
          name: data.c_name.clone(),
          arguments: args,
+         condition: data.cpp_method.condition.clone(),
        })
   }
 
@@ -1705,7 +3191,8 @@ impl<'aa> RustGenerator<'aa> {
         cpp_doc: type_info.doc.clone(),
         cpp_template_arguments: None,
         kind: match type_info.kind {
-          CppTypeKind::Class { .. } => {
+          CppTypeKind::Class { .. } |
+          CppTypeKind::Union { .. } => {
             match self
                     .input_data
                     .cpp_data
@@ -1729,8 +3216,10 @@ impl<'aa> RustGenerator<'aa> {
                                    .has_non_public_destructor(&CppTypeClassBase {
                                                                  name: type_info.name.clone(),
                                                                  template_arguments: None,
+                                                                 enclosing_template_class: None,
                                                                }),
                   slot_wrapper: None,
+                  subclass: None,
                 }
               }
             }
@@ -1772,6 +3261,12 @@ impl<'aa> RustGenerator<'aa> {
             RustTypeWrapperKind::Enum {
               values: prepare_enum_values(values),
               is_flaggable: is_flaggable,
+              representation: self
+                .input_data
+                .cpp_enum_representations
+                .get(&type_info.name)
+                .unwrap_or(&self.input_data.enum_representation)
+                .clone(),
             }
           }
         },
@@ -1790,6 +3285,10 @@ impl<'aa> RustGenerator<'aa> {
         let mut arg_captions = Vec::new();
         if let Some(ref args) = item.cpp_template_arguments {
           for x in args {
+            if let CppTypeBase::TemplateArgumentValue { .. } = x.base {
+              arg_captions.push(x.base.caption(TypeCaptionStrategy::Short)?.to_class_case());
+              continue;
+            }
             let rust_type = complete_type(result,
                                           &self.input_data.dependency_types,
                                           &x.to_cpp_ffi_type(CppTypeRole::NotReturnType)?,
@@ -1826,10 +3325,8 @@ impl<'aa> RustGenerator<'aa> {
       }
       for ins in &template_instantiations.instantiations {
         let rust_name = self
-          .calculate_rust_name(&template_instantiations.class_name,
-                               &type_info.include_file,
-                               false,
-                               None)?;
+          .template_class_rust_name(&template_instantiations.class_name,
+                                    &type_info.include_file)?;
         unnamed_items.push(RustProcessedTypeInfo {
                              cpp_name: template_instantiations.class_name.clone(),
                              cpp_doc: type_info.doc.clone(),
@@ -1850,8 +3347,13 @@ impl<'aa> RustGenerator<'aa> {
                                                                     Some(ins
                                                                            .template_arguments
                                                                            .clone()),
+                                                                  enclosing_template_class:
+                                                                    type_info
+                                                                      .enclosing_template_class
+                                                                      .clone(),
                                                                 }),
                                slot_wrapper: None,
+                               subclass: None,
                              },
                              rust_name: rust_name,
                              is_public: true,
@@ -1933,6 +3435,15 @@ impl<'aa> RustGenerator<'aa> {
         } else {
           arg_names.join("_")
         };
+        // the public type is generated in the parent module of the raw slot types
+        let mut public_type_name = self
+          .top_module_names
+          .get(&header.include_file_base_name)
+          .chain_err(|| "no top level module generated for slots")?
+          .clone();
+        public_type_name
+          .parts
+          .push(format!("slot_{}", args_text).to_class_case());
         let rust_type_info = RustProcessedTypeInfo {
           cpp_name: qt_slot_wrapper.class_name.clone(),
           cpp_template_arguments: None,
@@ -1961,18 +3472,213 @@ impl<'aa> RustGenerator<'aa> {
               Ok(t)
             })?,
                                  receiver_id: qt_slot_wrapper.receiver_id.clone(),
-                                 public_type_name: format!("slot_{}", args_text).to_class_case(),
+                                 public_type_name: public_type_name,
                                  callback_name: format!("slot_{}_callback", args_text)
                                    .to_snake_case(),
                                }),
+            subclass: None,
           },
         };
         result.push(rust_type_info);
       }
+      for subclass in &header.subclasses {
+        let rust_name = self
+          .calculate_rust_name(&format!("{}Subclass", subclass.base_class_name),
+                               &header.include_file_base_name,
+                               false,
+                               None)?;
+        result.push(RustProcessedTypeInfo {
+                      cpp_name: subclass.class_name.clone(),
+                      cpp_template_arguments: None,
+                      cpp_doc: None,
+                      rust_name: rust_name.clone(),
+                      is_public: true,
+                      kind: RustTypeWrapperKind::Struct {
+                        size_const_name: None,
+                        is_deletable: true,
+                        slot_wrapper: None,
+                        subclass: None,
+                      },
+                    });
+        let type_last_name = rust_name.last_name()?.to_snake_case();
+        let mut virtual_methods = Vec::new();
+        for method in &subclass.virtual_methods {
+          match self.subclass_virtual_method(&result, header, subclass, method, &type_last_name) {
+            Ok(r) => virtual_methods.push(r),
+            Err(err) => {
+              if method
+                   .cpp_method
+                   .class_membership
+                   .as_ref()
+                   .map_or(false, |info| info.is_pure_virtual) {
+                // the C++ subclass can't be used without an implementation
+                return Err(err).chain_err(|| {
+                                            format!("can't override pure virtual method in Rust: {}",
+                                                    method.cpp_method.short_text())
+                                          });
+              }
+              log::llog(log::DebugRustSkips, || {
+                format!("Can't override virtual method in Rust: {}: {}",
+                        method.cpp_method.short_text(),
+                        err)
+              });
+            }
+          }
+        }
+        let base_last_name = subclass
+          .base_class_name
+          .split("::")
+          .last()
+          .chain_err(|| unexpected("split can't return empty iterator"))?;
+        let trait_name = format!("{}Virtuals",
+                                 remove_qt_prefix_and_convert_case(base_last_name,
+                                                                   Case::Class,
+                                                                   self.input_data
+                                                                     .remove_qt_prefix));
+        let rust_subclass = RustSubclass {
+          base_class_name: subclass.base_class_name.clone(),
+          trait_name: trait_name,
+          deleter_name: format!("{}_deleter", type_last_name),
+          virtual_methods: virtual_methods,
+        };
+        let type_info = result
+          .last_mut()
+          .chain_err(|| unexpected("subclass type info expected"))?;
+        if let RustTypeWrapperKind::Struct { ref mut subclass, .. } = type_info.kind {
+          *subclass = Some(rust_subclass);
+        }
+      }
+      if let Some(ref meta_object) = header.qt_dynamic_meta_object {
+        for class_name in &[&meta_object.builder_class_name, &meta_object.object_class_name] {
+          // strip the wrapper library's prefix from the name
+          let type_name = class_name
+            .rsplit('_')
+            .next()
+            .chain_err(|| unexpected("rsplit can't return empty iterator"))?;
+          result.push(RustProcessedTypeInfo {
+                        cpp_name: class_name.to_string(),
+                        cpp_template_arguments: None,
+                        cpp_doc: None,
+                        rust_name: self.calculate_rust_name(type_name,
+                                                            &header.include_file_base_name,
+                                                            false,
+                                                            None)?,
+                        is_public: true,
+                        kind: RustTypeWrapperKind::Struct {
+                          size_const_name: None,
+                          is_deletable: true,
+                          slot_wrapper: None,
+                          subclass: None,
+                        },
+                      });
+        }
+      }
     }
     Ok(result)
   }
 
+  /// Generates information about a virtual method of a generated subclass
+  /// for implementing it in Rust. `processed_types` must contain the subclass.
+  /// Returns an error if the method can't be overridden in Rust.
+  fn subclass_virtual_method(&self,
+                             processed_types: &[RustProcessedTypeInfo],
+                             header: &CppFfiHeaderData,
+                             subclass: &CppSubclass,
+                             method: &CppSubclassVirtualMethod,
+                             type_last_name: &str)
+                             -> Result<RustSubclassVirtualMethod> {
+    let is_const = method
+      .cpp_method
+      .class_membership
+      .as_ref()
+      .map_or(false, |info| info.is_const);
+    let is_pure_virtual = method
+      .cpp_method
+      .class_membership
+      .as_ref()
+      .map_or(false, |info| info.is_pure_virtual);
+    let find_method = |name: &str| {
+      header
+        .methods
+        .iter()
+        .find(|m| m.cpp_method.name == name && m.cpp_method.class_name() == Some(&subclass.class_name))
+    };
+    let setter_cpp_name = format!("set_{}_callback", method.name);
+    if find_method(setter_cpp_name.as_str()).is_none() {
+      return Err("callback setter is not available".into());
+    }
+    let base_method_cpp_name = format!("{}_base", method.name);
+    let (base_method_name, base_method_returns_result) = if is_pure_virtual {
+      (None, false)
+    } else {
+      let base_method = find_method(base_method_cpp_name.as_str())
+        .chain_err(|| "base class implementation is not available")?;
+      let returns_result = base_method
+        .c_signature
+        .arguments
+        .iter()
+        .any(|arg| arg.meaning == CppFfiArgumentMeaning::ExceptionSlot);
+      (Some(sanitize_rust_identifier(&base_method_cpp_name.to_snake_case())), returns_result)
+    };
+    let arguments = method
+      .arguments
+      .iter()
+      .map_if_ok(|t| {
+                   complete_type(processed_types,
+                                 &self.input_data.dependency_types,
+                                 t,
+                                 &CppFfiArgumentMeaning::Argument(0),
+                                 false,
+                                 &ReturnValueAllocationPlace::NotApplicable)
+                 })?;
+    let return_allocation_place = if method.return_type.conversion ==
+                                     CppIndirectionChange::ValueToPointer {
+      ReturnValueAllocationPlace::Heap
+    } else {
+      ReturnValueAllocationPlace::NotApplicable
+    };
+    let return_type = complete_type(processed_types,
+                                    &self.input_data.dependency_types,
+                                    &method.return_type,
+                                    &CppFfiArgumentMeaning::ReturnValue,
+                                    false,
+                                    &return_allocation_place)?;
+    let callback_type = ffi_type(processed_types,
+                                 &self.input_data.dependency_types,
+                                 &CppType {
+                                   base: CppTypeBase::FunctionPointer(method
+                                                                        .function_type
+                                                                        .clone()),
+                                   indirection: CppTypeIndirection::None,
+                                   is_const: false,
+                                   is_const2: false,
+                                 })?;
+    let name = method.name.to_snake_case();
+    Ok(RustSubclassVirtualMethod {
+         name: sanitize_rust_identifier(&name),
+         cpp_declaration: method.cpp_method.short_text(),
+         is_const: is_const,
+         argument_names: method
+           .cpp_method
+           .arguments
+           .iter()
+           .enumerate()
+           .map(|(num, arg)| if arg.name.is_empty() {
+                  format!("arg{}", num)
+                } else {
+                  sanitize_rust_identifier(&arg.name.to_snake_case())
+                })
+           .collect(),
+         arguments: arguments,
+         return_type: return_type,
+         setter_name: sanitize_rust_identifier(&setter_cpp_name.to_snake_case()),
+         base_method_name: base_method_name,
+         base_method_returns_result: base_method_returns_result,
+         callback_name: format!("{}_{}_callback", type_last_name, name),
+         callback_type: callback_type,
+       })
+  }
+
   /// Generates `RustName` for specified function or type name,
   /// including crate name and modules list.
   fn calculate_rust_name(&self,
@@ -2068,6 +3774,8 @@ fn calculate_rust_name_test_part(name: &'static str,
     include_file_base_name: include_file.to_string(),
     methods: Vec::new(),
     qt_slot_wrappers: Vec::new(),
+    subclasses: Vec::new(),
+    qt_dynamic_meta_object: None,
   };
   let mut generator = RustGenerator {
     top_module_names: HashMap::new(),
@@ -2079,6 +3787,9 @@ fn calculate_rust_name_test_part(name: &'static str,
       crate_name: "qt_core".to_string(),
       remove_qt_prefix: true,
       filtered_namespaces: Vec::new(),
+      cpp_callbacks: Vec::new(),
+      enum_representation: Default::default(),
+      cpp_enum_representations: HashMap::new(),
     },
   };
   generator.top_module_names = generator.calc_top_module_names().unwrap();
@@ -2116,6 +3827,24 @@ fn calculate_rust_name_test() {
                                 &["qt_core", "rect", "ns", "func1"]);
 }
 
+#[test]
+fn operator_trait_test() {
+  assert_eq!(operator_trait(&CppOperator::Addition),
+             Some(("ops", "Add", "add", OperatorTraitKind::Binary)));
+  assert_eq!(operator_trait(&CppOperator::UnaryMinus),
+             Some(("ops", "Neg", "neg", OperatorTraitKind::Unary)));
+  assert_eq!(operator_trait(&CppOperator::MultiplicationAssignment),
+             Some(("ops", "MulAssign", "mul_assign", OperatorTraitKind::Assign)));
+  assert_eq!(operator_trait(&CppOperator::Subscript),
+             Some(("ops", "Index", "index", OperatorTraitKind::Index)));
+  assert_eq!(operator_trait(&CppOperator::NotEqualTo),
+             Some(("cmp", "PartialEq", "ne", OperatorTraitKind::Comparison)));
+  assert_eq!(operator_trait(&CppOperator::LogicalNot), None);
+  assert_eq!(operator_trait(&CppOperator::Assignment), None);
+  assert_eq!(OperatorTraitKind::Unary.arguments_count(), 1);
+  assert_eq!(OperatorTraitKind::Comparison.arguments_count(), 2);
+}
+
 #[test]
 fn prepare_enum_values_test_simple() {
   let r = prepare_enum_values(&[CppEnumValue {
@@ -2272,6 +4001,13 @@ impl RustSingleMethod {
       common_doc: None,
       is_unsafe: self.is_unsafe,
       scope: self.scope.clone(),
+      // deprecation attributes have no effect on trait implementations
+      deprecation: if self.scope == RustMethodScope::TraitImpl {
+        None
+      } else {
+        self.arguments.cpp_method.cpp_method.deprecation.clone()
+      },
+      condition: self.arguments.cpp_method.cpp_method.condition.clone(),
     }
   }
 
@@ -2427,3 +4163,344 @@ impl RustSingleMethod {
     Ok(result)
   }
 }
+
+#[cfg(test)]
+fn closure_callback_test_method() -> CppAndFfiMethod {
+  use cpp_method::{CppMethodArgument, CppExceptionSpecification};
+  let void_ptr = CppType {
+    indirection: CppTypeIndirection::Ptr,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::Void,
+  };
+  let int_type = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+  };
+  let arguments = vec![CppMethodArgument {
+                          name: "callback".to_string(),
+                          argument_type: CppType {
+                            indirection: CppTypeIndirection::None,
+                            is_const: false,
+                            is_const2: false,
+                            base: CppTypeBase::FunctionPointer(CppFunctionPointerType {
+                              return_type: Box::new(CppType::void()),
+                              arguments: vec![int_type, void_ptr.clone()],
+                              allows_variadic_arguments: false,
+                            }),
+                          },
+                          has_default_value: false,
+                        },
+                        CppMethodArgument {
+                          name: "data".to_string(),
+                          argument_type: void_ptr,
+                          has_default_value: false,
+                        }];
+  let method = CppMethod {
+    name: "run".to_string(),
+    class_membership: None,
+    return_type: CppType::void(),
+    arguments: arguments,
+    doc: None,
+    inheritance_chain: Vec::new(),
+    allows_variadic_arguments: false,
+    exception_specification: CppExceptionSpecification::None,
+    deprecation: None,
+    condition: None,
+    include_file: String::new(),
+    origin_location: None,
+    template_arguments: None,
+    template_arguments_values: None,
+    operator: None,
+    declaration_code: None,
+    is_ffi_whitelisted: false,
+  };
+  let c_signature = method
+    .c_signature(ReturnValueAllocationPlace::NotApplicable)
+    .unwrap();
+  CppAndFfiMethod {
+    cpp_method: method,
+    kind: ::cpp_ffi_data::CppFfiMethodKind::Real,
+    allocation_place: ReturnValueAllocationPlace::NotApplicable,
+    c_signature: c_signature,
+    c_name: "ctr_G_run".to_string(),
+  }
+}
+
+#[test]
+fn closure_callback_test() {
+  let method = closure_callback_test_method();
+  let mut generator = RustGenerator {
+    top_module_names: HashMap::new(),
+    processed_types: Vec::new(),
+    input_data: RustGeneratorInputData {
+      cpp_ffi_headers: Vec::new(),
+      cpp_data: &Default::default(),
+      dependency_types: Vec::new(),
+      crate_name: "ctr".to_string(),
+      remove_qt_prefix: false,
+      filtered_namespaces: Vec::new(),
+      cpp_callbacks: Vec::new(),
+      enum_representation: Default::default(),
+      cpp_enum_representations: HashMap::new(),
+    },
+  };
+  // callbacks may be stored by the C++ side, so they are only converted when configured
+  let rust_method = generator
+    .generate_rust_single_method(&method, &RustMethodScope::Free, false)
+    .unwrap();
+  assert_eq!(rust_method.arguments.arguments.len(), 2);
+  assert!(rust_method.is_unsafe);
+
+  generator.input_data.cpp_callbacks = vec![CppCallback {
+                                              method_name: "run".to_string(),
+                                              callback_argument: "callback".to_string(),
+                                              user_data_argument: "data".to_string(),
+                                            }];
+  let rust_method = generator
+    .generate_rust_single_method(&method, &RustMethodScope::Free, false)
+    .unwrap();
+  assert_eq!(rust_method.arguments.arguments.len(), 1);
+  let arg = &rust_method.arguments.arguments[0];
+  assert_eq!(arg.name, "callback");
+  assert_eq!(arg.ffi_index, 0);
+  assert_eq!(arg.argument_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::ClosureToCallback {
+               user_data_ffi_index: 1,
+               callback_user_data_index: 1,
+             });
+  if let RustType::Closure {
+           ref arguments,
+           ref return_type,
+           ..
+         } = arg.argument_type.rust_api_type {
+    assert_eq!(arguments.len(), 1);
+    assert_eq!(return_type.as_ref(), &RustType::EmptyTuple);
+  } else {
+    panic!("closure type expected");
+  }
+  assert!(!rust_method.is_unsafe);
+}
+
+#[test]
+fn std_function_test() {
+  use cpp_method::{CppMethodArgument, CppExceptionSpecification};
+  let int_type = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+  };
+  let function_type = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::StdFunction(CppFunctionPointerType {
+                                     return_type: Box::new(int_type.clone()),
+                                     arguments: vec![int_type],
+                                     allows_variadic_arguments: false,
+                                   }),
+  };
+  let method = CppMethod {
+    name: "wrap".to_string(),
+    class_membership: None,
+    return_type: function_type.clone(),
+    arguments: vec![CppMethodArgument {
+                      name: "function".to_string(),
+                      argument_type: function_type,
+                      has_default_value: false,
+                    }],
+    doc: None,
+    inheritance_chain: Vec::new(),
+    allows_variadic_arguments: false,
+    exception_specification: CppExceptionSpecification::None,
+    deprecation: None,
+    condition: None,
+    include_file: String::new(),
+    origin_location: None,
+    template_arguments: None,
+    template_arguments_values: None,
+    operator: None,
+    declaration_code: None,
+    is_ffi_whitelisted: false,
+  };
+  let c_signature = method
+    .c_signature(ReturnValueAllocationPlace::NotApplicable)
+    .unwrap();
+  let method = CppAndFfiMethod {
+    cpp_method: method,
+    kind: ::cpp_ffi_data::CppFfiMethodKind::Real,
+    allocation_place: ReturnValueAllocationPlace::NotApplicable,
+    c_signature: c_signature,
+    c_name: "ctr_G_wrap".to_string(),
+  };
+  let generator = RustGenerator {
+    top_module_names: HashMap::new(),
+    processed_types: Vec::new(),
+    input_data: RustGeneratorInputData {
+      cpp_ffi_headers: Vec::new(),
+      cpp_data: &Default::default(),
+      dependency_types: Vec::new(),
+      crate_name: "ctr".to_string(),
+      remove_qt_prefix: false,
+      filtered_namespaces: Vec::new(),
+      cpp_callbacks: Vec::new(),
+      enum_representation: Default::default(),
+      cpp_enum_representations: HashMap::new(),
+    },
+  };
+  let rust_method = generator
+    .generate_rust_single_method(&method, &RustMethodScope::Free, false)
+    .unwrap();
+  assert_eq!(rust_method.arguments.arguments.len(), 1);
+  let arg = &rust_method.arguments.arguments[0];
+  assert_eq!(arg.argument_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::BoxToCppClosure);
+  // C++ may call and destroy the argument on any thread
+  assert_eq!(::rust_code_generator::rust_type_to_code(&arg.argument_type.rust_api_type, "ctr"),
+             "Box<Fn(::libc::c_int) -> ::libc::c_int + Send + Sync>");
+  assert_eq!(::rust_code_generator::rust_type_to_code(&arg.argument_type.rust_ffi_type, "ctr"),
+             "::cpp_utils::CppClosure");
+  let return_type = &rust_method.arguments.return_type;
+  assert_eq!(return_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::BoxToCppClosure);
+  assert_eq!(::rust_code_generator::rust_type_to_code(&return_type.rust_api_type, "ctr"),
+             "Box<Fn(::libc::c_int) -> ::libc::c_int>");
+  assert_eq!(::rust_code_generator::rust_type_to_code(&return_type.rust_ffi_type, "ctr"),
+             "::cpp_utils::CppClosure");
+}
+
+#[test]
+fn closure_slot_type_test() {
+  let cpp_int_type = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+  };
+  let int_type = RustType::Common {
+    base: RustName::new(vec!["libc".to_string(), "c_int".to_string()]).unwrap(),
+    generic_arguments: None,
+    is_const: false,
+    is_const2: false,
+    indirection: RustTypeIndirection::None,
+  };
+  let public_type_name = RustName::new(vec!["qt_core".to_string(),
+                                            "slots".to_string(),
+                                            "SlotCInt".to_string()])
+    .unwrap();
+  let raw_slot = RustProcessedTypeInfo {
+    cpp_name: "qt_core_SlotWrapper_int".to_string(),
+    cpp_doc: None,
+    cpp_template_arguments: None,
+    kind: RustTypeWrapperKind::Struct {
+      size_const_name: None,
+      is_deletable: true,
+      slot_wrapper: Some(RustQtSlotWrapper {
+                           arguments: vec![CompleteType {
+                                             cpp_type: cpp_int_type.clone(),
+                                             cpp_ffi_type: cpp_int_type,
+                                             cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+                                             rust_ffi_type: int_type.clone(),
+                                             rust_api_type: int_type.clone(),
+                                             rust_api_to_c_conversion: RustToCTypeConversion::None,
+                                           }],
+                           receiver_id: "1custom_slot(int)".to_string(),
+                           public_type_name: public_type_name.clone(),
+                           callback_name: "slot_c_int_callback".to_string(),
+                         }),
+      subclass: None,
+    },
+    // the public type's location doesn't depend on the raw type's path
+    rust_name: RustName::new(vec!["qt_core".to_string(), "RawSlotCInt".to_string()]).unwrap(),
+    is_public: true,
+  };
+  let generator = RustGenerator {
+    top_module_names: HashMap::new(),
+    processed_types: vec![raw_slot],
+    input_data: RustGeneratorInputData {
+      cpp_ffi_headers: Vec::new(),
+      cpp_data: &Default::default(),
+      dependency_types: Vec::new(),
+      crate_name: "qt_core".to_string(),
+      remove_qt_prefix: true,
+      filtered_namespaces: Vec::new(),
+      cpp_callbacks: Vec::new(),
+      enum_representation: Default::default(),
+      cpp_enum_representations: HashMap::new(),
+    },
+  };
+  assert_eq!(generator.find_closure_slot_type(&[int_type.clone()]),
+             Some(public_type_name));
+  assert_eq!(generator.find_closure_slot_type(&[]), None);
+  assert_eq!(generator.find_closure_slot_type(&[int_type.clone(), int_type]), None);
+}
+
+#[cfg(test)]
+fn constant_test_variable(base: CppTypeBase, value: i64) -> CppVariable {
+  CppVariable {
+    name: "x".to_string(),
+    class_type: None,
+    variable_type: CppType {
+      base: base,
+      indirection: CppTypeIndirection::None,
+      is_const: true,
+      is_const2: false,
+    },
+    is_const: true,
+    is_constexpr: false,
+    value: Some(CppLiteralValue::Integer(value)),
+    include_file: "ctr.h".to_string(),
+    origin_location: ::cpp_data::CppOriginLocation {
+      include_file_path: "ctr.h".to_string(),
+      line: 1,
+      column: 1,
+    },
+  }
+}
+
+#[test]
+fn constant_value_range_test() {
+  let generator = RustGenerator {
+    top_module_names: HashMap::new(),
+    processed_types: Vec::new(),
+    input_data: RustGeneratorInputData {
+      cpp_ffi_headers: Vec::new(),
+      cpp_data: &Default::default(),
+      dependency_types: Vec::new(),
+      crate_name: "ctr".to_string(),
+      remove_qt_prefix: false,
+      filtered_namespaces: Vec::new(),
+      cpp_callbacks: Vec::new(),
+      enum_representation: Default::default(),
+      cpp_enum_representations: HashMap::new(),
+    },
+  };
+  let constant = |base: CppTypeBase, value: i64| {
+    generator.generate_constant(&constant_test_variable(base, value),
+                                RustName::new(vec!["ctr".to_string(), "X".to_string()]).unwrap())
+  };
+  let int = CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int);
+  assert_eq!(constant(int.clone(), 0x7FFFFFFF).unwrap().value, "2147483647");
+  assert_eq!(constant(int.clone(), -0x80000000).unwrap().value, "-2147483648");
+  // `const int x = 0x80000000;`
+  assert!(constant(int.clone(), 0x80000000).is_err());
+  let char_type = CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Char);
+  assert_eq!(constant(char_type.clone(), 127).unwrap().value, "127");
+  assert!(constant(char_type.clone(), 200).is_err());
+  assert!(constant(char_type.clone(), -1).is_err());
+  let uint = CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::UInt);
+  assert_eq!(constant(uint.clone(), 0xFFFFFFFF).unwrap().value, "4294967295");
+  assert!(constant(uint.clone(), 0x100000000).is_err());
+  let uint64 = CppTypeBase::SpecificNumeric(CppSpecificNumericType {
+                                              name: "uint64_t".to_string(),
+                                              bits: 64,
+                                              kind: CppSpecificNumericTypeKind::Integer {
+                                                is_signed: false,
+                                              },
+                                            });
+  assert_eq!(constant(uint64, i64::max_value()).unwrap().value,
+             i64::max_value().to_string());
+}
@@ -1,11 +1,12 @@
 //! Types holding information about generates Rust API.
 
-use cpp_ffi_data::CppAndFfiMethod;
+use cpp_ffi_data::{CppAndFfiMethod, CppFfiArgumentMeaning};
 use cpp_type::CppType;
 use cpp_data::CppData;
-use rust_type::{RustName, CompleteType, RustType};
+use rust_type::{RustName, CompleteType, RustType, RustTypeIndirection};
 use cpp_method::CppMethodDoc;
-use cpp_data::CppTypeDoc;
+use cpp_data::{CppTypeDoc, CppDeprecation};
+use common::target::Condition;
 use std::path::PathBuf;
 
 /// One variant of a Rust enum
@@ -42,12 +43,79 @@ pub struct RustQtSlotWrapper {
   pub arguments: Vec<CompleteType>,
   /// Identifier of the slot for `QObject::connect`
   pub receiver_id: String,
-  /// Name of the public Rust struct of this wrapper
-  pub public_type_name: String,
+  /// Full name of the public Rust struct of this wrapper
+  pub public_type_name: RustName,
   /// Name of the extern callback function of this wrapper
   pub callback_name: String,
 }
 
+/// Information about a virtual method of a generated C++ subclass
+/// that can be overridden by Rust code
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct RustSubclassVirtualMethod {
+  /// Name of the method in the trait
+  pub name: String,
+  /// C++ declaration of the method (for documentation)
+  pub cpp_declaration: String,
+  /// True if the C++ method is const
+  pub is_const: bool,
+  /// Names of the method's arguments
+  pub argument_names: Vec<String>,
+  /// Types of the method's arguments
+  pub arguments: Vec<CompleteType>,
+  /// Return type of the method
+  pub return_type: CompleteType,
+  /// Name of the subclass's method that sets the callback
+  pub setter_name: String,
+  /// Name of the subclass's method that calls the base class
+  /// implementation. None if the method is pure virtual.
+  pub base_method_name: Option<String>,
+  /// True if the base implementation method returns `Result` because
+  /// it may throw a C++ exception
+  pub base_method_returns_result: bool,
+  /// Name of the extern callback function of this method
+  pub callback_name: String,
+  /// Type of the callback function
+  pub callback_type: RustType,
+}
+
+/// Information about a generated C++ subclass on Rust side
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct RustSubclass {
+  /// Name of the C++ base class
+  pub base_class_name: String,
+  /// Name of the trait containing virtual methods
+  pub trait_name: String,
+  /// Name of the extern function that drops the trait object
+  pub deleter_name: String,
+  /// Virtual methods that can be overridden
+  pub virtual_methods: Vec<RustSubclassVirtualMethod>,
+}
+
+/// Representation of C++ enums in the generated Rust crate.
+///
+/// The representation can be selected using `Config::set_enum_representation`
+/// and `Config::set_cpp_enum_representation`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize)]
+pub enum RustEnumRepresentation {
+  /// Rust `enum` with explicit discriminants. Enum values not listed
+  /// in the C++ declaration can't be represented, so receiving
+  /// such a value from C++ is undefined behavior.
+  Enum,
+  /// `#[repr(transparent)]` struct containing the integer value, with
+  /// associated constants for the enum values. Any value can be represented.
+  Newtype,
+}
+
+impl Default for RustEnumRepresentation {
+  fn default() -> RustEnumRepresentation {
+    RustEnumRepresentation::Enum
+  }
+}
+
 /// Information about a Rust type wrapper
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[derive(Serialize, Deserialize)]
@@ -60,6 +128,8 @@ pub enum RustTypeWrapperKind {
     /// for this type, i.e. if `QFlags<T>` with this C++ type
     /// is used in API.
     is_flaggable: bool,
+    /// Representation of the enum in Rust
+    representation: RustEnumRepresentation,
   },
   /// Struct wrapper
   Struct {
@@ -75,6 +145,8 @@ pub enum RustTypeWrapperKind {
     is_deletable: bool,
     /// Additional information for a Qt slot wrapper struct
     slot_wrapper: Option<RustQtSlotWrapper>,
+    /// Additional information for a generated subclass struct
+    subclass: Option<RustSubclass>,
   },
 }
 
@@ -215,6 +287,10 @@ pub struct RustMethod {
   pub variant_docs: Vec<RustMethodDocItem>,
   /// Rustdoc content that will appear before documentation for variants.
   pub common_doc: Option<String>,
+  /// Deprecation information, if the method is deprecated.
+  pub deprecation: Option<CppDeprecation>,
+  /// Condition on target platforms this method is available for.
+  pub condition: Option<Condition>,
 }
 
 /// Information about type of `self` argument of the method.
@@ -236,6 +312,12 @@ pub enum TraitImplExtra {
   /// For `CppDeletable` trait implementation,
   /// `deleter_name` contains name of the FFI function used as deleter.
   CppDeletable { deleter_name: String },
+  /// For `PartialOrd` trait implementation, `partial_cmp` method
+  /// is generated using the `lt` method of the implementation.
+  PartialOrd,
+  /// For `Clone` trait implementation of a `Copy` type,
+  /// `clone` method returns a copy of `self`.
+  CopyClone,
 }
 
 /// Information about an associated type value
@@ -287,6 +369,34 @@ pub struct RustQtReceiverDeclaration {
   pub receiver_id: String,
   /// Types or arguments.
   pub arguments: Vec<RustType>,
+  /// Closure slot type with the same arguments that can be connected
+  /// to this signal by `connect_closure`. Always `None` for slots.
+  pub closure_slot_type: Option<RustName>,
+}
+
+/// Declaration of a typed accessor of a Qt property
+/// declared with `Q_PROPERTY` macro.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RustQtPropertyDeclaration {
+  /// Name of the accessor type.
+  pub type_name: String,
+  /// Name of the method in `Properties` type that
+  /// creates an object of this type.
+  pub method_name: String,
+  /// C++ name of the property
+  pub original_name: String,
+  /// Return type of the getter.
+  pub value_type: RustType,
+  /// Name of the getter method.
+  pub getter: String,
+  /// Name of the setter method, if any.
+  pub setter: Option<String>,
+  /// Type of the setter's argument, if there is a setter.
+  pub setter_argument_type: Option<RustType>,
+  /// Name of the reset method, if any.
+  pub reset: Option<String>,
+  /// Name of the method in `Signals` type that returns the notify signal, if any.
+  pub notify_signal: Option<String>,
 }
 
 /// Part of the information about a Rust type declaration.
@@ -310,6 +420,8 @@ pub enum RustTypeDeclarationKind {
     /// List of Qt receiver types for signals and slots of
     /// this C++ type.
     qt_receivers: Vec<RustQtReceiverDeclaration>,
+    /// List of accessors of Qt properties of this C++ type.
+    qt_properties: Vec<RustQtPropertyDeclaration>,
   },
   /// Information about a Rust trait created for overloading emulation.
   MethodParametersTrait {
@@ -332,6 +444,37 @@ pub enum RustTypeDeclarationKind {
     /// (used for generating documentation).
     method_name: RustName,
   },
+  /// Information about a Rust type alias created for a C++ typedef.
+  TypeAlias {
+    /// Fully qualified name of the C++ typedef.
+    cpp_typedef_name: String,
+    /// Rust type the alias refers to.
+    target_type: RustType,
+    /// C++ documentation of the typedef.
+    cpp_doc: Option<CppTypeDoc>,
+  },
+  /// Information about a Rust iterator type created for a C++ container
+  /// that provides a pair of iterators.
+  IteratorAdapter {
+    /// Rust type of the container.
+    container_type: RustType,
+    /// Rust type of the C++ iterator.
+    iterator_type: RustType,
+    /// Type of items produced by the iterator.
+    item_type: RustType,
+    /// Method of the container returning iterator to the first item.
+    begin: RustMethodArgumentsVariant,
+    /// Method of the container returning past-the-end iterator.
+    end: RustMethodArgumentsVariant,
+    /// `operator*` of the iterator.
+    deref: RustMethodArgumentsVariant,
+    /// Prefix `operator++` of the iterator.
+    increment: RustMethodArgumentsVariant,
+    /// `operator!=` or `operator==` of the iterator.
+    comparison: RustMethodArgumentsVariant,
+    /// True if `comparison` is `operator==`.
+    comparison_is_equal: bool,
+  },
 }
 
 /// Information about a Rust type declaration.
@@ -346,6 +489,10 @@ pub struct RustTypeDeclaration {
   /// Additional documentation content that will appear before C++ documentation or any other
   /// automatically generated content.
   pub rust_doc: Option<String>,
+  /// Deprecation information, if the type is deprecated.
+  pub deprecation: Option<CppDeprecation>,
+  /// Condition on target platforms this type is available for.
+  pub condition: Option<Condition>,
 }
 
 /// Information about a Rust module.
@@ -359,6 +506,8 @@ pub struct RustModule {
   pub types: Vec<RustTypeDeclaration>,
   /// Free functions within the module.
   pub functions: Vec<RustMethod>,
+  /// Constants within the module.
+  pub constants: Vec<RustConstant>,
   /// Trait implementations associated with free functions.
   pub trait_impls: Vec<TraitImpl>,
   /// Markdown content of Rust documentation for this module.
@@ -367,6 +516,22 @@ pub struct RustModule {
   pub submodules: Vec<RustModule>,
 }
 
+/// Information about a Rust constant generated from a C++ constant.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct RustConstant {
+  /// Full name of the constant
+  pub name: RustName,
+  /// Type of the constant
+  pub value_type: RustType,
+  /// Rust code of the value
+  pub value: String,
+  /// Name of the corresponding C++ entity
+  pub cpp_name: String,
+  /// True if the constant was generated from a preprocessor macro
+  pub is_cpp_macro: bool,
+}
+
 /// Information about a loaded dependency.
 #[derive(Debug, Clone)]
 pub struct DependencyInfo {
@@ -394,6 +559,46 @@ pub enum RustMethodCaptionStrategy {
   SelfAndIndex,
 }
 
+impl RustMethodArgumentsVariant {
+  /// Returns index of the FFI function argument receiving
+  /// information about a caught C++ exception, if any.
+  pub fn exception_slot_ffi_index(&self) -> Option<usize> {
+    self
+      .cpp_method
+      .c_signature
+      .arguments
+      .iter()
+      .position(|arg| arg.meaning == CppFfiArgumentMeaning::ExceptionSlot)
+  }
+
+  /// Returns return type of the public API method. If the C++ method
+  /// may throw and exceptions are caught by the wrapper, the type is
+  /// `Result<T, CppException>`, where `T` is Rust API return type of the C++ method.
+  pub fn api_return_type(&self) -> RustType {
+    self.wrap_api_return_type(self.return_type.rust_api_type.clone())
+  }
+
+  /// Wraps `rust_type` in `Result<_, CppException>` if the C++ method
+  /// may throw and exceptions are caught by the wrapper.
+  /// Returns `rust_type` unchanged otherwise.
+  pub fn wrap_api_return_type(&self, rust_type: RustType) -> RustType {
+    if self.exception_slot_ffi_index().is_none() {
+      return rust_type;
+    }
+    let common = |parts: &[&str], generic_arguments: Option<Vec<RustType>>| {
+      RustType::Common {
+        base: RustName { parts: parts.iter().map(|x| x.to_string()).collect() },
+        generic_arguments: generic_arguments,
+        is_const: false,
+        is_const2: false,
+        indirection: RustTypeIndirection::None,
+      }
+    };
+    common(&["std", "result", "Result"],
+           Some(vec![rust_type, common(&["cpp_utils", "CppException"], None)]))
+  }
+}
+
 impl RustMethodCaptionStrategy {
   /// Returns list of all available strategies sorted by priority
   /// (more preferred strategies go first).
@@ -458,4 +663,6 @@ pub struct RustFFIFunction {
   pub name: String,
   /// Arguments of the function.
   pub arguments: Vec<RustFFIArgument>,
+  /// Condition on target platforms this function is available for.
+  pub condition: Option<Condition>,
 }
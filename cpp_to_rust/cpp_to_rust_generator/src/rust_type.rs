@@ -36,6 +36,17 @@ pub enum RustToCTypeConversion {
   CppBoxToPtr,
   /// `qt_core::flags::Flags<T>` to `libc::c_uint`
   QFlagsToUInt,
+  /// `&mut FnMut` closure to a trampoline function pointer.
+  /// The closure is passed to the trampoline through a user data pointer.
+  ClosureToCallback {
+    /// Index of the user data argument in the FFI function's arguments
+    user_data_ffi_index: usize,
+    /// Index of the user data argument in the callback's arguments
+    callback_user_data_index: usize,
+  },
+  /// `Box<FnMut>` closure to `cpp_utils::CppClosure`
+  /// (used for C++ `std::function` type)
+  BoxToCppClosure,
 }
 
 /// Information about a completely processed type
@@ -107,6 +118,26 @@ pub enum RustType {
     /// Argument types of the function.
     arguments: Vec<RustType>,
   },
+  /// A mutable reference to a closure, e.g. `&'a mut FnMut(i32) -> bool`,
+  /// or a boxed closure, e.g. `Box<Fn(i32) -> bool + Send + Sync>`.
+  /// References are used in place of a C callback accompanied by a user data pointer,
+  /// and boxes are used in place of C++ `std::function` type. Boxed closures
+  /// are `Fn` because copies of a `std::function` share the closure and
+  /// may call it re-entrantly.
+  Closure {
+    /// Return type of the closure.
+    return_type: Box<RustType>,
+    /// Argument types of the closure.
+    arguments: Vec<RustType>,
+    /// True if the closure is boxed instead of borrowed.
+    is_boxed: bool,
+    /// True if the closure must implement `Send` and `Sync`. This is set
+    /// for boxed closures passed to C++ because C++ code may call
+    /// and destroy them on any thread.
+    is_send: bool,
+    /// Lifetime of the reference. Always `None` for boxed closures.
+    lifetime: Option<String>,
+  },
 }
 
 
@@ -240,11 +271,11 @@ impl RustType {
       name
     }
          RustType::FunctionPointer { .. } => "fn".to_string(),
+         RustType::Closure { .. } => "closure".to_string(),
        })
   }
 
   /// Returns true if this type is a reference.
-  #[allow(dead_code)]
   pub fn is_ref(&self) -> bool {
     match *self {
       RustType::Common { ref indirection, .. } => {
@@ -254,6 +285,7 @@ impl RustType {
           _ => false,
         }
       }
+      RustType::Closure { ref is_boxed, .. } => !*is_boxed,
       RustType::EmptyTuple |
       RustType::FunctionPointer { .. } => false,
     }
@@ -262,12 +294,24 @@ impl RustType {
   /// Returns a copy of this type with `new_lifetime` added, if possible.
   pub fn with_lifetime(&self, new_lifetime: String) -> RustType {
     let mut r = self.clone();
-    if let RustType::Common { ref mut indirection, .. } = r {
-      match *indirection {
-        RustTypeIndirection::Ref { ref mut lifetime } |
-        RustTypeIndirection::PtrRef { ref mut lifetime } => *lifetime = Some(new_lifetime),
-        _ => {}
+    match r {
+      RustType::Common { ref mut indirection, .. } => {
+        match *indirection {
+          RustTypeIndirection::Ref { ref mut lifetime } |
+          RustTypeIndirection::PtrRef { ref mut lifetime } => *lifetime = Some(new_lifetime),
+          _ => {}
+        }
       }
+      RustType::Closure {
+        ref mut lifetime,
+        ref is_boxed,
+        ..
+      } => {
+        if !*is_boxed {
+          *lifetime = Some(new_lifetime);
+        }
+      }
+      _ => {}
     }
     r
   }
@@ -283,9 +327,31 @@ impl RustType {
           _ => None,
         }
       }
+      RustType::Closure { ref lifetime, .. } => lifetime.as_ref(),
       _ => None,
     }
   }
+  /// Returns the type referenced by this reference type,
+  /// e.g. `Point` for `&'a mut Point`.
+  pub fn ref_to_value(&self) -> Result<RustType> {
+    let mut r = self.clone();
+    if let RustType::Common {
+             ref mut is_const,
+             ref mut indirection,
+             ..
+           } = r {
+      match *indirection {
+        RustTypeIndirection::Ref { .. } => {}
+        _ => return Err("not a reference type".into()),
+      }
+      *indirection = RustTypeIndirection::None;
+      *is_const = false;
+    } else {
+      return Err("not a RustType::Common".into());
+    }
+    Ok(r)
+  }
+
   /// Returns true if indirection that is applied last has const qualifier.
   pub fn last_is_const(&self) -> Result<bool> {
     if let RustType::Common {
@@ -354,7 +420,8 @@ impl RustType {
         }
         false
       }
-      RustType::EmptyTuple => false,
+      RustType::EmptyTuple |
+      RustType::Closure { .. } => false,
       RustType::FunctionPointer { .. } => true,
     }
   }
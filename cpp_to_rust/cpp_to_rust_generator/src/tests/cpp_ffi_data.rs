@@ -253,6 +253,7 @@ fn signature_class_method() {
                           base: CppTypeBase::Class(CppTypeClassBase {
                                                      name: "Class1".to_string(),
                                                      template_arguments: None,
+                                                     enclosing_template_class: None,
                                                    }),
                         },
                         ffi_type: CppType {
@@ -262,6 +263,7 @@ fn signature_class_method() {
                           base: CppTypeBase::Class(CppTypeClassBase {
                                                      name: "Class1".to_string(),
                                                      template_arguments: None,
+                                                     enclosing_template_class: None,
                                                    }),
                         },
                         conversion: CppIndirectionChange::NoChange,
@@ -325,6 +327,7 @@ fn signature_class_method_const() {
                           base: CppTypeBase::Class(CppTypeClassBase {
                                                      name: "Class1".to_string(),
                                                      template_arguments: None,
+                                                     enclosing_template_class: None,
                                                    }),
                         },
                         ffi_type: CppType {
@@ -334,6 +337,7 @@ fn signature_class_method_const() {
                           base: CppTypeBase::Class(CppTypeClassBase {
                                                      name: "Class1".to_string(),
                                                      template_arguments: None,
+                                                     enclosing_template_class: None,
                                                    }),
                         },
                         conversion: CppIndirectionChange::NoChange,
@@ -574,6 +578,7 @@ fn c_base_name_conversion_operator() {
                                    base: CppTypeBase::Class(CppTypeClassBase {
                                                               name: "QPoint".to_string(),
                                                               template_arguments: None,
+                                                              enclosing_template_class: None,
                                                             }),
                                    indirection: CppTypeIndirection::Ref,
                                  }));
@@ -24,14 +24,19 @@ pub fn empty_membership(class_name: &'static str) -> CppMethodClassMembership {
     kind: CppMethodKind::Regular,
     is_virtual: false,
     is_pure_virtual: false,
+    is_final: false,
     is_const: false,
     is_static: false,
     visibility: CppVisibility::Public,
     is_signal: false,
     is_slot: false,
+    is_explicit: false,
+    is_deleted: false,
+    is_defaulted: false,
     class_type: CppTypeClassBase {
       name: class_name.to_string(),
       template_arguments: None,
+      enclosing_template_class: None,
     },
   }
 }
@@ -45,6 +50,9 @@ pub fn empty_regular_method() -> CppMethod {
     doc: None,
     inheritance_chain: Vec::new(),
     allows_variadic_arguments: false,
+    exception_specification: CppExceptionSpecification::None,
+    deprecation: None,
+    condition: None,
     include_file: String::new(),
     origin_location: None,
     template_arguments: None,
@@ -318,6 +326,7 @@ fn c_signature_method_with_this() {
               base: CppTypeBase::Class(CppTypeClassBase {
                                          name: "MyClass2".to_string(),
                                          template_arguments: None,
+                                         enclosing_template_class: None,
                                        }),
             },
             name: "my_arg".to_string(),
@@ -450,6 +459,7 @@ fn c_signature_constructor() {
                base: CppTypeBase::Class(CppTypeClassBase {
                                           name: "MyClass".to_string(),
                                           template_arguments: None,
+                                          enclosing_template_class: None,
                                         }),
              });
   assert_eq!(r_stack.arguments[1].argument_type.conversion,
@@ -483,6 +493,7 @@ fn c_signature_constructor() {
                base: CppTypeBase::Class(CppTypeClassBase {
                                           name: "MyClass".to_string(),
                                           template_arguments: None,
+                                          enclosing_template_class: None,
                                         }),
              });
   assert_eq!(r_heap.return_type.conversion,
@@ -555,6 +566,7 @@ fn c_signature_method_returning_class() {
     base: CppTypeBase::Class(CppTypeClassBase {
                                name: "MyClass3".to_string(),
                                template_arguments: None,
+                               enclosing_template_class: None,
                              }),
   };
   method1
@@ -567,6 +579,7 @@ fn c_signature_method_returning_class() {
               base: CppTypeBase::Class(CppTypeClassBase {
                                          name: "MyClass2".to_string(),
                                          template_arguments: None,
+                                         enclosing_template_class: None,
                                        }),
             },
             name: "my_arg".to_string(),
@@ -609,6 +622,7 @@ fn c_signature_method_returning_class() {
                base: CppTypeBase::Class(CppTypeClassBase {
                                           name: "MyClass3".to_string(),
                                           template_arguments: None,
+                                          enclosing_template_class: None,
                                         }),
              });
   assert_eq!(r_stack.arguments[2].argument_type.conversion,
@@ -654,6 +668,7 @@ fn c_signature_method_returning_class() {
                base: CppTypeBase::Class(CppTypeClassBase {
                                           name: "MyClass3".to_string(),
                                           template_arguments: None,
+                                          enclosing_template_class: None,
                                         }),
              });
   assert_eq!(r_heap.return_type.conversion,
@@ -703,14 +718,19 @@ fn short_text1() {
                              kind: CppMethodKind::Regular,
                              is_virtual: false,
                              is_pure_virtual: false,
+                             is_final: false,
                              is_const: true,
                              is_static: false,
                              visibility: CppVisibility::Protected,
                              is_signal: false,
                              is_slot: false,
+                             is_explicit: false,
+                             is_deleted: false,
+                             is_defaulted: false,
                              class_type: CppTypeClassBase {
                                name: "Class1".to_string(),
                                template_arguments: None,
+                               enclosing_template_class: None,
                              },
                            }),
     operator: None,
@@ -743,6 +763,9 @@ fn short_text1() {
     doc: None,
     inheritance_chain: Vec::new(),
     allows_variadic_arguments: false,
+    exception_specification: CppExceptionSpecification::None,
+    deprecation: None,
+    condition: None,
     include_file: String::new(),
     origin_location: None,
     template_arguments: None,
@@ -753,3 +776,58 @@ fn short_text1() {
   assert_eq!(method.short_text(),
              "protected int Class1::method1(int arg1, double arg2 = ?) const");
 }
+
+#[test]
+fn copy_constructor() {
+  let class_type = CppType {
+    indirection: CppTypeIndirection::Ref,
+    is_const: true,
+    is_const2: false,
+    base: CppTypeBase::Class(CppTypeClassBase {
+                               name: "Class1".to_string(),
+                               template_arguments: None,
+                               enclosing_template_class: None,
+                             }),
+  };
+  let mut method1 = empty_regular_method();
+  method1.name = "Class1".to_string();
+  method1.class_membership = Some(CppMethodClassMembership {
+                                    kind: CppMethodKind::Constructor,
+                                    ..empty_membership("Class1")
+                                  });
+  assert!(!method1.is_copy_constructor());
+  method1
+    .arguments
+    .push(CppMethodArgument {
+            argument_type: class_type.clone(),
+            name: "other".to_string(),
+            has_default_value: false,
+          });
+  assert!(method1.is_copy_constructor());
+  assert!(!method1.is_converting_constructor());
+
+  let mut method2 = method1.clone();
+  method2.arguments[0].argument_type.indirection = CppTypeIndirection::Ptr;
+  assert!(!method2.is_copy_constructor());
+
+  let mut method3 = method1.clone();
+  method3
+    .arguments
+    .push(CppMethodArgument {
+            argument_type: CppType {
+              indirection: CppTypeIndirection::None,
+              is_const: false,
+              is_const2: false,
+              base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+            },
+            name: "flags".to_string(),
+            has_default_value: false,
+          });
+  assert!(!method3.is_copy_constructor());
+  method3.arguments[1].has_default_value = true;
+  assert!(method3.is_copy_constructor());
+
+  let mut method4 = method1.clone();
+  method4.class_membership = Some(empty_membership("Class1"));
+  assert!(!method4.is_copy_constructor());
+}
@@ -1,15 +1,30 @@
 extern crate tempdir;
 
 use cpp_parser;
+use cpp_post_processor::cpp_post_process;
 use cpp_data::*;
 use cpp_method::*;
 use cpp_type::*;
 use cpp_operator::CppOperator;
+use cpp_multi_target;
+use common::target::{Target, Arch, OS, Family, Env, PointerWidth, Endian, Condition};
 use common::file_utils::{create_dir, create_file, PathBufWithAdded};
 
 use std::path::PathBuf;
+use std::collections::HashMap;
 
 fn run_parser(code: &'static str) -> ParserCppData {
+  run_parser_with_macros(code, Vec::new())
+}
+
+fn run_parser_with_macros(code: &'static str, macro_whitelist: Vec<String>) -> ParserCppData {
+  run_parser_with_arguments(code, macro_whitelist, Vec::new())
+}
+
+fn run_parser_with_arguments(code: &'static str,
+                             macro_whitelist: Vec<String>,
+                             clang_arguments: Vec<String>)
+                             -> ParserCppData {
   let dir = tempdir::TempDir::new("test_cpp_parser_run").unwrap();
   let include_dir = dir.path().with_added("include");
   create_dir(&include_dir).unwrap();
@@ -26,8 +41,9 @@ fn run_parser(code: &'static str) -> ParserCppData {
                                      target_include_paths: Vec::new(),
                                      tmp_cpp_path: dir.path().with_added("1.cpp"),
                                      name_blacklist: Vec::new(),
+                                     macro_whitelist: macro_whitelist,
                                      framework_paths: Vec::new(),
-                                     clang_arguments: Vec::new(),
+                                     clang_arguments: clang_arguments,
                                    },
                                    &[])
       .unwrap();
@@ -73,6 +89,9 @@ fn simple_func() {
                doc: None,
                inheritance_chain: Vec::new(),
                allows_variadic_arguments: false,
+               exception_specification: CppExceptionSpecification::None,
+               deprecation: None,
+               condition: None,
                include_file: "myfakelib.h".to_string(),
                origin_location: None,
                template_arguments: None,
@@ -111,6 +130,9 @@ fn simple_func_with_default_value() {
                doc: None,
                inheritance_chain: Vec::new(),
                allows_variadic_arguments: false,
+               exception_specification: CppExceptionSpecification::None,
+               deprecation: None,
+               condition: None,
                include_file: "myfakelib.h".to_string(),
                origin_location: None,
                template_arguments: None,
@@ -181,6 +203,7 @@ fn functions_with_class_arg() {
                                    base: CppTypeBase::Class(CppTypeClassBase {
                                                               name: "Magic".to_string(),
                                                               template_arguments: None,
+                                                              enclosing_template_class: None,
                                                             }),
                                  },
                                  has_default_value: false,
@@ -188,6 +211,9 @@ fn functions_with_class_arg() {
                doc: None,
                inheritance_chain: Vec::new(),
                allows_variadic_arguments: false,
+               exception_specification: CppExceptionSpecification::None,
+               deprecation: None,
+               condition: None,
                include_file: "myfakelib.h".to_string(),
                origin_location: None,
                template_arguments: None,
@@ -215,6 +241,7 @@ fn functions_with_class_arg() {
                                    base: CppTypeBase::Class(CppTypeClassBase {
                                                               name: "Magic".to_string(),
                                                               template_arguments: None,
+                                                              enclosing_template_class: None,
                                                             }),
                                  },
                                  has_default_value: false,
@@ -222,6 +249,9 @@ fn functions_with_class_arg() {
                doc: None,
                inheritance_chain: Vec::new(),
                allows_variadic_arguments: false,
+               exception_specification: CppExceptionSpecification::None,
+               deprecation: None,
+               condition: None,
                include_file: "myfakelib.h".to_string(),
                origin_location: None,
                template_arguments: None,
@@ -249,6 +279,7 @@ fn functions_with_class_arg() {
                                    base: CppTypeBase::Class(CppTypeClassBase {
                                                               name: "Magic".to_string(),
                                                               template_arguments: None,
+                                                              enclosing_template_class: None,
                                                             }),
                                  },
                                  has_default_value: false,
@@ -256,6 +287,9 @@ fn functions_with_class_arg() {
                doc: None,
                inheritance_chain: Vec::new(),
                allows_variadic_arguments: false,
+               exception_specification: CppExceptionSpecification::None,
+               deprecation: None,
+               condition: None,
                include_file: "myfakelib.h".to_string(),
                origin_location: None,
                template_arguments: None,
@@ -301,6 +335,9 @@ fn variadic_func() {
                doc: None,
                inheritance_chain: Vec::new(),
                allows_variadic_arguments: true,
+               exception_specification: CppExceptionSpecification::None,
+               deprecation: None,
+               condition: None,
                include_file: "myfakelib.h".to_string(),
                origin_location: None,
                template_arguments: None,
@@ -345,11 +382,15 @@ fn free_template_func() {
                doc: None,
                inheritance_chain: Vec::new(),
                allows_variadic_arguments: false,
+               exception_specification: CppExceptionSpecification::None,
+               deprecation: None,
+               condition: None,
                include_file: "myfakelib.h".to_string(),
                origin_location: None,
                template_arguments: Some(TemplateArgumentsDeclaration {
                                           nested_level: 0,
                                           names: vec!["T".to_string()],
+                                          value_parameters: Vec::new(),
                                         }),
                template_arguments_values: None,
                declaration_code: Some("template < typename T > T abs ( T value )".to_string()),
@@ -376,6 +417,7 @@ fn free_func_operator_sub() {
                    base: CppTypeBase::Class(CppTypeClassBase {
                                               name: "C1".to_string(),
                                               template_arguments: None,
+                                              enclosing_template_class: None,
                                             }),
                  },
                  arguments: vec![CppMethodArgument {
@@ -387,6 +429,7 @@ fn free_func_operator_sub() {
                                      base: CppTypeBase::Class(CppTypeClassBase {
                                                                 name: "C1".to_string(),
                                                                 template_arguments: None,
+                                                                enclosing_template_class: None,
                                                               }),
                                    },
                                    has_default_value: false,
@@ -400,6 +443,7 @@ fn free_func_operator_sub() {
                                      base: CppTypeBase::Class(CppTypeClassBase {
                                                                 name: "C1".to_string(),
                                                                 template_arguments: None,
+                                                                enclosing_template_class: None,
                                                               }),
                                    },
                                    has_default_value: false,
@@ -407,6 +451,9 @@ fn free_func_operator_sub() {
                  doc: None,
                  inheritance_chain: Vec::new(),
                  allows_variadic_arguments: false,
+                 exception_specification: CppExceptionSpecification::None,
+                 deprecation: None,
+                 condition: None,
                  include_file: "myfakelib.h".to_string(),
                  origin_location: None,
                  template_arguments: None,
@@ -447,15 +494,20 @@ fn simple_class_method() {
                                         class_type: CppTypeClassBase {
                                           name: "MyClass".to_string(),
                                           template_arguments: None,
+                                          enclosing_template_class: None,
                                         },
                                         kind: CppMethodKind::Regular,
                                         is_virtual: false,
                                         is_pure_virtual: false,
+                                        is_final: false,
                                         is_const: false,
                                         is_static: false,
                                         visibility: CppVisibility::Public,
                                         is_signal: false,
                                         is_slot: false,
+                                        is_explicit: false,
+                                        is_deleted: false,
+                                        is_defaulted: false,
                                       }),
                operator: None,
                return_type: CppType {
@@ -477,6 +529,9 @@ fn simple_class_method() {
                doc: None,
                inheritance_chain: Vec::new(),
                allows_variadic_arguments: false,
+               exception_specification: CppExceptionSpecification::None,
+               deprecation: None,
+               condition: None,
                include_file: "myfakelib.h".to_string(),
                origin_location: None,
                template_arguments: None,
@@ -600,6 +655,7 @@ fn advanced_class_methods() {
              Some(TemplateArgumentsDeclaration {
                     nested_level: 0,
                     names: vec!["K".to_string(), "V".to_string()],
+                    value_parameters: Vec::new(),
                   }));
   assert_eq!(data.methods[7].arguments.len(), 1);
   assert_eq!(data.methods[7].arguments[0].argument_type,
@@ -636,6 +692,7 @@ fn template_class_method() {
                &Some(TemplateArgumentsDeclaration {
                        nested_level: 0,
                        names: vec!["T".to_string()],
+                       value_parameters: Vec::new(),
                      }));
     assert!(bases.is_empty());
     assert!(fields.is_empty());
@@ -658,15 +715,20 @@ fn template_class_method() {
                                                      index: 0,
                                                    },
                                                  }]),
+                                          enclosing_template_class: None,
                                         },
                                         kind: CppMethodKind::Regular,
                                         is_virtual: false,
                                         is_pure_virtual: false,
+                                        is_final: false,
                                         is_const: false,
                                         is_static: false,
                                         visibility: CppVisibility::Public,
                                         is_signal: false,
                                         is_slot: false,
+                                        is_explicit: false,
+                                        is_deleted: false,
+                                        is_defaulted: false,
                                       }),
                operator: None,
                return_type: CppType {
@@ -691,6 +753,9 @@ fn template_class_method() {
                doc: None,
                inheritance_chain: Vec::new(),
                allows_variadic_arguments: false,
+               exception_specification: CppExceptionSpecification::None,
+               deprecation: None,
+               condition: None,
                include_file: "myfakelib.h".to_string(),
                origin_location: None,
                template_arguments: None,
@@ -716,6 +781,7 @@ fn template_class_template_method() {
              Some(TemplateArgumentsDeclaration {
                     nested_level: 1,
                     names: vec!["F".to_string()],
+                    value_parameters: Vec::new(),
                   }));
   assert_eq!(data.methods[0].return_type,
              CppType {
@@ -823,6 +889,7 @@ fn template_instantiation() {
                base: CppTypeBase::Class(CppTypeClassBase {
                                           name: "Vector".to_string(),
                                           template_arguments: Some(vec![int.clone()]),
+                                          enclosing_template_class: None,
                                         }),
              });
   // TODO: test template_instantiations
@@ -960,6 +1027,7 @@ fn derived_class_simple() {
                          base: CppTypeBase::Class(CppTypeClassBase {
                                                     name: "Base".to_string(),
                                                     template_arguments: None,
+                                                    enclosing_template_class: None,
                                                   }),
                        },
                        is_virtual: false,
@@ -991,6 +1059,7 @@ fn derived_class_simple_private() {
                          base: CppTypeBase::Class(CppTypeClassBase {
                                                     name: "Base".to_string(),
                                                     template_arguments: None,
+                                                    enclosing_template_class: None,
                                                   }),
                        },
                        is_virtual: false,
@@ -1022,6 +1091,7 @@ fn derived_class_simple_virtual() {
                          base: CppTypeBase::Class(CppTypeClassBase {
                                                     name: "Base".to_string(),
                                                     template_arguments: None,
+                                                    enclosing_template_class: None,
                                                   }),
                        },
                        is_virtual: true,
@@ -1051,6 +1121,7 @@ fn derived_class_multiple() {
                          base: CppTypeBase::Class(CppTypeClassBase {
                                                     name: "Base2".to_string(),
                                                     template_arguments: None,
+                                                    enclosing_template_class: None,
                                                   }),
                        },
                        is_virtual: false,
@@ -1064,6 +1135,7 @@ fn derived_class_multiple() {
                          base: CppTypeBase::Class(CppTypeClassBase {
                                                     name: "Base1".to_string(),
                                                     template_arguments: None,
+                                                    enclosing_template_class: None,
                                                   }),
                        },
                        is_virtual: false,
@@ -1201,7 +1273,18 @@ fn non_type_template_parameter() {
   let data = run_parser("\
   template<int> struct QAtomicOpsSupport { enum { IsSupported = 0 }; };
   template<> struct QAtomicOpsSupport<4> { enum { IsSupported = 1 }; };");
-  assert!(data.types.is_empty());
+  assert_eq!(data.types.len(), 1);
+  assert_eq!(data.types[0].name, "QAtomicOpsSupport");
+  if let CppTypeKind::Class { ref template_arguments, .. } = data.types[0].kind {
+    assert_eq!(template_arguments,
+               &Some(TemplateArgumentsDeclaration {
+                       nested_level: 0,
+                       names: vec!["Value1".to_string()],
+                       value_parameters: vec![0],
+                     }));
+  } else {
+    panic!("invalid type kind");
+  }
 }
 
 #[test]
@@ -1237,11 +1320,74 @@ fn fixed_size_integers() {
                 base: CppTypeBase::Class(CppTypeClassBase {
                                            name: "QVector".to_string(),
                                            template_arguments: Some(vec![type1.clone()]),
+                                           enclosing_template_class: None,
                                          }),
               });
 
 }
 
+#[test]
+fn non_type_template_argument() {
+  let data = run_parser("
+  template<typename T, int N> class MyArray {
+  public:
+    T* data();
+  };
+  MyArray<float, 3> f1();
+  ");
+  assert_eq!(data.types.len(), 1);
+  if let CppTypeKind::Class { ref template_arguments, .. } = data.types[0].kind {
+    assert_eq!(template_arguments,
+               &Some(TemplateArgumentsDeclaration {
+                       nested_level: 0,
+                       names: vec!["T".to_string(), "N".to_string()],
+                       value_parameters: vec![1],
+                     }));
+  } else {
+    panic!("invalid type kind");
+  }
+  let f1 = data
+    .methods
+    .iter()
+    .find(|m| m.name == "f1")
+    .unwrap();
+  let float = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Float),
+  };
+  let three = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::TemplateArgumentValue {
+      value: 3,
+      enum_name: None,
+    },
+  };
+  assert_eq!(f1.return_type,
+             CppType {
+               indirection: CppTypeIndirection::None,
+               is_const: false,
+               is_const2: false,
+               base: CppTypeBase::Class(CppTypeClassBase {
+                                          name: "MyArray".to_string(),
+                                          template_arguments: Some(vec![float.clone(),
+                                                                        three.clone()]),
+                                          enclosing_template_class: None,
+                                        }),
+             });
+  let data = cpp_post_process(data, Vec::new(), &HashMap::new()).unwrap();
+  assert_eq!(data.current.processed.template_instantiations,
+             vec![CppTemplateInstantiations {
+                    class_name: "MyArray".to_string(),
+                    instantiations: vec![CppTemplateInstantiation {
+                                           template_arguments: vec![float, three],
+                                         }],
+                  }]);
+}
+
 
 #[test]
 fn template_class_with_base() {
@@ -1264,6 +1410,7 @@ fn template_class_with_base() {
                &Some(TemplateArgumentsDeclaration {
                        nested_level: 0,
                        names: vec!["T".to_string()],
+                       value_parameters: Vec::new(),
                      }));
     assert!(bases.is_empty());
     assert!(fields.is_empty());
@@ -1282,6 +1429,7 @@ fn template_class_with_base() {
                &Some(TemplateArgumentsDeclaration {
                        nested_level: 0,
                        names: vec!["T".to_string()],
+                       value_parameters: Vec::new(),
                      }));
     assert_eq!(bases.len(), 1);
     assert!(fields.is_empty());
@@ -1289,3 +1437,1053 @@ fn template_class_with_base() {
     panic!("invalid type kind");
   }
 }
+
+#[test]
+fn typedefs() {
+  let data = run_parser("
+  class Magic {
+  public:
+    typedef int Handle;
+  };
+  typedef unsigned int MyUInt;
+  using MagicPtr = Magic*;
+  template<typename T> class C1 {
+  public:
+    typedef T Item;
+  };
+  typedef unsigned long long int GLuint64;
+  ");
+  assert_eq!(data.typedefs.len(), 3);
+  assert_eq!(data.typedefs[0].name, "Magic::Handle");
+  assert_eq!(data.typedefs[0].include_file, "myfakelib.h");
+  assert_eq!(data.typedefs[0].target_type,
+             CppType {
+               indirection: CppTypeIndirection::None,
+               is_const: false,
+               is_const2: false,
+               base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+             });
+  assert_eq!(data.typedefs[1].name, "MyUInt");
+  assert_eq!(data.typedefs[1].target_type,
+             CppType {
+               indirection: CppTypeIndirection::None,
+               is_const: false,
+               is_const2: false,
+               base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::UInt),
+             });
+  assert_eq!(data.typedefs[2].name, "MagicPtr");
+  assert_eq!(data.typedefs[2].target_type,
+             CppType {
+               indirection: CppTypeIndirection::Ptr,
+               is_const: false,
+               is_const2: false,
+               base: CppTypeBase::Class(CppTypeClassBase {
+                                          name: "Magic".to_string(),
+                                          template_arguments: None,
+                                          enclosing_template_class: None,
+                                        }),
+             });
+  assert!(data.typedefs[2].origin_location.line > 0);
+}
+
+#[test]
+fn variables() {
+  let data = run_parser("
+  namespace ns {
+    extern int counter;
+    constexpr double pi = 3.14;
+  }
+  class Magic {
+  public:
+    static const int max_count = -0x10;
+    static Magic instance;
+  private:
+    static int secret;
+  };
+  const char* const magic_name = \"magic\";
+  ");
+  assert_eq!(data.variables.len(), 5);
+  assert_eq!(data.variables[0].name, "ns::counter");
+  assert_eq!(data.variables[0].include_file, "myfakelib.h");
+  assert_eq!(data.variables[0].class_type, None);
+  assert_eq!(data.variables[0].variable_type,
+             CppType {
+               indirection: CppTypeIndirection::None,
+               is_const: false,
+               is_const2: false,
+               base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+             });
+  assert!(!data.variables[0].is_const);
+  assert_eq!(data.variables[0].value, None);
+
+  assert_eq!(data.variables[1].name, "ns::pi");
+  assert!(data.variables[1].is_const);
+  assert!(data.variables[1].is_constexpr);
+  assert_eq!(data.variables[1].value,
+             Some(CppLiteralValue::Float("3.14".to_string())));
+
+  assert_eq!(data.variables[2].name, "Magic::max_count");
+  assert_eq!(data.variables[2].short_name(), "max_count");
+  assert_eq!(data.variables[2].class_type,
+             Some(CppTypeClassBase {
+                    name: "Magic".to_string(),
+                    template_arguments: None,
+                    enclosing_template_class: None,
+                  }));
+  assert!(data.variables[2].is_const);
+  assert!(!data.variables[2].is_constexpr);
+  assert_eq!(data.variables[2].value, Some(CppLiteralValue::Integer(-16)));
+
+  assert_eq!(data.variables[3].name, "Magic::instance");
+  assert!(!data.variables[3].is_const);
+  assert_eq!(data.variables[3].value, None);
+
+  assert_eq!(data.variables[4].name, "magic_name");
+  assert!(data.variables[4].is_const);
+  assert_eq!(data.variables[4].value,
+             Some(CppLiteralValue::String("magic".to_string())));
+  assert!(data.variables[4].origin_location.line > 0);
+}
+
+#[test]
+fn char_literals_and_digit_separators() {
+  let data = run_parser("
+  constexpr char c1 = '1';
+  constexpr char c2 = '\\n';
+  constexpr char c3 = '\\'';
+  constexpr int v1 = 1'000'000;
+  constexpr unsigned int v2 = 0xFFFF'FFFFu;
+  constexpr wchar_t c4 = L'a';
+  ");
+  let value = |name: &str| {
+    data
+      .variables
+      .iter()
+      .find(|v| v.name == name)
+      .unwrap()
+      .value
+      .clone()
+  };
+  assert_eq!(value("c1"), Some(CppLiteralValue::Integer(49)));
+  assert_eq!(value("c2"), Some(CppLiteralValue::Integer(10)));
+  assert_eq!(value("c3"), Some(CppLiteralValue::Integer(39)));
+  assert_eq!(value("v1"), Some(CppLiteralValue::Integer(1000000)));
+  assert_eq!(value("v2"), Some(CppLiteralValue::Integer(0xFFFFFFFF)));
+  assert_eq!(value("c4"), None);
+}
+
+#[test]
+fn macros() {
+  let data = run_parser_with_macros("
+  #define MY_VERSION 0x050900
+  #define MY_VERSION_STR \"5.9.0\"
+  #define MY_PI (3.14f)
+  #define MY_MIN -(1)
+  #define MY_DEFAULT_VERSION MY_VERSION
+  #define MY_MAX(a, b) ((a) > (b) ? (a) : (b))
+  #define MY_EMPTY
+  #define OTHER_VALUE 1
+  int f1();
+  ",
+                                    vec!["MY_*".to_string()]);
+  assert_eq!(data.macros.len(), 5);
+  assert_eq!(data.macros[0].name, "MY_VERSION");
+  assert_eq!(data.macros[0].include_file, "myfakelib.h");
+  assert_eq!(data.macros[0].value, CppLiteralValue::Integer(0x050900));
+  assert!(data.macros[0].origin_location.line > 0);
+  assert_eq!(data.macros[1].name, "MY_VERSION_STR");
+  assert_eq!(data.macros[1].value,
+             CppLiteralValue::String("5.9.0".to_string()));
+  assert_eq!(data.macros[2].name, "MY_PI");
+  assert_eq!(data.macros[2].value,
+             CppLiteralValue::Float("3.14".to_string()));
+  assert_eq!(data.macros[3].name, "MY_MIN");
+  assert_eq!(data.macros[3].value, CppLiteralValue::Integer(-1));
+  assert_eq!(data.macros[4].name, "MY_DEFAULT_VERSION");
+  assert_eq!(data.macros[4].value, CppLiteralValue::Integer(0x050900));
+}
+
+#[test]
+fn unions() {
+  let data = run_parser("
+  union Value {
+    int i;
+    float f;
+  };
+  struct Event {
+    int type;
+    union {
+      int code;
+      double amount;
+    };
+  };
+  Value get_value(const Event& e);
+  ");
+  assert_eq!(data.types.len(), 2);
+  assert_eq!(data.types[0].name, "Value");
+  assert!(data.types[0].is_class());
+  if let CppTypeKind::Union { ref fields } = data.types[0].kind {
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[0].name, "i");
+    assert_eq!(fields[0].field_type,
+               CppType {
+                 indirection: CppTypeIndirection::None,
+                 is_const: false,
+                 is_const2: false,
+                 base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+               });
+    assert_eq!(fields[0].visibility, CppVisibility::Public);
+    assert_eq!(fields[1].name, "f");
+    assert_eq!(fields[1].field_type,
+               CppType {
+                 indirection: CppTypeIndirection::None,
+                 is_const: false,
+                 is_const2: false,
+                 base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Float),
+               });
+  } else {
+    panic!("invalid type kind");
+  }
+  assert_eq!(data.types[1].name, "Event");
+  if let CppTypeKind::Class { ref fields, .. } = data.types[1].kind {
+    let names: Vec<_> = fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["type", "code", "amount"]);
+  } else {
+    panic!("invalid type kind");
+  }
+  assert_eq!(data.methods.len(), 1);
+  assert_eq!(data.methods[0].name, "get_value");
+  assert_eq!(data.methods[0].return_type.base,
+             CppTypeBase::Class(CppTypeClassBase {
+                                  name: "Value".to_string(),
+                                  template_arguments: None,
+                                  enclosing_template_class: None,
+                                }));
+}
+
+#[test]
+fn type_nested_in_template_class() {
+  let data = run_parser("
+  template<typename T> class MyList {
+  public:
+    class iterator {
+    public:
+      T& operator*();
+    };
+    iterator begin();
+  };
+  MyList<int>::iterator f1();
+  ");
+  assert_eq!(data.types.len(), 2);
+  assert_eq!(data.types[0].name, "MyList");
+  assert_eq!(data.types[1].name, "MyList::iterator");
+  assert_eq!(data.types[1].enclosing_template_class,
+             Some("MyList".to_string()));
+  if let CppTypeKind::Class { ref template_arguments, .. } = data.types[1].kind {
+    assert_eq!(template_arguments,
+               &Some(TemplateArgumentsDeclaration {
+                       nested_level: 0,
+                       names: vec!["T".to_string()],
+                       value_parameters: Vec::new(),
+                     }));
+  } else {
+    panic!("invalid type kind");
+  }
+  let iterator_type = |arg: CppType| {
+    CppType {
+      indirection: CppTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: "MyList::iterator".to_string(),
+                                 template_arguments: Some(vec![arg]),
+                                 enclosing_template_class: Some("MyList".to_string()),
+                               }),
+    }
+  };
+  let begin = data
+    .methods
+    .iter()
+    .find(|m| m.name == "begin")
+    .unwrap();
+  assert_eq!(begin.return_type,
+             iterator_type(CppType {
+                             indirection: CppTypeIndirection::None,
+                             is_const: false,
+                             is_const2: false,
+                             base: CppTypeBase::TemplateParameter {
+                               nested_level: 0,
+                               index: 0,
+                             },
+                           }));
+  let f1 = data
+    .methods
+    .iter()
+    .find(|m| m.name == "f1")
+    .unwrap();
+  assert_eq!(f1.return_type,
+             iterator_type(CppType {
+                             indirection: CppTypeIndirection::None,
+                             is_const: false,
+                             is_const2: false,
+                             base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+                           }));
+  assert_eq!(f1.return_type.to_cpp_code(None).unwrap(),
+             "typename MyList< int >::iterator");
+}
+
+#[test]
+fn converting_constructors() {
+  let data = run_parser("
+  class Source {};
+  class Target {
+  public:
+    Target(int value);
+    explicit Target(double value);
+    Target(const Source& source, int flags = 0);
+    Target(const Target& other);
+    Target(int a, int b);
+    operator bool() const;
+    explicit operator int() const;
+  };
+  ");
+  let constructors: Vec<_> = data
+    .methods
+    .iter()
+    .filter(|m| m.name == "Target")
+    .collect();
+  assert_eq!(constructors.len(), 5);
+  assert!(!constructors[0]
+               .class_membership
+               .as_ref()
+               .unwrap()
+               .is_explicit);
+  assert!(constructors[0].is_converting_constructor());
+  assert!(constructors[1]
+            .class_membership
+            .as_ref()
+            .unwrap()
+            .is_explicit);
+  assert!(!constructors[1].is_converting_constructor());
+  assert!(constructors[2].is_converting_constructor());
+  assert!(!constructors[3].is_converting_constructor());
+  assert!(!constructors[4].is_converting_constructor());
+  let operator_bool = data
+    .methods
+    .iter()
+    .find(|m| m.name == "operator bool")
+    .unwrap();
+  assert!(operator_bool.is_implicit_conversion_operator());
+  let operator_int = data
+    .methods
+    .iter()
+    .find(|m| m.name == "operator int")
+    .unwrap();
+  assert!(operator_int
+            .class_membership
+            .as_ref()
+            .unwrap()
+            .is_explicit);
+  assert!(!operator_int.is_implicit_conversion_operator());
+}
+
+#[test]
+fn safe_bool_operator() {
+  let data = run_parser("
+  class Connection {
+    typedef void *Connection::*RestrictedBool;
+  public:
+    operator RestrictedBool() const;
+  };
+  ");
+  assert_eq!(data.methods.len(), 1);
+  let bool_type = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool),
+  };
+  assert_eq!(data.methods[0].name, "operator bool");
+  assert_eq!(data.methods[0].operator,
+             Some(CppOperator::Conversion(bool_type.clone())));
+  assert_eq!(data.methods[0].return_type, bool_type);
+  assert!(data.methods[0].is_implicit_conversion_operator());
+}
+
+#[test]
+fn container_iterators() {
+  let data = run_parser("
+  class ListIterator {
+  public:
+    const int& operator*() const;
+    ListIterator& operator++();
+    bool operator!=(const ListIterator& other) const;
+  };
+  class List {
+  public:
+    ListIterator constBegin() const;
+    ListIterator constEnd() const;
+  };
+  class BadList {
+  public:
+    ListIterator begin() const;
+  };
+  ");
+  let data = cpp_post_process(data, Vec::new(), &HashMap::new()).unwrap();
+  assert_eq!(data.current.processed.container_iterators,
+             vec![CppContainerIterator {
+                    container_class: "List".to_string(),
+                    iterator_class: "ListIterator".to_string(),
+                    begin_method: "constBegin".to_string(),
+                    end_method: "constEnd".to_string(),
+                  }]);
+  assert!(data.container_iterator("List").is_some());
+  assert!(data.container_iterator("BadList").is_none());
+}
+
+#[test]
+fn trivially_copyable_types() {
+  let data = run_parser("
+  class Point {
+  public:
+    int x;
+    int y;
+  };
+  class Line {
+  public:
+    Point p1;
+    Point p2;
+  };
+  class String {
+  public:
+    String(const String& other);
+    char* data;
+  };
+  class Label {
+  public:
+    String text;
+  };
+  class Shape {
+  public:
+    virtual ~Shape();
+  };
+  class Handle {
+  public:
+    ~Handle();
+  };
+  ");
+  let data = cpp_post_process(data, Vec::new(), &HashMap::new()).unwrap();
+  assert!(data.is_trivially_copyable("Point"));
+  assert!(data.is_trivially_copyable("Line"));
+  assert!(!data.is_trivially_copyable("String"));
+  assert!(!data.is_trivially_copyable("Label"));
+  assert!(!data.is_trivially_copyable("Shape"));
+  assert!(!data.is_trivially_copyable("Handle"));
+}
+
+#[test]
+fn exception_specifications() {
+  let data = run_parser("
+  void f1() noexcept;
+  void f2() throw();
+  void f3();
+  void f4() throw(int);
+  void f5() noexcept(false);
+  void f6(void (*callback)() throw(int));
+  template<typename A, typename B> class Pair {};
+  void f7() throw(Pair<int, int>, int);
+  void f8() noexcept(true);
+  ");
+  let find = |name: &str| -> CppMethod {
+    data
+      .methods
+      .iter()
+      .find(|m| m.name == name)
+      .unwrap()
+      .clone()
+  };
+  assert_eq!(find("f1").exception_specification,
+             CppExceptionSpecification::NoThrow);
+  assert!(!find("f1").may_throw());
+  assert_eq!(find("f2").exception_specification,
+             CppExceptionSpecification::NoThrow);
+  assert!(!find("f2").may_throw());
+  assert_eq!(find("f3").exception_specification,
+             CppExceptionSpecification::None);
+  assert!(find("f3").may_throw());
+  assert_eq!(find("f4").exception_specification,
+             CppExceptionSpecification::Dynamic(vec!["int".to_string()]));
+  assert!(find("f4").may_throw());
+  assert_eq!(find("f5").exception_specification,
+             CppExceptionSpecification::None);
+  assert!(find("f5").may_throw());
+  // exception specification of an argument's type doesn't belong to the function
+  assert_eq!(find("f6").exception_specification,
+             CppExceptionSpecification::None);
+  assert_eq!(find("f7").exception_specification,
+             CppExceptionSpecification::Dynamic(vec!["Pair<int, int>".to_string(),
+                                                     "int".to_string()]));
+  assert_eq!(find("f8").exception_specification,
+             CppExceptionSpecification::NoThrow);
+}
+
+#[test]
+fn deleted_and_defaulted_methods() {
+  let data = run_parser("
+  class NonCopyable {
+  public:
+    NonCopyable();
+    NonCopyable(const NonCopyable& other) = delete;
+    NonCopyable& operator=(const NonCopyable& other) = delete;
+    int value() const;
+  };
+  class PrivateCopy {
+  public:
+    PrivateCopy();
+  private:
+    PrivateCopy(const PrivateCopy& other);
+  };
+  class Copyable {
+  public:
+    Copyable() = default;
+    Copyable(const Copyable& other) = default;
+    ~Copyable() = default;
+    int x;
+  };
+  class Derived : public NonCopyable {
+  public:
+    Derived();
+  };
+  NonCopyable* f1();
+  PrivateCopy* f2();
+  Copyable f3();
+  Derived* f4();
+  ");
+  let membership = |class_name: &str, method_name: &str, arguments_count: usize| {
+    data
+      .methods
+      .iter()
+      .find(|m| {
+              m.class_name() == Some(&class_name.to_string()) && m.name == method_name &&
+              m.arguments.len() == arguments_count
+            })
+      .unwrap()
+      .class_membership
+      .clone()
+      .unwrap()
+  };
+  assert!(!membership("NonCopyable", "NonCopyable", 0).is_deleted);
+  assert!(membership("NonCopyable", "NonCopyable", 1).is_deleted);
+  assert!(!membership("NonCopyable", "NonCopyable", 1).is_defaulted);
+  assert!(membership("NonCopyable", "operator=", 1).is_deleted);
+  assert!(!membership("NonCopyable", "value", 0).is_deleted);
+  assert!(membership("Copyable", "Copyable", 0).is_defaulted);
+  assert!(membership("Copyable", "Copyable", 1).is_defaulted);
+  assert!(membership("Copyable", "~Copyable", 0).is_defaulted);
+  assert!(!membership("Copyable", "Copyable", 1).is_deleted);
+
+  let data = cpp_post_process(data, Vec::new(), &HashMap::new()).unwrap();
+  let places = &data.current.processed.type_allocation_places;
+  assert_eq!(places["NonCopyable"], CppTypeAllocationPlace::Heap);
+  assert_eq!(places["PrivateCopy"], CppTypeAllocationPlace::Heap);
+  assert_eq!(places["Derived"], CppTypeAllocationPlace::Heap);
+  assert_eq!(places["Copyable"], CppTypeAllocationPlace::Stack);
+  assert!(data
+            .current
+            .processed
+            .trivially_copyable_types
+            .contains(&"Copyable".to_string()));
+}
+
+#[test]
+fn final_methods() {
+  let data = run_parser("
+  class Base {
+  public:
+    virtual ~Base();
+    virtual int f1();
+    virtual int f2();
+  };
+  class Derived : public Base {
+  public:
+    int f1() override;
+    int f2() final;
+  };");
+  let is_final = |class_name: &str, method_name: &str| {
+    data
+      .methods
+      .iter()
+      .find(|m| m.class_name() == Some(&class_name.to_string()) && m.name == method_name)
+      .unwrap()
+      .class_membership
+      .as_ref()
+      .unwrap()
+      .is_final
+  };
+  assert!(!is_final("Base", "f1"));
+  assert!(!is_final("Base", "f2"));
+  assert!(!is_final("Derived", "f1"));
+  assert!(is_final("Derived", "f2"));
+}
+
+#[test]
+fn move_only_types() {
+  let data = run_parser("
+  class MoveOnly {
+  public:
+    MoveOnly();
+    MoveOnly(MoveOnly&& other);
+    int x;
+  };
+  class DefaultedMove {
+  public:
+    DefaultedMove(DefaultedMove&& other) = default;
+    DefaultedMove& operator=(DefaultedMove&& other) = default;
+    int x;
+  };
+  class MoveAndCopy {
+  public:
+    MoveAndCopy(const MoveAndCopy& other) = default;
+    MoveAndCopy(MoveAndCopy&& other) = default;
+    int x;
+  };
+  MoveOnly f1();
+  DefaultedMove f2();
+  MoveAndCopy f3();
+  ");
+  let data = cpp_post_process(data, Vec::new(), &HashMap::new()).unwrap();
+  let places = &data.current.processed.type_allocation_places;
+  assert_eq!(places["MoveOnly"], CppTypeAllocationPlace::Heap);
+  assert_eq!(places["DefaultedMove"], CppTypeAllocationPlace::Heap);
+  assert_eq!(places["MoveAndCopy"], CppTypeAllocationPlace::Stack);
+  assert!(!data.is_trivially_copyable("MoveOnly"));
+  assert!(!data.is_trivially_copyable("DefaultedMove"));
+  assert!(data.is_trivially_copyable("MoveAndCopy"));
+}
+
+#[test]
+fn deprecated_entities() {
+  let data = run_parser("
+  #define MY_DEPRECATED_X(text) __attribute__((deprecated(text)))
+  [[deprecated(\"use g1 instead\")]] void f1();
+  __attribute__((deprecated)) void f2();
+  MY_DEPRECATED_X(\"use \" \"g3\") void f3();
+  void f4();
+  class [[deprecated]] Class1 {
+  public:
+    [[deprecated(\"not this one\")]] void method1();
+  };
+  class Class2 {};
+  ");
+  let method_deprecation = |name: &str| {
+    data
+      .methods
+      .iter()
+      .find(|m| m.name == name)
+      .unwrap()
+      .deprecation
+      .clone()
+  };
+  assert_eq!(method_deprecation("f1"),
+             Some(CppDeprecation { message: Some("use g1 instead".to_string()) }));
+  assert_eq!(method_deprecation("f2"),
+             Some(CppDeprecation { message: None }));
+  assert_eq!(method_deprecation("f3"),
+             Some(CppDeprecation { message: Some("use g3".to_string()) }));
+  assert_eq!(method_deprecation("f4"), None);
+  assert_eq!(method_deprecation("method1"),
+             Some(CppDeprecation { message: Some("not this one".to_string()) }));
+  let type_deprecation = |name: &str| {
+    data
+      .types
+      .iter()
+      .find(|t| t.name == name)
+      .unwrap()
+      .deprecation
+      .clone()
+  };
+  assert_eq!(type_deprecation("Class1"),
+             Some(CppDeprecation { message: None }));
+  assert_eq!(type_deprecation("Class2"), None);
+}
+
+#[test]
+fn doc_comments() {
+  let data = run_parser("
+  /// \\brief Computes a sum.
+  ///
+  /// Uses \\c int arithmetic.
+  /// \\param a First value
+  /// \\param b Second value
+  /// \\return Sum of \\p a and \\p b
+  int sum(int a, int b);
+
+  /// Returns \\c a times \\c b.
+  int product(int a, int b);
+
+  /** A class for a < b comparisons. */
+  class Class1 {
+  public:
+    /// Returns the value.
+    int value() const;
+  };
+
+  enum Enum1 {
+    /// First value
+    Value1
+  };
+
+  int undocumented();
+  ");
+  let find_method = |name: &str| data.methods.iter().find(|m| m.name == name).unwrap();
+  let sum_doc = find_method("sum").doc.clone().unwrap();
+  assert_eq!(sum_doc.html,
+             "<p>Computes a sum.</p>\n\
+              <p>Uses <code>int</code> arithmetic.</p>\n\
+              <p>Parameters:</p><ul><li><code>a</code>: First value</li>\
+              <li><code>b</code>: Second value</li></ul>\n\
+              <p>Returns: Sum of <code>a</code> and <code>b</code></p>");
+  assert!(sum_doc.url.is_empty());
+  assert!(sum_doc.mismatched_declaration.is_none());
+  assert_eq!(find_method("product").doc.clone().unwrap().html,
+             "<p>Returns <code>a</code> times <code>b</code>.</p>");
+  assert_eq!(find_method("value").doc.clone().unwrap().html,
+             "<p>Returns the value.</p>");
+  assert!(find_method("undocumented").doc.is_none());
+
+  let find_type = |name: &str| data.types.iter().find(|t| t.name == name).unwrap();
+  assert_eq!(find_type("Class1").doc.clone().unwrap().html,
+             "<p>A class for a &lt; b comparisons.</p>");
+  if let CppTypeKind::Enum { ref values } = find_type("Enum1").kind {
+    assert_eq!(values[0].doc, Some("<p>First value</p>".to_string()));
+  } else {
+    panic!("invalid type kind");
+  }
+}
+
+#[test]
+fn multi_target_merge() {
+  let code = "
+  #ifdef TEST_WINDOWS
+  class WinClass {
+  public:
+    int handle();
+  };
+  void win_func();
+  #endif
+
+  class Class1 {
+  public:
+  #ifdef TEST_WINDOWS
+    void f(long x);
+  #else
+    void f(int x);
+  #endif
+    void g();
+  };
+
+  class Handle {
+  public:
+  #ifdef TEST_WINDOWS
+    void* value;
+  #else
+    int value;
+  #endif
+    void close();
+  };
+
+  #ifdef TEST_WINDOWS
+  [[deprecated]] void h() noexcept;
+  #else
+  void h();
+  #endif
+  ";
+  let windows_target = Target {
+    arch: Arch::X86_64,
+    os: OS::Windows,
+    family: Family::Windows,
+    env: Env::Msvc,
+    pointer_width: PointerWidth::P64,
+    endian: Endian::Little,
+  };
+  let linux_target = Target {
+    arch: Arch::X86_64,
+    os: OS::Linux,
+    family: Family::Unix,
+    env: Env::Gnu,
+    pointer_width: PointerWidth::P64,
+    endian: Endian::Little,
+  };
+  let data = cpp_multi_target::merge(vec![(windows_target,
+                                           run_parser_with_arguments(code,
+                                                                     Vec::new(),
+                                                                     vec!["-DTEST_WINDOWS"
+                                                                            .to_string()])),
+                                          (linux_target, run_parser(code))]);
+  let windows = Some(Condition::Family(Family::Windows));
+  let unix = Some(Condition::Family(Family::Unix));
+  let find_type = |name: &str| data.types.iter().find(|t| t.name == name).unwrap();
+  assert_eq!(find_type("WinClass").condition, windows);
+  assert_eq!(find_type("Class1").condition, None);
+  let find_method = |name: &str| data.methods.iter().find(|m| m.name == name).unwrap();
+  assert_eq!(find_method("handle").condition, windows);
+  assert_eq!(find_method("win_func").condition, windows);
+  assert_eq!(find_method("g").condition, None);
+  let f_conditions: Vec<_> = data
+    .methods
+    .iter()
+    .filter(|m| m.name == "f")
+    .map(|m| (m.arguments[0].argument_type.to_cpp_pseudo_code(), m.condition.clone()))
+    .collect();
+  assert_eq!(f_conditions,
+             vec![("long".to_string(), windows.clone()), ("int".to_string(), unix.clone())]);
+  // declaration of the type differs between targets
+  assert!(data.types.iter().all(|t| t.name != "Handle"));
+  assert!(data.methods.iter().all(|m| m.name != "close"));
+  // deprecation and exception specification don't make a method platform-specific
+  let h: Vec<_> = data.methods.iter().filter(|m| m.name == "h").collect();
+  assert_eq!(h.len(), 1);
+  assert_eq!(h[0].condition, None);
+}
+
+#[test]
+fn multi_target_presence_condition() {
+  let target = |os: OS, family: Family, pointer_width: PointerWidth| {
+    Target {
+      arch: Arch::X86_64,
+      os: os,
+      family: family,
+      env: Env::Gnu,
+      pointer_width: pointer_width,
+      endian: Endian::Little,
+    }
+  };
+  let targets = vec![target(OS::Linux, Family::Unix, PointerWidth::P64),
+                     target(OS::MacOS, Family::Unix, PointerWidth::P64),
+                     target(OS::Windows, Family::Windows, PointerWidth::P64),
+                     target(OS::Linux, Family::Unix, PointerWidth::P32)];
+  assert_eq!(cpp_multi_target::presence_condition(&targets, &[true, true, false, true]),
+             Condition::Family(Family::Unix));
+  assert_eq!(cpp_multi_target::presence_condition(&targets, &[false, true, false, false]),
+             Condition::OS(OS::MacOS));
+  assert_eq!(cpp_multi_target::presence_condition(&targets, &[true, false, false, true]),
+             Condition::OS(OS::Linux));
+  assert_eq!(cpp_multi_target::presence_condition(&targets, &[false, false, false, true]),
+             Condition::PointerWidth(PointerWidth::P32));
+  assert_eq!(cpp_multi_target::presence_condition(&targets, &[true, true, true, false]),
+             Condition::PointerWidth(PointerWidth::P64));
+}
+
+#[test]
+fn std_function() {
+  let data = run_parser("namespace std {
+  template<typename F> class function;
+  template<typename R, typename... Args>
+  class function<R(Args...)> {
+  public:
+    R operator()(Args... args) const;
+  };
+}
+void set_handler(const std::function<bool(int)>& handler);
+std::function<void()> handler();
+void set_handlers(std::function<void(int)> a, std::function<void(int&)> b);");
+  let int_type = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+  };
+  let bool_type = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool),
+  };
+  let set_handler = data
+    .methods
+    .iter()
+    .find(|m| m.name == "set_handler")
+    .unwrap();
+  assert_eq!(set_handler.arguments.len(), 1);
+  assert_eq!(set_handler.arguments[0].argument_type,
+             CppType {
+               indirection: CppTypeIndirection::Ref,
+               is_const: true,
+               is_const2: false,
+               base: CppTypeBase::StdFunction(CppFunctionPointerType {
+                                                return_type: Box::new(bool_type),
+                                                arguments: vec![int_type.clone()],
+                                                allows_variadic_arguments: false,
+                                              }),
+             });
+  let handler = data
+    .methods
+    .iter()
+    .find(|m| m.name == "handler")
+    .unwrap();
+  assert_eq!(handler.return_type,
+             CppType {
+               indirection: CppTypeIndirection::None,
+               is_const: false,
+               is_const2: false,
+               base: CppTypeBase::StdFunction(CppFunctionPointerType {
+                                                return_type: Box::new(CppType::void()),
+                                                arguments: Vec::new(),
+                                                allows_variadic_arguments: false,
+                                              }),
+             });
+  let set_handlers = data
+    .methods
+    .iter()
+    .find(|m| m.name == "set_handlers")
+    .unwrap();
+  assert_eq!(set_handlers.arguments.len(), 2);
+  assert_eq!(set_handlers.arguments[1].argument_type.base,
+             CppTypeBase::StdFunction(CppFunctionPointerType {
+                                        return_type: Box::new(CppType::void()),
+                                        arguments: vec![CppType {
+                                                          indirection: CppTypeIndirection::Ref,
+                                                          ..int_type
+                                                        }],
+                                        allows_variadic_arguments: false,
+                                      }));
+}
+
+#[test]
+fn qt_properties() {
+  let data = run_parser("
+  #define Q_PROPERTY(text)
+  namespace Qt { enum Flag { A, B }; }
+  template<typename T> class QList {};
+  class Widget {
+    Q_PROPERTY(bool visible READ isVisible WRITE setVisible NOTIFY visibleChanged)
+    Q_PROPERTY(Qt::Flag flag READ flag RESET unsetFlag)
+    Q_PROPERTY(QList<unsigned int> ids READ ids CONSTANT)
+  public:
+    class Nested {
+      Q_PROPERTY(int value READ value)
+    public:
+      int value() const;
+    };
+    bool isVisible() const;
+    void setVisible(bool visible);
+    void visibleChanged();
+    Qt::Flag flag() const;
+    void unsetFlag();
+    QList<unsigned int> ids() const;
+  };
+  ");
+  let widget = data
+    .types
+    .iter()
+    .find(|t| t.name == "Widget")
+    .unwrap();
+  assert_eq!(widget.qt_properties,
+             vec![CppQtProperty {
+                    name: "visible".to_string(),
+                    type_name: "bool".to_string(),
+                    read: Some("isVisible".to_string()),
+                    write: Some("setVisible".to_string()),
+                    reset: None,
+                    notify: Some("visibleChanged".to_string()),
+                  },
+                  CppQtProperty {
+                    name: "flag".to_string(),
+                    type_name: "Qt::Flag".to_string(),
+                    read: Some("flag".to_string()),
+                    write: None,
+                    reset: Some("unsetFlag".to_string()),
+                    notify: None,
+                  },
+                  CppQtProperty {
+                    name: "ids".to_string(),
+                    type_name: "QList<unsigned int>".to_string(),
+                    read: Some("ids".to_string()),
+                    write: None,
+                    reset: None,
+                    notify: None,
+                  }]);
+  let nested = data
+    .types
+    .iter()
+    .find(|t| t.name == "Widget::Nested")
+    .unwrap();
+  assert_eq!(nested.qt_properties.len(), 1);
+  assert_eq!(nested.qt_properties[0].name, "value");
+}
+
+#[test]
+fn qt_properties_required() {
+  let data = run_parser("
+  #define Q_PROPERTY(text)
+  class Item {
+    Q_PROPERTY(int width READ width REQUIRED WRITE setWidth NOTIFY widthChanged)
+    Q_PROPERTY(int height REQUIRED READ height)
+  public:
+    int width() const;
+    void setWidth(int width);
+    void widthChanged();
+    int height() const;
+  };
+  ");
+  let item = data
+    .types
+    .iter()
+    .find(|t| t.name == "Item")
+    .unwrap();
+  assert_eq!(item.qt_properties,
+             vec![CppQtProperty {
+                    name: "width".to_string(),
+                    type_name: "int".to_string(),
+                    read: Some("width".to_string()),
+                    write: Some("setWidth".to_string()),
+                    reset: None,
+                    notify: Some("widthChanged".to_string()),
+                  },
+                  CppQtProperty {
+                    name: "height".to_string(),
+                    type_name: "int".to_string(),
+                    read: Some("height".to_string()),
+                    write: None,
+                    reset: None,
+                    notify: None,
+                  }]);
+}
+
+#[test]
+fn qt_properties_revision() {
+  let data = run_parser("
+  #define Q_PROPERTY(text)
+  class Item {
+    Q_PROPERTY(int width READ width REVISION(1) WRITE setWidth)
+    Q_PROPERTY(int height READ height REVISION 2 NOTIFY heightChanged)
+  public:
+    int width() const;
+    void setWidth(int width);
+    int height() const;
+    void heightChanged();
+  };
+  ");
+  let item = data
+    .types
+    .iter()
+    .find(|t| t.name == "Item")
+    .unwrap();
+  assert_eq!(item.qt_properties,
+             vec![CppQtProperty {
+                    name: "width".to_string(),
+                    type_name: "int".to_string(),
+                    read: Some("width".to_string()),
+                    write: Some("setWidth".to_string()),
+                    reset: None,
+                    notify: None,
+                  },
+                  CppQtProperty {
+                    name: "height".to_string(),
+                    type_name: "int".to_string(),
+                    read: Some("height".to_string()),
+                    write: None,
+                    reset: None,
+                    notify: Some("heightChanged".to_string()),
+                  }]);
+}
@@ -226,6 +226,7 @@ fn class_value() {
     base: CppTypeBase::Class(CppTypeClassBase {
                                name: "QPoint".to_string(),
                                template_arguments: None,
+                               enclosing_template_class: None,
                              }),
   };
   assert_eq!(type1.is_void(), false);
@@ -251,6 +252,7 @@ fn class_value() {
                 base: CppTypeBase::Class(CppTypeClassBase {
                                            name: "QPoint".to_string(),
                                            template_arguments: None,
+                                           enclosing_template_class: None,
                                          }),
               });
   assert_eq!(&ffi_return_type.ffi_type.to_cpp_code(None).unwrap(),
@@ -270,6 +272,7 @@ fn class_value() {
                 base: CppTypeBase::Class(CppTypeClassBase {
                                            name: "QPoint".to_string(),
                                            template_arguments: None,
+                                           enclosing_template_class: None,
                                          }),
               });
   assert_eq!(&ffi_arg.ffi_type.to_cpp_code(None).unwrap(),
@@ -287,6 +290,7 @@ fn class_const_ref() {
     base: CppTypeBase::Class(CppTypeClassBase {
                                name: "QRectF".to_string(),
                                template_arguments: None,
+                               enclosing_template_class: None,
                              }),
   };
   assert_eq!(type1.is_void(), false);
@@ -314,6 +318,7 @@ fn class_const_ref() {
                   base: CppTypeBase::Class(CppTypeClassBase {
                                              name: "QRectF".to_string(),
                                              template_arguments: None,
+                                             enclosing_template_class: None,
                                            }),
                 });
     assert_eq!(&ffi1.ffi_type.to_cpp_code(None).unwrap(), "const QRectF*");
@@ -331,6 +336,7 @@ fn class_mut_ref() {
     base: CppTypeBase::Class(CppTypeClassBase {
                                name: "QRectF".to_string(),
                                template_arguments: None,
+                               enclosing_template_class: None,
                              }),
   };
   assert_eq!(type1.is_void(), false);
@@ -358,6 +364,7 @@ fn class_mut_ref() {
                   base: CppTypeBase::Class(CppTypeClassBase {
                                              name: "QRectF".to_string(),
                                              template_arguments: None,
+                                             enclosing_template_class: None,
                                            }),
                 });
     assert_eq!(&ffi1.ffi_type.to_cpp_code(None).unwrap(), "QRectF*");
@@ -375,6 +382,7 @@ fn class_mut_ptr() {
     base: CppTypeBase::Class(CppTypeClassBase {
                                name: "QObject".to_string(),
                                template_arguments: None,
+                               enclosing_template_class: None,
                              }),
   };
   assert_eq!(type1.is_void(), false);
@@ -404,6 +412,7 @@ fn class_with_template_args() {
                          base: CppTypeBase::Class(CppTypeClassBase {
                                                     name: "QString".to_string(),
                                                     template_arguments: None,
+                                                    enclosing_template_class: None,
                                                   }),
                        }]);
   let type1 = CppType {
@@ -413,6 +422,7 @@ fn class_with_template_args() {
     base: CppTypeBase::Class(CppTypeClassBase {
                                name: "QVector".to_string(),
                                template_arguments: args.clone(),
+                               enclosing_template_class: None,
                              }),
   };
   assert_eq!(type1.is_void(), false);
@@ -440,6 +450,7 @@ fn class_with_template_args() {
                 base: CppTypeBase::Class(CppTypeClassBase {
                                            name: "QVector".to_string(),
                                            template_arguments: args.clone(),
+                                           enclosing_template_class: None,
                                          }),
               });
   assert_eq!(&ffi_return_type.ffi_type.to_cpp_code(None).unwrap(),
@@ -459,6 +470,7 @@ fn class_with_template_args() {
                 base: CppTypeBase::Class(CppTypeClassBase {
                                            name: "QVector".to_string(),
                                            template_arguments: args.clone(),
+                                           enclosing_template_class: None,
                                          }),
               });
   assert_eq!(&ffi_arg.ffi_type.to_cpp_code(None).unwrap(),
@@ -467,6 +479,30 @@ fn class_with_template_args() {
   assert!(type1.needs_allocation_place_variants());
 }
 
+#[test]
+fn class_nested_in_template_class() {
+  let int = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+  };
+  let type1 = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::Class(CppTypeClassBase {
+                               name: "ns::MyList::iterator".to_string(),
+                               template_arguments: Some(vec![int]),
+                               enclosing_template_class: Some("ns::MyList".to_string()),
+                             }),
+  };
+  assert_eq!(type1.to_cpp_code(None).unwrap(),
+             "typename ns::MyList< int >::iterator");
+  assert_eq!(type1.caption(TypeCaptionStrategy::Full).unwrap(),
+             "ns_MyList_iterator_int");
+}
+
 #[test]
 fn nested_template_cpp_code() {
   let type1 = CppType {
@@ -482,6 +518,7 @@ fn nested_template_cpp_code() {
                                       base: CppTypeBase::Class(CppTypeClassBase {
                                         name: "QString".to_string(),
                                         template_arguments: None,
+                                        enclosing_template_class: None,
                                       }),
                                     },
                                     CppType {
@@ -497,10 +534,13 @@ fn nested_template_cpp_code() {
                                                       base: CppTypeBase::Class(CppTypeClassBase {
                                                         name: "QString".to_string(),
                                                         template_arguments: None,
+                                                        enclosing_template_class: None,
                                                       }),
                                                     }]),
+                                        enclosing_template_class: None,
                                       }),
                                     }]),
+                               enclosing_template_class: None,
                              }),
   };
   let code = type1.to_cpp_code(None).unwrap();
@@ -518,6 +558,7 @@ fn qflags() {
                          base: CppTypeBase::Class(CppTypeClassBase {
                                                     name: "Qt::AlignmentFlag".to_string(),
                                                     template_arguments: None,
+                                                    enclosing_template_class: None,
                                                   }),
                        }]);
   let type1 = CppType {
@@ -527,6 +568,7 @@ fn qflags() {
     base: CppTypeBase::Class(CppTypeClassBase {
                                name: "QFlags".to_string(),
                                template_arguments: args.clone(),
+                               enclosing_template_class: None,
                              }),
   };
   assert_eq!(type1.is_void(), false);
@@ -647,6 +689,59 @@ fn function1() {
   assert!(!type1.needs_allocation_place_variants());
 }
 
+#[test]
+fn std_function1() {
+  let int_type = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+  };
+  let type1 = CppType {
+    is_const: true,
+    is_const2: false,
+    indirection: CppTypeIndirection::Ref,
+    base: CppTypeBase::StdFunction(CppFunctionPointerType {
+                                     allows_variadic_arguments: false,
+                                     return_type: Box::new(CppType::void()),
+                                     arguments: vec![int_type.clone(), int_type.clone()],
+                                   }),
+  };
+  assert_eq!(type1.is_void(), false);
+  assert_eq!(type1.base.is_class(), false);
+  assert_eq!(type1.base.is_function_pointer(), false);
+  assert_eq!(type1.to_cpp_code(None).unwrap(),
+             "const std::function<void(int, int)>&");
+  assert!(type1.to_cpp_code(Some("my_name")).is_err());
+  assert_eq!(type1.base.caption(TypeCaptionStrategy::Short).unwrap(),
+             "function");
+  assert_eq!(type1.base.caption(TypeCaptionStrategy::Full).unwrap(),
+             "void_function_int_int");
+  for role in &[CppTypeRole::NotReturnType, CppTypeRole::ReturnType] {
+    let ffi1 = type1.to_cpp_ffi_type(role.clone()).unwrap();
+    assert_eq!(&ffi1.original_type, &type1);
+    assert_eq!(ffi1.ffi_type.base, type1.base);
+    assert_eq!(ffi1.ffi_type.indirection, CppTypeIndirection::None);
+    assert_eq!(ffi1.ffi_type.is_const, false);
+    assert_eq!(ffi1.conversion, CppIndirectionChange::StdFunctionToClosure);
+  }
+
+  let mut type2 = type1.clone();
+  type2.is_const = false;
+  assert!(type2.to_cpp_ffi_type(CppTypeRole::NotReturnType).is_err());
+
+  let mut type3 = type1.clone();
+  type3.base = CppTypeBase::StdFunction(CppFunctionPointerType {
+                                          allows_variadic_arguments: false,
+                                          return_type: Box::new(CppType::void()),
+                                          arguments: vec![CppType {
+                                                            indirection: CppTypeIndirection::Ref,
+                                                            ..int_type.clone()
+                                                          }],
+                                        });
+  assert!(type3.to_cpp_ffi_type(CppTypeRole::NotReturnType).is_err());
+}
+
 #[test]
 fn instantiate1() {
   let type1 = CppType {
@@ -671,3 +766,21 @@ fn instantiate1() {
   assert_eq!(r.is_const, false);
   assert_eq!(r.is_const2, true);
 }
+
+#[test]
+fn class_base_equality_ignores_enclosing_template_class() {
+  use std::collections::HashSet;
+  let type1 = CppTypeClassBase {
+    name: "QList::iterator".to_string(),
+    template_arguments: None,
+    enclosing_template_class: Some("QList".to_string()),
+  };
+  let type2 = CppTypeClassBase {
+    enclosing_template_class: None,
+    ..type1.clone()
+  };
+  assert_eq!(type1, type2);
+  let mut set = HashSet::new();
+  set.insert(type1);
+  assert!(set.contains(&type2));
+}
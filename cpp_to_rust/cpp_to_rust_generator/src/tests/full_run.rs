@@ -2,7 +2,7 @@ use common::file_utils::{PathBufWithAdded, create_dir};
 use common::utils::{run_command, add_env_path_item};
 use common::cpp_lib_builder::{CppLibBuilder, BuildType};
 use common::errors::fancy_unwrap;
-use config::{Config, CrateProperties, CacheUsage};
+use config::{Config, CrateProperties, CacheUsage, RustEnumRepresentation};
 use common::cpp_build_config::CppBuildConfigData;
 use common::target;
 use std::process::Command;
@@ -51,6 +51,8 @@ fn full_run() {
                                temp_dir.path().with_added("cache"),
                                crate_properties);
   config.add_include_directive("ctrt1/all.h");
+  config.add_cpp_subclass("Counter");
+  config.set_cpp_enum_representation(RustEnumRepresentation::Newtype, "Status");
   let include_path = {
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("test_assets");
@@ -10,6 +10,14 @@
 // placement new statements require this
 #include <new>
 
+// for catching C++ exceptions in wrapper functions
+#include <exception>
+#include <string.h>
+
+// for passing closures to `std::function` arguments and return values
+#include <functional>
+#include <memory>
+
 // original C++ library includes generated by cpp_to_rust
 {include_directives_code}
 
@@ -23,5 +31,83 @@ void {lib_name_lowercase}_call_destructor(T* x) {{
     x->~T();
 }}
 
+// Receives information about a C++ exception caught by a wrapper function.
+// The layout must match `cpp_utils::CppExceptionSlot`.
+struct {lib_name_lowercase}_exception_slot {{
+    int is_set;
+    char message[512];
+}};
+
+// Writes `message` of a caught C++ exception to `slot`
+// passed to a wrapper function.
+inline void {lib_name_lowercase}_set_exception(void* slot, const char* message) {{
+    {lib_name_lowercase}_exception_slot* exception_slot =
+        static_cast<{lib_name_lowercase}_exception_slot*>(slot);
+    exception_slot->is_set = 1;
+    strncpy(exception_slot->message, message, sizeof(exception_slot->message) - 1);
+    exception_slot->message[sizeof(exception_slot->message) - 1] = 0;
+}}
+
+// Returns a value-initialized `T`. Wrapper functions return this value
+// when a C++ exception was caught.
+template<typename T>
+T {lib_name_lowercase}_default_value() {{
+    return T();
+}}
+
+// Callback function with a context pointer passed in place of a `std::function`.
+// `callback` is called with `data` as the first argument.
+// `deleter` is called with `data` when the closure is no longer needed.
+// The layout must match `cpp_utils::CppClosure`.
+struct {lib_name_lowercase}_closure {{
+    void (*callback)();
+    void* data;
+    void (*deleter)(void*);
+}};
+
+template<typename F>
+struct {lib_name_lowercase}_std_function;
+
+// Converts `std::function` objects to and from closures.
+template<typename R, typename... Args>
+struct {lib_name_lowercase}_std_function<std::function<R(Args...)>> {{
+    typedef std::function<R(Args...)> function_type;
+    typedef R (*callback_type)(void*, Args...);
+
+    // Creates a `std::function` calling `closure`'s callback.
+    // Copies of the function share ownership of `closure`'s data,
+    // so the callback may be called re-entrantly or from multiple threads.
+    static function_type from_closure({lib_name_lowercase}_closure closure) {{
+        std::shared_ptr<void> data(closure.data, closure.deleter);
+        callback_type callback = reinterpret_cast<callback_type>(closure.callback);
+        return [data, callback](Args... args) -> R {{
+            return callback(data.get(), args...);
+        }};
+    }}
+
+    // Creates a closure owning a copy of `function`.
+    static {lib_name_lowercase}_closure to_closure(const function_type& function) {{
+        {lib_name_lowercase}_closure closure;
+        closure.callback = reinterpret_cast<void (*)()>(&call);
+        closure.data = new function_type(function);
+        closure.deleter = &destroy;
+        return closure;
+    }}
+
+    // Calls a function created by `to_closure`. C++ exceptions
+    // can't be propagated to the Rust caller, so they terminate the program.
+    static R call(void* data, Args... args) {{
+        try {{
+            return (*static_cast<function_type*>(data))(args...);
+        }} catch (...) {{
+            std::terminate();
+        }}
+    }}
+
+    static void destroy(void* data) {{
+        delete static_cast<function_type*>(data);
+    }}
+}};
+
 
 #endif // {lib_name_uppercase}_GLOBAL_H
@@ -0,0 +1,371 @@
+// Classes generated by cpp_to_rust.
+// See the template at "cpp_to_rust/cpp_to_rust_generator/templates/c_lib/qt_dynamic_meta_object.h".
+
+#include <vector>
+
+// Builds a `QMetaObject` of a `QObject` subclass at runtime. Signals, slots and
+// properties must be added before `finalize()` is called. The meta-object returned by
+// `meta_object()` is valid until the builder is deleted.
+class {builder_class_name} {{
+public:
+  explicit {builder_class_name}(const char* class_name)
+    : m_class_name(class_name), m_is_finalized(false) {{
+    m_meta_object.d.superdata = &QObject::staticMetaObject;
+    m_meta_object.d.stringdata = 0;
+    m_meta_object.d.data = 0;
+    m_meta_object.d.static_metacall = 0;
+    m_meta_object.d.relatedMetaObjects = 0;
+    m_meta_object.d.extradata = 0;
+  }}
+
+  // Adds a signal with `signature` (e.g. "valueChanged(int)") and returns
+  // its index among signals of the class. Returns -1 if the signature is invalid
+  // or the meta-object is already finalized.
+  int add_signal(const char* signature) {{
+    return add_method(&m_signals, signature);
+  }}
+
+  // Adds a slot with `signature` and returns its index among slots of the class.
+  // Returns -1 if the signature is invalid or the meta-object is already finalized.
+  int add_slot(const char* signature) {{
+    return add_method(&m_slots, signature);
+  }}
+
+  // Adds a property and returns its index among properties of the class.
+  // `notify_signal` is the index of the signal emitted when the property changes
+  // or -1 if there is no such signal. Returns -1 if the arguments are invalid
+  // or the meta-object is already finalized.
+  int add_property(const char* name, const char* type_name, bool is_writable, int notify_signal) {{
+    if (m_is_finalized || notify_signal < -1 || notify_signal >= m_signals.size()) {{
+      return -1;
+    }}
+    Property property;
+    property.name = name;
+    property.type_name = QMetaObject::normalizedType(type_name);
+    property.is_writable = is_writable;
+    property.notify_signal = notify_signal;
+    if (property.name.isEmpty() || property.type_name.isEmpty()) {{
+      return -1;
+    }}
+    m_properties.append(property);
+    return m_properties.size() - 1;
+  }}
+
+  int signal_count() const {{ return m_signals.size(); }}
+  int slot_count() const {{ return m_slots.size(); }}
+  int property_count() const {{ return m_properties.size(); }}
+
+  // Returns the number of arguments of slot with index `slot`.
+  int slot_arguments_count(int slot) const {{
+    return m_slots[slot].argument_types.size();
+  }}
+
+  const QByteArray& class_name() const {{ return m_class_name; }}
+
+  // Builds the meta-object. After this, no signals, slots and properties
+  // can be added.
+  void finalize() {{
+    if (m_is_finalized) {{
+      return;
+    }}
+    m_strings.clear();
+    m_data.clear();
+    // class name must have the first index
+    add_string(m_class_name);
+    const uint empty_string = add_string(QByteArray());
+    const int method_count = m_signals.size() + m_slots.size();
+    bool has_notify_signals = false;
+    for (const Property& property : m_properties) {{
+      if (property.notify_signal >= 0) {{
+        has_notify_signals = true;
+      }}
+    }}
+    const uint header_size = 14;
+    const uint methods_index = header_size;
+    const uint parameters_index = methods_index + 5 * method_count;
+    uint parameters_size = 0;
+    for (const Method& method : m_signals + m_slots) {{
+      parameters_size += 1 + 2 * method.argument_types.size();
+    }}
+    const uint properties_index = parameters_index + parameters_size;
+
+    // header
+    m_data.push_back(7); // revision
+    m_data.push_back(0); // class name
+    m_data.push_back(0); // class info count
+    m_data.push_back(0); // class info index
+    m_data.push_back(method_count);
+    m_data.push_back(method_count > 0 ? methods_index : 0);
+    m_data.push_back(m_properties.size());
+    m_data.push_back(m_properties.isEmpty() ? 0 : properties_index);
+    m_data.push_back(0); // enums count
+    m_data.push_back(0); // enums index
+    m_data.push_back(0); // constructors count
+    m_data.push_back(0); // constructors index
+    m_data.push_back(0); // flags
+    m_data.push_back(m_signals.size());
+
+    // methods: name, argc, parameters, tag, flags
+    const uint access_public = 0x02;
+    const uint method_signal = 0x04;
+    const uint method_slot = 0x08;
+    uint parameters_offset = parameters_index;
+    for (int i = 0; i < method_count; i++) {{
+      const bool is_signal = i < m_signals.size();
+      const Method& method = is_signal ? m_signals[i] : m_slots[i - m_signals.size()];
+      m_data.push_back(add_string(method.name));
+      m_data.push_back(method.argument_types.size());
+      m_data.push_back(parameters_offset);
+      m_data.push_back(empty_string);
+      m_data.push_back(access_public | (is_signal ? method_signal : method_slot));
+      parameters_offset += 1 + 2 * method.argument_types.size();
+    }}
+
+    // parameters: return type, argument types, argument names
+    for (const Method& method : m_signals + m_slots) {{
+      m_data.push_back(QMetaType::Void);
+      for (const QByteArray& type_name : method.argument_types) {{
+        m_data.push_back(type_info(type_name));
+      }}
+      for (int i = 0; i < method.argument_types.size(); i++) {{
+        m_data.push_back(empty_string);
+      }}
+    }}
+
+    // properties: name, type, flags
+    const uint readable = 0x00000001;
+    const uint writable = 0x00000002;
+    const uint designable = 0x00001000;
+    const uint scriptable = 0x00004000;
+    const uint stored = 0x00010000;
+    const uint resolve_editable = 0x00080000;
+    const uint notify = 0x00400000;
+    for (const Property& property : m_properties) {{
+      m_data.push_back(add_string(property.name));
+      m_data.push_back(type_info(property.type_name));
+      m_data.push_back(readable | designable | scriptable | stored | resolve_editable |
+                       (property.is_writable ? writable : 0) |
+                       (property.notify_signal >= 0 ? notify : 0));
+    }}
+    if (has_notify_signals) {{
+      for (const Property& property : m_properties) {{
+        m_data.push_back(property.notify_signal >= 0 ? property.notify_signal : 0);
+      }}
+    }}
+    m_data.push_back(0); // end of data
+
+    build_string_data();
+    m_meta_object.d.stringdata = reinterpret_cast<const QByteArrayData*>(&m_string_data[0]);
+    m_meta_object.d.data = &m_data[0];
+    m_is_finalized = true;
+  }}
+
+  bool is_finalized() const {{ return m_is_finalized; }}
+
+  // Returns the built meta-object or null if `finalize()` was not called.
+  const QMetaObject* meta_object() const {{
+    return m_is_finalized ? &m_meta_object : 0;
+  }}
+
+private:
+  struct Method {{
+    QByteArray name;
+    QList<QByteArray> argument_types;
+  }};
+
+  struct Property {{
+    QByteArray name;
+    QByteArray type_name;
+    bool is_writable;
+    int notify_signal;
+  }};
+
+  int add_method(QList<Method>* methods, const char* signature) {{
+    if (m_is_finalized) {{
+      return -1;
+    }}
+    QByteArray normalized = QMetaObject::normalizedSignature(signature);
+    int open_index = normalized.indexOf('(');
+    if (open_index <= 0 || !normalized.endsWith(')')) {{
+      return -1;
+    }}
+    Method method;
+    method.name = normalized.left(open_index);
+    QByteArray arguments = normalized.mid(open_index + 1, normalized.size() - open_index - 2);
+    // split arguments by commas that are not inside template arguments
+    int depth = 0;
+    int start = 0;
+    for (int i = 0; i <= arguments.size(); i++) {{
+      if (i == arguments.size() || (arguments[i] == ',' && depth == 0)) {{
+        if (i > start) {{
+          method.argument_types.append(arguments.mid(start, i - start));
+        }} else if (i < arguments.size() || start > 0) {{
+          return -1;
+        }}
+        start = i + 1;
+      }} else if (arguments[i] == '<') {{
+        depth++;
+      }} else if (arguments[i] == '>') {{
+        depth--;
+      }}
+    }}
+    methods->append(method);
+    return methods->size() - 1;
+  }}
+
+  uint add_string(const QByteArray& string) {{
+    int index = m_strings.indexOf(string);
+    if (index < 0) {{
+      m_strings.append(string);
+      index = m_strings.size() - 1;
+    }}
+    return index;
+  }}
+
+  // Returns the type value stored in the meta-object data.
+  // Built-in types are stored as type ids, other types are stored as names.
+  uint type_info(const QByteArray& type_name) {{
+    const uint is_unresolved_type = 0x80000000;
+    int id = QMetaType::type(type_name.constData());
+    if (id != QMetaType::UnknownType && id < QMetaType::User) {{
+      return id;
+    }}
+    return is_unresolved_type | add_string(type_name);
+  }}
+
+  // Writes `QByteArrayData` headers followed by the strings' contents
+  // in the same way moc does it.
+  void build_string_data() {{
+    const int headers_size = m_strings.size() * sizeof(QByteArrayData);
+    int size = headers_size;
+    for (const QByteArray& string : m_strings) {{
+      size += string.size() + 1;
+    }}
+    m_string_data.assign(size, 0);
+    int string_offset = headers_size;
+    for (int i = 0; i < m_strings.size(); i++) {{
+      const QByteArray& string = m_strings[i];
+      const int header_offset = i * sizeof(QByteArrayData);
+      const int string_size = string.size();
+      const qptrdiff offset = string_offset - header_offset;
+      QByteArrayData header =
+        Q_STATIC_BYTE_ARRAY_DATA_HEADER_INITIALIZER_WITH_OFFSET(string_size, offset);
+      memcpy(&m_string_data[header_offset], &header, sizeof(QByteArrayData));
+      memcpy(&m_string_data[string_offset], string.constData(), string_size);
+      string_offset += string_size + 1;
+    }}
+  }}
+
+  QByteArray m_class_name;
+  bool m_is_finalized;
+  QList<Method> m_signals;
+  QList<Method> m_slots;
+  QList<Property> m_properties;
+  QList<QByteArray> m_strings;
+  std::vector<char> m_string_data;
+  std::vector<uint> m_data;
+  QMetaObject m_meta_object;
+}};
+
+// `QObject` subclass that uses a meta-object created by `{builder_class_name}`.
+// Invocations of its slots and accesses to its properties are forwarded to the handler.
+class {object_class_name} : public QObject {{
+public:
+  explicit {object_class_name}(const {builder_class_name}& meta_object)
+    : m_meta_object(&meta_object), m_callback(0), m_data(0), m_deleter(0) {{ }}
+
+  ~{object_class_name}() {{
+    set_handler(0, 0, 0);
+  }}
+
+  // Sets the handler. The callback receives `data`, the object, the kind of the call
+  // (0 for slot invocation, 1 for reading a property, 2 for writing a property),
+  // the index of the slot or the property, the number of items in the argument array
+  // and the argument array. `deleter` is called with `data` when the handler
+  // is replaced or the object is deleted.
+  void set_handler({callback_arg}, void* data, void (*deleter)(void*)) {{
+    if (m_deleter) {{
+      m_deleter(m_data);
+    }}
+    m_callback = callback;
+    m_data = data;
+    m_deleter = deleter;
+  }}
+
+  // Emits the signal with index `signal` among signals of the class.
+  // The first item of `args` is reserved for the return value, the rest
+  // of items point to the signal's arguments.
+  void emit_signal(int signal, void** args) {{
+    QMetaObject::activate(this, m_meta_object->meta_object(), signal, args);
+  }}
+
+  const QMetaObject* metaObject() const override {{
+    return m_meta_object->meta_object();
+  }}
+
+  void* qt_metacast(const char* class_name) override {{
+    if (class_name && m_meta_object->class_name() == class_name) {{
+      return this;
+    }}
+    return QObject::qt_metacast(class_name);
+  }}
+
+  int qt_metacall(QMetaObject::Call call, int id, void** args) override {{
+    id = QObject::qt_metacall(call, id, args);
+    if (id < 0) {{
+      return id;
+    }}
+    const int signal_count = m_meta_object->signal_count();
+    const int method_count = signal_count + m_meta_object->slot_count();
+    const int property_count = m_meta_object->property_count();
+    switch (call) {{
+    case QMetaObject::InvokeMetaMethod:
+      if (id < signal_count) {{
+        QMetaObject::activate(this, metaObject(), id, args);
+      }} else if (id < method_count && m_callback) {{
+        const int slot = id - signal_count;
+        m_callback(m_data, this, 0, slot,
+                   1 + m_meta_object->slot_arguments_count(slot), args);
+      }}
+      id -= method_count;
+      break;
+    case QMetaObject::RegisterMethodArgumentMetaType:
+      if (id < method_count) {{
+        *reinterpret_cast<int*>(args[0]) = -1;
+      }}
+      id -= method_count;
+      break;
+    case QMetaObject::ReadProperty:
+    case QMetaObject::WriteProperty:
+      if (id < property_count && m_callback) {{
+        m_callback(m_data, this, call == QMetaObject::ReadProperty ? 1 : 2, id, 1, args);
+      }}
+      id -= property_count;
+      break;
+    case QMetaObject::RegisterPropertyMetaType:
+      if (id < property_count) {{
+        *reinterpret_cast<int*>(args[0]) = -1;
+      }}
+      id -= property_count;
+      break;
+    case QMetaObject::ResetProperty:
+    case QMetaObject::QueryPropertyDesignable:
+    case QMetaObject::QueryPropertyScriptable:
+    case QMetaObject::QueryPropertyStored:
+    case QMetaObject::QueryPropertyEditable:
+    case QMetaObject::QueryPropertyUser:
+      id -= property_count;
+      break;
+    default:
+      break;
+    }}
+    return id;
+  }}
+
+private:
+  const {builder_class_name}* m_meta_object;
+  {callback_field};
+  void* m_data;
+  void (*m_deleter)(void*);
+}};
+
@@ -3,11 +3,20 @@
 class {class_name} : public QObject {{
   Q_OBJECT
 public:
-  {class_name}() : m_func(0), m_data(0) {{ }}
+  {class_name}() : m_func(0), m_data(0), m_deleter(0) {{ }}
+  ~{class_name}() {{
+    if (m_deleter && m_data) {{
+      m_deleter(m_data);
+    }}
+  }}
   void set({func_arg}, void* data) {{
     m_func = func;
     m_data = data;
   }}
+  // Makes the object responsible for deleting `data` passed to `set`.
+  void set_deleter(void (*deleter)(void*)) {{
+    m_deleter = deleter;
+  }}
 
 public slots:
   void custom_slot({method_args}) {{
@@ -19,4 +28,5 @@ public slots:
 private:
   {func_field};
   void* m_data;
+  void (*m_deleter)(void*);
 }};
@@ -0,0 +1,23 @@
+// Class generated by cpp_to_rust.
+// See the template at "cpp_to_rust/cpp_to_rust_generator/templates/c_lib/subclass.h".
+class {class_name} : public {base_class_name} {{
+public:
+  using {base_class_name}::{base_constructor_name};
+  ~{class_name}() {{
+    set_virtuals_data(0);
+  }}
+  void set_virtuals_data(void* data) {{
+    if (m_deleter && m_data) {{
+      m_deleter(m_data);
+    }}
+    m_data = data;
+  }}
+  void set_virtuals_deleter(void (*deleter)(void*)) {{
+    m_deleter = deleter;
+  }}
+{methods}
+private:
+  void* m_data = 0;
+  void (*m_deleter)(void*) = 0;
+{fields}}};
+
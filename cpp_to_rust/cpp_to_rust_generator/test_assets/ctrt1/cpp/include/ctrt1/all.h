@@ -1,2 +1,5 @@
 #include "ctrt1/utils.h"
 #include "ctrt1/class1.h"
+#include "ctrt1/virtual_class.h"
+#include "ctrt1/enums.h"
+#include "ctrt1/point.h"
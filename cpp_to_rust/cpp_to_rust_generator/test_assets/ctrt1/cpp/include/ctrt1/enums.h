@@ -0,0 +1,18 @@
+#ifndef CTRT1_ENUMS_H
+#define CTRT1_ENUMS_H
+
+#include "ctrt1/exports.h"
+
+enum Direction {
+  DirectionUp = 1,
+  DirectionDown = 5
+};
+
+enum Status : unsigned int {
+  StatusSuccess = 0,
+  StatusFailure = 0xFFFFFFFFu
+};
+
+CTRT1_EXPORT Status ctrt1_check(int x);
+
+#endif // CTRT1_ENUMS_H
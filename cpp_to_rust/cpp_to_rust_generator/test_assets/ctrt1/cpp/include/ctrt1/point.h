@@ -0,0 +1,22 @@
+#ifndef CTRT1_POINT_H
+#define CTRT1_POINT_H
+
+#include "ctrt1/exports.h"
+
+class CTRT1_EXPORT Point {
+public:
+  Point(int x, int y) : m_x(x), m_y(y) {}
+  Point(const Point& other) = default;
+  int x() const { return m_x; }
+  int y() const { return m_y; }
+
+private:
+  int m_x;
+  int m_y;
+};
+
+inline Point ctrt1_point(int x, int y) {
+  return Point(x, y);
+}
+
+#endif // CTRT1_POINT_H
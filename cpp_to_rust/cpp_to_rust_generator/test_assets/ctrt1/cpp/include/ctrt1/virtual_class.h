@@ -0,0 +1,14 @@
+#include "ctrt1/exports.h"
+
+class CTRT1_EXPORT Counter {
+public:
+  Counter() {}
+  virtual ~Counter();
+
+  virtual int step() const { return 1; }
+  virtual int next(int value) = 0;
+  virtual int twoSteps() const { return step() * 2; }
+  virtual int firstStep() const final { return step(); }
+
+  int advance(int value) { return next(value) + step(); }
+};
@@ -0,0 +1,9 @@
+#include "ctrt1/enums.h"
+
+Status ctrt1_check(int x) {
+  if (x >= 0) {
+    return StatusSuccess;
+  } else {
+    return StatusFailure;
+  }
+}
@@ -0,0 +1,3 @@
+#include "ctrt1/virtual_class.h"
+
+Counter::~Counter() {}
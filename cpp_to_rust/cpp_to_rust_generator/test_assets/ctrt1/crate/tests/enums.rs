@@ -0,0 +1,41 @@
+extern crate rust_ctrt1;
+extern crate cpp_utils;
+use rust_ctrt1::enums::{Direction, Status, ctrt1_check};
+use cpp_utils::UnknownEnumValue;
+use std::convert::TryFrom;
+
+#[test]
+fn enum_conversions() {
+  assert_eq!(Direction::Down.to_int(), 5);
+  assert_eq!(Direction::try_from(1), Ok(Direction::Up));
+  assert_eq!(Direction::try_from(5), Ok(Direction::Down));
+  assert_eq!(Direction::try_from(3), Err(UnknownEnumValue(3)));
+}
+
+#[test]
+fn newtype_enum_unknown_value() {
+  let value = Status::from_int(42);
+  assert_eq!(value.to_int(), 42);
+  assert_eq!(value.name(), None);
+  assert_eq!(Status::from(42), value);
+}
+
+#[test]
+fn newtype_enum_out_of_range_value() {
+  // 0xFFFFFFFF doesn't fit in c_int and wraps around
+  assert_eq!(Status::Failure.to_int(), -1);
+  assert_eq!(Status::from_int(-1), Status::Failure);
+  assert_eq!(Status::Failure.name(), Some("Failure"));
+  assert_eq!(ctrt1_check(1), Status::Success);
+  assert_eq!(ctrt1_check(-1), Status::Failure);
+}
+
+#[test]
+fn enum_representations_api() {
+  assert_eq!(Direction::Up.name(), Some("Up"));
+  assert_eq!(Status::Success.name(), Some("Success"));
+  assert_eq!(format!("{:?}", Direction::Up), "Up");
+  assert_eq!(format!("{:?}", Status::Success), "Success");
+  assert_eq!(format!("{:?}", Status::from_int(42)), "Status(42)");
+  assert_eq!(format!("{}", Status::from_int(42)), "42");
+}
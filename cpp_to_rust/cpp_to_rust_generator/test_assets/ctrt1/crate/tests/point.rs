@@ -0,0 +1,13 @@
+extern crate rust_ctrt1;
+use rust_ctrt1::point::{Point, ctrt1_point};
+
+#[test]
+fn point_copy() {
+  let a: Point = ctrt1_point(1, 2);
+  let b = a;
+  let c = a.clone();
+  assert_eq!(a.x(), 1);
+  assert_eq!(b.y(), 2);
+  assert_eq!(c.x(), 1);
+  assert_eq!(c.y(), 2);
+}
@@ -0,0 +1,50 @@
+extern crate rust_ctrt1;
+use rust_ctrt1::subclasses::{CounterSubclass, CounterVirtuals};
+use std::cell::Cell;
+
+struct Doubler;
+
+impl CounterVirtuals for Doubler {
+  fn next(&self, _this: &mut CounterSubclass, value: i32) -> i32 {
+    value * 2
+  }
+}
+
+struct BigSteps {
+  calls: Cell<i32>,
+}
+
+impl CounterVirtuals for BigSteps {
+  fn step(&self, _this: &CounterSubclass) -> i32 {
+    self.calls.set(self.calls.get() + 1);
+    10 * self.calls.get()
+  }
+  fn next(&self, _this: &mut CounterSubclass, value: i32) -> i32 {
+    value
+  }
+}
+
+#[test]
+fn subclass_default_virtual_method() {
+  let mut object = CounterSubclass::new();
+  object.set_virtuals(Doubler);
+  assert_eq!(object.advance(3), 7);
+}
+
+#[test]
+fn subclass_overridden_virtual_method() {
+  let mut object = CounterSubclass::new();
+  object.set_virtuals(BigSteps { calls: Cell::new(0) });
+  assert_eq!(object.advance(3), 13);
+  assert_eq!(object.advance(3), 23);
+}
+
+#[test]
+fn subclass_base_implementation_calling_virtual_method() {
+  let mut object = CounterSubclass::new();
+  object.set_virtuals(BigSteps { calls: Cell::new(0) });
+  // the base implementation of `twoSteps` calls the overridden `step`
+  assert_eq!(object.two_steps(), 20);
+  // `firstStep` is final, so it's not a part of `CounterVirtuals`
+  assert_eq!(object.first_step(), 20);
+}
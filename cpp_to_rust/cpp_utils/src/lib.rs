@@ -4,7 +4,7 @@
 mod tests {
   use std::rc::Rc;
   use std::cell::RefCell;
-  use {CppDeletable, Deleter, CppBox};
+  use {CppDeletable, Deleter, CppBox, CppExceptionSlot, CppClosure, UnknownEnumValue};
 
   struct Struct1 {
     value: Rc<RefCell<i32>>,
@@ -30,6 +30,47 @@ mod tests {
     }
     assert!(value1.borrow().clone() == 42);
   }
+
+  #[test]
+  fn test_closure() {
+    extern "C" fn add(data: *mut ::std::os::raw::c_void, x: i32) -> i32 {
+      let value = unsafe { &*(data as *mut Rc<RefCell<i32>>) };
+      let r = *value.borrow() + x;
+      r
+    }
+    let value = Rc::new(RefCell::new(40));
+    let closure = unsafe {
+      CppClosure::new(Box::new(value.clone()),
+                      ::std::mem::transmute(add as extern "C" fn(*mut ::std::os::raw::c_void,
+                                                                 i32)
+                                                                 -> i32))
+    };
+    let callback: extern "C" fn(*mut ::std::os::raw::c_void, i32) -> i32 =
+      unsafe { ::std::mem::transmute(closure.callback()) };
+    assert_eq!(callback(closure.data(), 2), 42);
+    assert_eq!(Rc::strong_count(&value), 2);
+    drop(closure);
+    assert_eq!(Rc::strong_count(&value), 1);
+  }
+
+  #[test]
+  fn test_exception_slot() {
+    let mut slot = CppExceptionSlot::new();
+    assert!(slot.take().is_none());
+    slot.is_set = 1;
+    for (i, c) in b"bad_alloc".iter().enumerate() {
+      slot.message[i] = *c as ::std::os::raw::c_char;
+    }
+    let exception = slot.take().unwrap();
+    assert_eq!(exception.message(), "bad_alloc");
+    assert!(slot.take().is_none());
+  }
+
+  #[test]
+  fn test_unknown_enum_value() {
+    let error = UnknownEnumValue(42);
+    assert_eq!(error.to_string(), "unknown enum value: 42");
+  }
 }
 
 /// Deleter function type.
@@ -179,6 +220,148 @@ pub mod new_uninitialized {
   }
 }
 
+/// A C++ exception caught by a wrapper function.
+///
+/// Rust functions corresponding to C++ functions that may throw
+/// return `Result<T, CppException>` if catching C++ exceptions is enabled
+/// in `cpp_to_rust` configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CppException {
+  message: String,
+}
+
+impl CppException {
+  /// Creates an exception object with `message`.
+  pub fn new<S: Into<String>>(message: S) -> CppException {
+    CppException { message: message.into() }
+  }
+
+  /// Returns message of the exception. For exceptions derived from
+  /// `std::exception`, it's the value returned by `what()`. For other
+  /// exception types, it's the name of the exception type.
+  pub fn message(&self) -> &str {
+    &self.message
+  }
+}
+
+impl std::fmt::Display for CppException {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "C++ exception: {}", self.message)
+  }
+}
+
+impl std::error::Error for CppException {
+  fn description(&self) -> &str {
+    &self.message
+  }
+}
+
+/// Error returned by `TryFrom` implementations of generated enum types
+/// when the integer value doesn't correspond to any of the enum's variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownEnumValue(pub std::os::raw::c_int);
+
+impl std::fmt::Display for UnknownEnumValue {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "unknown enum value: {}", self.0)
+  }
+}
+
+impl std::error::Error for UnknownEnumValue {
+  fn description(&self) -> &str {
+    "unknown enum value"
+  }
+}
+
+/// Buffer receiving information about a C++ exception caught by a wrapper function.
+/// The layout of this type must match `exception_slot` type declared
+/// in the wrapper library.
+///
+/// This type is an implementation detail of `cpp_to_rust` and should not be used directly.
+#[repr(C)]
+pub struct CppExceptionSlot {
+  is_set: std::os::raw::c_int,
+  message: [std::os::raw::c_char; 512],
+}
+
+impl CppExceptionSlot {
+  /// Creates an empty slot.
+  pub fn new() -> CppExceptionSlot {
+    CppExceptionSlot {
+      is_set: 0,
+      message: [0; 512],
+    }
+  }
+
+  /// Returns the exception written to the slot by the wrapper function, if any,
+  /// and clears the slot.
+  pub fn take(&mut self) -> Option<CppException> {
+    if self.is_set == 0 {
+      return None;
+    }
+    let message = unsafe { std::ffi::CStr::from_ptr(self.message.as_ptr()) }
+      .to_string_lossy()
+      .into_owned();
+    self.is_set = 0;
+    self.message[0] = 0;
+    Some(CppException::new(message))
+  }
+}
+
+/// A callback with a context pointer used to pass closures
+/// to and from C++ `std::function` objects.
+/// The layout of this type must match `closure` type declared
+/// in the wrapper library.
+///
+/// The object owns its data: `deleter` is called with `data` when the object is dropped.
+/// Passing the object to the wrapper library transfers the ownership to the C++ side.
+///
+/// This type is an implementation detail of `cpp_to_rust` and should not be used directly.
+#[repr(C)]
+pub struct CppClosure {
+  callback: Option<extern "C" fn()>,
+  data: *mut std::os::raw::c_void,
+  deleter: Option<extern "C" fn(*mut std::os::raw::c_void)>,
+}
+
+/// Drops the `Box<T>` owned by a `CppClosure`.
+extern "C" fn delete_boxed<T>(data: *mut std::os::raw::c_void) {
+  unsafe {
+    drop(Box::from_raw(data as *mut T));
+  }
+}
+
+impl CppClosure {
+  /// Creates a closure owning `data`. `callback` must be a function
+  /// accepting a pointer to `T` as its first argument, transmuted to `extern "C" fn()`.
+  pub unsafe fn new<T>(data: Box<T>, callback: extern "C" fn()) -> CppClosure {
+    CppClosure {
+      callback: Some(callback),
+      data: Box::into_raw(data) as *mut std::os::raw::c_void,
+      deleter: Some(delete_boxed::<T>),
+    }
+  }
+
+  /// Returns the callback function. It should be transmuted
+  /// to the function type expected by the closure's creator.
+  pub fn callback(&self) -> extern "C" fn() {
+    self.callback.expect("CppClosure has no callback")
+  }
+
+  /// Returns the data pointer that should be passed to the callback.
+  pub fn data(&self) -> *mut std::os::raw::c_void {
+    self.data
+  }
+}
+
+impl Drop for CppClosure {
+  fn drop(&mut self) {
+    if let Some(deleter) = self.deleter {
+      deleter(self.data);
+    }
+  }
+}
+
 /// Provides access to C++ `static_cast` conversion from derived class to base class.
 ///
 /// This trait is automatically implemented by `cpp_to_rust`.
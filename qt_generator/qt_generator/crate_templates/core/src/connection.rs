@@ -40,16 +40,111 @@ pub trait Receiver {
 /// A Qt signal.
 pub trait Signal: Receiver {
   /// Connects this signal to another signal or slot with compatible arguments.
+  ///
+  /// Uses `Qt::AutoConnection` connection type.
   fn connect<A, R: Receiver<Arguments = A>>(&self, receiver: &R) -> ::meta_object::Connection
     where Self::Arguments: ArgumentsCompatible<A>
   {
-    // TODO: allow to change connection type
-    // TODO: meta_object::Connection should have operator bool()
+    self.connect_with_type(receiver, ::qt::ConnectionType::Auto)
+  }
+
+  /// Connects this signal to another signal or slot with compatible arguments
+  /// using the specified connection type.
+  ///
+  /// Use `Connection::is_valid` to check if the connection was made.
+  /// It may fail e.g. if `Qt::UniqueConnection` was requested and
+  /// the same connection already exists.
+  fn connect_with_type<A, R: Receiver<Arguments = A>>(&self,
+                                                      receiver: &R,
+                                                      connection_type: ::qt::ConnectionType)
+                                                      -> ::meta_object::Connection
+    where Self::Arguments: ArgumentsCompatible<A>
+  {
     unsafe {
       ::object::Object::connect_static((self.object() as *const ::object::Object,
                                         Self::receiver_id().as_ptr() as *const c_char,
                                         receiver.object() as *const ::object::Object,
-                                        R::receiver_id().as_ptr() as *const c_char))
+                                        R::receiver_id().as_ptr() as *const c_char,
+                                        connection_type))
+    }
+  }
+
+  /// Connects this signal to another signal or slot with compatible arguments
+  /// and returns a guard that breaks the connection when dropped.
+  fn connect_guarded<A, R: Receiver<Arguments = A>>(&self, receiver: &R) -> ConnectionGuard
+    where Self::Arguments: ArgumentsCompatible<A>
+  {
+    ConnectionGuard::new(self.connect(receiver))
+  }
+
+  /// Disconnects this signal from `receiver`.
+  /// Returns true if at least one connection was broken.
+  fn disconnect<R: Receiver>(&self, receiver: &R) -> bool {
+    unsafe {
+      ::object::Object::disconnect_static((self.object() as *const ::object::Object,
+                                           Self::receiver_id().as_ptr() as *const c_char,
+                                           receiver.object() as *const ::object::Object,
+                                           R::receiver_id().as_ptr() as *const c_char))
+    }
+  }
+
+  /// Disconnects this signal from all signals and slots.
+  /// Returns true if at least one connection was broken.
+  fn disconnect_all(&self) -> bool {
+    unsafe {
+      ::object::Object::disconnect_static((self.object() as *const ::object::Object,
+                                           Self::receiver_id().as_ptr() as *const c_char,
+                                           ::std::ptr::null(),
+                                           ::std::ptr::null()))
+    }
+  }
+}
+
+impl ::meta_object::Connection {
+  /// Returns true if the connection was successfully made
+  /// and hasn't been broken since.
+  pub fn is_valid(&self) -> bool {
+    self.as_bool()
+  }
+
+  /// Breaks the connection.
+  /// Returns false if the connection was invalid or already broken.
+  pub fn disconnect(&self) -> bool {
+    ::object::Object::disconnect_static(self)
+  }
+}
+
+/// A signal connection that is broken when the guard is dropped.
+///
+/// Dropping the guard has no effect if the connection has already been broken,
+/// e.g. because the sender or the receiver was deleted.
+pub struct ConnectionGuard {
+  connection: Option<::meta_object::Connection>,
+}
+
+impl ConnectionGuard {
+  /// Creates a guard of `connection`.
+  pub fn new(connection: ::meta_object::Connection) -> ConnectionGuard {
+    ConnectionGuard { connection: Some(connection) }
+  }
+
+  /// Returns the guarded connection.
+  pub fn connection(&self) -> &::meta_object::Connection {
+    self.connection.as_ref().unwrap()
+  }
+
+  /// Releases the connection, so it won't be broken when the guard is dropped.
+  pub fn release(mut self) -> ::meta_object::Connection {
+    self.connection.take().unwrap()
+  }
+}
+
+impl Drop for ConnectionGuard {
+  fn drop(&mut self) {
+    if let Some(ref connection) = self.connection {
+      if connection.is_valid() {
+        connection.disconnect();
+      }
     }
   }
 }
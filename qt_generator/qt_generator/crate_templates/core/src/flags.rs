@@ -72,6 +72,8 @@ pub trait FlaggableEnum: Sized + Clone {
   fn to_flag_value(self) -> c_int;
   /// Returns name of the type for debug output.
   fn enum_name() -> &'static str;
+  /// Returns names and integer values of all variants of the enum.
+  fn named_values() -> &'static [(&'static str, c_int)];
 }
 
 /// Trait representing types that can be converted to `Flags`.
@@ -90,7 +92,30 @@ impl<T: FlaggableEnum> EnumOrFlags<T> for Flags<T>
 
 impl<T: FlaggableEnum> std::fmt::Debug for Flags<T> {
   fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-    write!(f, "Flags<{}>({})", T::enum_name(), self.value)
+    // values with more bits (e.g. combinations and masks) are matched first,
+    // and matched bits are removed, so each bit is listed under one name only
+    let mut named_values: Vec<_> = T::named_values()
+      .iter()
+      .filter(|&&(_, value)| value != 0)
+      .collect();
+    named_values.sort_by(|a, b| b.1.count_ones().cmp(&a.1.count_ones()));
+    let mut matched = Vec::new();
+    let mut unknown_bits = self.value;
+    for &&(name, value) in &named_values {
+      if unknown_bits & value == value {
+        matched.push((value as u32, name));
+        unknown_bits &= !value;
+      }
+    }
+    matched.sort_by_key(|&(value, _)| value);
+    let mut names: Vec<_> = matched.into_iter().map(|(_, name)| name.to_string()).collect();
+    if unknown_bits != 0 {
+      names.push(format!("{:#x}", unknown_bits));
+    }
+    if names.is_empty() {
+      names.push("0".to_string());
+    }
+    write!(f, "Flags<{}>({})", T::enum_name(), names.join(" | "))
   }
 }
 
@@ -124,4 +124,5 @@ include_generated!();
 
 pub mod connection;
 pub mod flags;
+pub mod meta_object_builder;
 mod impl_arguments_compatible;
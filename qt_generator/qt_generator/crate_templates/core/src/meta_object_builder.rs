@@ -0,0 +1,277 @@
+//! Types for defining Qt signals, slots and properties in Rust code.
+//!
+//! `MetaObjectBuilder` creates a Qt meta-object at runtime. Signals, slots and properties
+//! added to the builder appear in `QObject::metaObject()` of objects created with
+//! `DynamicObject::with_handler`, so these objects can be used with the string-based
+//! Qt APIs (e.g. `QObject::connect` with `SIGNAL()` and `SLOT()` signatures,
+//! `QObject::property` or QML).
+//!
+//! Slot invocations and property accesses are forwarded to the object's
+//! `DynamicObjectHandler`. Arguments are passed as pointers to C++ values, so
+//! the handler must use Rust types corresponding to the types specified
+//! in the signatures.
+//!
+//! ```rust,no_run
+//! extern crate qt_core;
+//! use qt_core::dynamic_meta_object::DynamicObject;
+//! use qt_core::meta_object_builder::{MetaObjectBuilder, DynamicObjectHandler, SlotArguments,
+//!                                    PropertyValue};
+//!
+//! struct Counter {
+//!   value: i32,
+//! }
+//!
+//! impl DynamicObjectHandler for Counter {
+//!   fn invoke_slot(&mut self, object: &mut DynamicObject, _slot: i32, args: &SlotArguments) {
+//!     // the only slot is "setValue(int)"
+//!     let value = unsafe { *args.get::<i32>(0) };
+//!     if value != self.value {
+//!       self.value = value;
+//!       // the only signal is "valueChanged(int)"
+//!       unsafe { object.emit(0, &[&value as *const i32 as *const _]) };
+//!     }
+//!   }
+//!
+//!   fn read_property(&mut self,
+//!                    _object: &mut DynamicObject,
+//!                    _property: i32,
+//!                    value: &mut PropertyValue) {
+//!     unsafe { value.set(self.value) };
+//!   }
+//! }
+//!
+//! fn main() {
+//!   let mut builder = MetaObjectBuilder::new("Counter");
+//!   let value_changed = builder.add_signal("valueChanged(int)");
+//!   builder.add_slot("setValue(int)");
+//!   builder.add_property("value", "int", false, Some(value_changed));
+//!   let counter = DynamicObject::with_handler(builder.build(), Counter { value: 0 });
+//!   // `counter` can now be connected to other objects by signatures
+//! }
+//! ```
+
+use cpp_utils::CppBox;
+use dynamic_meta_object::{DynamicMetaObject, DynamicObject};
+use libc::{c_int, c_void};
+use std::cell::RefCell;
+use std::ffi::CString;
+
+/// Converts `value` to a null-terminated string.
+fn to_c_string(value: &str) -> CString {
+  CString::new(value).expect("strings passed to MetaObjectBuilder must not contain null bytes")
+}
+
+/// Creates a Qt meta-object describing signals, slots and properties
+/// of a `QObject` subclass at runtime.
+///
+/// Indexes returned by `add_signal`, `add_slot` and `add_property`
+/// are used to identify signals, slots and properties in `DynamicObject::emit`
+/// and `DynamicObjectHandler`. Each kind has its own indexes starting with 0.
+pub struct MetaObjectBuilder {
+  meta_object: CppBox<DynamicMetaObject>,
+}
+
+impl MetaObjectBuilder {
+  /// Creates a builder of a meta-object of class `class_name`.
+  /// The class inherits `QObject`.
+  pub fn new(class_name: &str) -> MetaObjectBuilder {
+    let class_name = to_c_string(class_name);
+    MetaObjectBuilder { meta_object: unsafe { DynamicMetaObject::new(class_name.as_ptr()) } }
+  }
+
+  /// Adds a signal with `signature` (e.g. `"valueChanged(int)"`) and returns its index.
+  ///
+  /// Panics if the signature is invalid.
+  pub fn add_signal(&mut self, signature: &str) -> i32 {
+    let c_signature = to_c_string(signature);
+    let index = unsafe { self.meta_object.add_signal(c_signature.as_ptr()) };
+    if index < 0 {
+      panic!("invalid signal signature: {}", signature);
+    }
+    index
+  }
+
+  /// Adds a slot with `signature` (e.g. `"setValue(int)"`) and returns its index.
+  /// Slots can't have return values.
+  ///
+  /// Panics if the signature is invalid.
+  pub fn add_slot(&mut self, signature: &str) -> i32 {
+    let c_signature = to_c_string(signature);
+    let index = unsafe { self.meta_object.add_slot(c_signature.as_ptr()) };
+    if index < 0 {
+      panic!("invalid slot signature: {}", signature);
+    }
+    index
+  }
+
+  /// Adds a property and returns its index. `type_name` is the C++ type of the property
+  /// (e.g. `"int"` or `"QString"`). If `is_writable` is false, the property is read-only.
+  /// `notify_signal` is the index of the signal emitted when the property's value changes.
+  ///
+  /// Panics if the name or the type is empty or `notify_signal` is not a valid signal index.
+  pub fn add_property(&mut self,
+                      name: &str,
+                      type_name: &str,
+                      is_writable: bool,
+                      notify_signal: Option<i32>)
+                      -> i32 {
+    let c_name = to_c_string(name);
+    let c_type_name = to_c_string(type_name);
+    let index = unsafe {
+      self
+        .meta_object
+        .add_property(c_name.as_ptr(),
+                      c_type_name.as_ptr(),
+                      is_writable,
+                      notify_signal.unwrap_or(-1))
+    };
+    if index < 0 {
+      panic!("invalid property: {}", name);
+    }
+    index
+  }
+
+  /// Builds the meta-object.
+  ///
+  /// As meta-objects of C++ classes, the meta-object is never deleted
+  /// because objects using it may exist until the end of the program.
+  pub fn build(mut self) -> &'static DynamicMetaObject {
+    self.meta_object.finalize();
+    unsafe { &*self.meta_object.into_raw() }
+  }
+}
+
+/// Pointers to arguments of a slot invocation.
+pub struct SlotArguments<'a>(&'a [*mut c_void]);
+
+impl<'a> SlotArguments<'a> {
+  /// Returns number of the arguments.
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  /// Returns true if the slot has no arguments.
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Returns reference to the argument with `index`.
+  ///
+  /// This function is unsafe because `T` must be the Rust type corresponding to
+  /// the argument's type specified in the slot's signature.
+  pub unsafe fn get<T>(&self, index: usize) -> &T {
+    &*(self.0[index] as *const T)
+  }
+}
+
+/// Pointer to a property's value passed to `DynamicObjectHandler`.
+pub struct PropertyValue(*mut c_void);
+
+impl PropertyValue {
+  /// Returns reference to the value.
+  ///
+  /// This function is unsafe because `T` must be the Rust type corresponding to
+  /// the property's type.
+  pub unsafe fn get<T>(&self) -> &T {
+    &*(self.0 as *const T)
+  }
+
+  /// Replaces the value with `value`.
+  ///
+  /// This function is unsafe because `T` must be the Rust type corresponding to
+  /// the property's type.
+  pub unsafe fn set<T>(&mut self, value: T) {
+    *(self.0 as *mut T) = value;
+  }
+}
+
+/// Implementation of slots and properties of a `DynamicObject`.
+///
+/// The handler's methods must not cause another call of the same handler,
+/// e.g. by emitting a signal with a direct connection to the object's own slot.
+/// Such calls abort the process.
+pub trait DynamicObjectHandler {
+  /// Called when the slot with index `slot` is invoked.
+  fn invoke_slot(&mut self, object: &mut DynamicObject, slot: i32, args: &SlotArguments);
+
+  /// Called when the value of the property with index `property` is requested.
+  /// The implementation should write the value to `value`. The default implementation
+  /// leaves the default value of the property's type.
+  #[allow(unused_variables)]
+  fn read_property(&mut self, object: &mut DynamicObject, property: i32, value: &mut PropertyValue) {
+  }
+
+  /// Called when the value of the writable property with index `property` is set.
+  /// The default implementation ignores the new value.
+  #[allow(unused_variables)]
+  fn write_property(&mut self, object: &mut DynamicObject, property: i32, value: &PropertyValue) {}
+}
+
+impl DynamicObject {
+  /// Creates an object using `meta_object`. Slot invocations and property accesses
+  /// are forwarded to `handler`. The handler is dropped when the object is deleted.
+  pub fn with_handler<H: DynamicObjectHandler + 'static>(meta_object: &'static DynamicMetaObject,
+                                                        handler: H)
+                                                        -> CppBox<DynamicObject> {
+    let mut object = DynamicObject::new(meta_object);
+    let data: Box<RefCell<Box<DynamicObjectHandler>>> = Box::new(RefCell::new(Box::new(handler)));
+    unsafe {
+      object.set_handler(handler_callback,
+                         Box::into_raw(data) as *mut c_void,
+                         handler_deleter);
+    }
+    object
+  }
+
+  /// Emits the signal with index `signal`. `args` must contain pointers to values
+  /// of the signal's arguments.
+  ///
+  /// This function is unsafe because types of the values must correspond to
+  /// the argument types specified in the signal's signature.
+  pub unsafe fn emit(&mut self, signal: i32, args: &[*const c_void]) {
+    // the first item is reserved for the return value
+    let mut all_args: Vec<*mut c_void> = Vec::with_capacity(args.len() + 1);
+    all_args.push(::std::ptr::null_mut());
+    all_args.extend(args.iter().map(|arg| *arg as *mut c_void));
+    self.emit_signal(signal, all_args.as_mut_ptr());
+  }
+}
+
+extern "C" fn handler_callback(data: *mut c_void,
+                               object: *mut DynamicObject,
+                               call: c_int,
+                               index: c_int,
+                               args_count: c_int,
+                               args: *mut *mut c_void) {
+  let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| unsafe {
+    let handler = &*(data as *const RefCell<Box<DynamicObjectHandler>>);
+    // a re-entrant call would alias the handler
+    let mut handler = handler
+      .try_borrow_mut()
+      .expect("re-entrant call of DynamicObjectHandler");
+    let object = &mut *object;
+    let args = ::std::slice::from_raw_parts(args, args_count as usize);
+    match call {
+      0 => handler.invoke_slot(object, index, &SlotArguments(&args[1..])),
+      1 => handler.read_property(object, index, &mut PropertyValue(args[0])),
+      2 => handler.write_property(object, index, &PropertyValue(args[0])),
+      _ => {}
+    }
+  }));
+  if result.is_err() {
+    ::std::process::abort();
+  }
+}
+
+extern "C" fn handler_deleter(data: *mut c_void) {
+  let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| unsafe {
+    let data = Box::from_raw(data as *mut RefCell<Box<DynamicObjectHandler>>);
+    // the handler can't be dropped while one of its methods is running
+    if data.try_borrow_mut().is_err() {
+      ::std::process::abort();
+    }
+  }));
+  if result.is_err() {
+    ::std::process::abort();
+  }
+}
@@ -0,0 +1,49 @@
+include_generated!();
+
+/// Converts references to `QObject`-based classes using `qobject_cast`.
+///
+/// This trait is automatically implemented by `cpp_to_rust`.
+/// If `T1` class is derived (in C++) from `T2` class and `T1` is declared
+/// with `Q_OBJECT` macro, `Cast<T1>` is implemented for `T2`.
+///
+/// Unlike `cpp_utils::DynamicCast`, the conversion uses Qt's meta-object system,
+/// so it doesn't require RTTI and works across dynamic library boundaries
+/// (e.g. for objects created by Qt plugins).
+/// Conversion returns `None` if the object is actually not an instance of
+/// the target type.
+pub trait Cast<T> {
+  /// Convert type of a const reference.
+  /// Returns `None` if `self` is not an instance of `T`.
+  fn qobject_cast(&self) -> Option<&T>;
+  /// Convert type of a mutable reference.
+  /// Returns `None` if `self` is not an instance of `T`.
+  fn qobject_cast_mut(&mut self) -> Option<&mut T>;
+}
+
+/// Converts type of a const pointer using `Cast` implementation of the type.
+/// `ptr` must be either a null pointer or a valid pointer to an instance of `T` class
+/// or a class derived from `T`.
+/// Returns null pointer if `ptr` does not point to an instance of `R` or an instance of
+/// a class derived from `R`.
+/// If `ptr` is null, this function does nothing and returns null pointer.
+pub unsafe fn qobject_cast<R, T: Cast<R>>(ptr: *const T) -> *const R {
+  ptr
+    .as_ref()
+    .and_then(|x| x.qobject_cast())
+    .map(|x| x as *const R)
+    .unwrap_or(::std::ptr::null())
+}
+
+/// Converts type of a mutable pointer using `Cast` implementation of the type.
+/// `ptr` must be either a null pointer or a valid pointer to an instance of `T` class
+/// or a class derived from `T`.
+/// Returns null pointer if `ptr` does not point to an instance of `R` or an instance of
+/// a class derived from `R`.
+/// If `ptr` is null, this function does nothing and returns null pointer.
+pub unsafe fn qobject_cast_mut<R, T: Cast<R>>(ptr: *mut T) -> *mut R {
+  ptr
+    .as_mut()
+    .and_then(|x| x.qobject_cast_mut())
+    .map(|x| x as *mut R)
+    .unwrap_or(::std::ptr::null_mut())
+}
@@ -0,0 +1,75 @@
+extern crate qt_core;
+use qt_core::connection::Signal;
+use qt_core::object::Object;
+use qt_core::qt::ConnectionType;
+use qt_core::slots::SlotStringRef;
+use qt_core::string::String;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+#[test]
+fn connection() {
+  let count = Rc::new(Cell::new(0));
+  let count2 = count.clone();
+  let slot1 = SlotStringRef::new(move |_| count2.set(count2.get() + 1));
+  let mut object = Object::new();
+
+  let connection = object.signals().object_name_changed().connect(&slot1);
+  assert!(connection.is_valid());
+  let connection2 = object
+    .signals()
+    .object_name_changed()
+    .connect_with_type(&slot1, ConnectionType::Unique);
+  assert!(!connection2.is_valid());
+  object.set_object_name(&String::from("a"));
+  assert_eq!(count.get(), 1);
+
+  assert!(object.signals().object_name_changed().disconnect(&slot1));
+  assert!(!connection.is_valid());
+  assert!(!object.signals().object_name_changed().disconnect(&slot1));
+  object.set_object_name(&String::from("b"));
+  assert_eq!(count.get(), 1);
+
+  {
+    let guard = object.signals().object_name_changed().connect_guarded(&slot1);
+    assert!(guard.connection().is_valid());
+    object.set_object_name(&String::from("c"));
+    assert_eq!(count.get(), 2);
+  }
+  object.set_object_name(&String::from("d"));
+  assert_eq!(count.get(), 2);
+
+  let connection3 = object
+    .signals()
+    .object_name_changed()
+    .connect_guarded(&slot1)
+    .release();
+  object.set_object_name(&String::from("e"));
+  assert_eq!(count.get(), 3);
+  assert!(connection3.disconnect());
+  assert!(!connection3.disconnect());
+}
+
+#[test]
+fn connect_closure() {
+  let names = Rc::new(RefCell::new(Vec::new()));
+  let names2 = names.clone();
+  let mut object = Object::new();
+  let connection = object
+    .signals()
+    .object_name_changed()
+    .connect_closure(move |name| names2.borrow_mut().push(name.to_std_string()));
+  assert!(connection.is_valid());
+  object.set_object_name(&String::from("a"));
+  object.set_object_name(&String::from("b"));
+  assert_eq!(*names.borrow(), vec!["a".to_string(), "b".to_string()]);
+
+  assert!(connection.disconnect());
+  object.set_object_name(&String::from("c"));
+  assert_eq!(names.borrow().len(), 2);
+
+  // the closure is dropped together with the sender
+  assert_eq!(Rc::strong_count(&names), 2);
+  drop(object);
+  assert_eq!(Rc::strong_count(&names), 1);
+}
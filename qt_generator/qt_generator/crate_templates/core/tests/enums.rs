@@ -0,0 +1,28 @@
+extern crate qt_core;
+use qt_core::flags::Flags;
+use qt_core::qt::{AlignmentFlag, Key};
+
+#[test]
+fn enum_names() {
+  assert_eq!(Key::A.name(), Some("A"));
+  assert_eq!(Key::from_name("A"), Some(Key::A));
+  assert_eq!(Key::from_name("Key_A"), Some(Key::A));
+  assert_eq!(Key::from_name("NoSuchKey"), None);
+  assert_eq!(format!("{}", Key::Escape), "Escape");
+  assert_eq!(format!("{:?}", Key::Escape), "Escape");
+}
+
+#[test]
+fn flags_debug() {
+  let flags = Flags::from_enum(AlignmentFlag::Left) | AlignmentFlag::Top;
+  assert_eq!(format!("{:?}", flags), "Flags<AlignmentFlag>(Left | Top)");
+  let flags = flags | Flags::from_int(0x4000_0000);
+  assert_eq!(format!("{:?}", flags),
+             "Flags<AlignmentFlag>(Left | Top | 0x40000000)");
+  let flags = Flags::from_enum(AlignmentFlag::HCenter) | AlignmentFlag::VCenter;
+  assert_eq!(format!("{:?}", flags), "Flags<AlignmentFlag>(Center)");
+  let flags = flags | AlignmentFlag::Left;
+  assert_eq!(format!("{:?}", flags), "Flags<AlignmentFlag>(Left | Center)");
+  let empty: Flags<AlignmentFlag> = Flags::default();
+  assert_eq!(format!("{:?}", empty), "Flags<AlignmentFlag>(0)");
+}
@@ -0,0 +1,75 @@
+extern crate qt_core;
+use qt_core::dynamic_meta_object::DynamicObject;
+use qt_core::libc::c_char;
+use qt_core::meta_object_builder::{MetaObjectBuilder, DynamicObjectHandler, SlotArguments,
+                                   PropertyValue};
+use qt_core::object::Object;
+use std::cell::Cell;
+use std::ffi::CStr;
+use std::rc::Rc;
+
+struct Sender;
+
+impl DynamicObjectHandler for Sender {
+  fn invoke_slot(&mut self, _object: &mut DynamicObject, _slot: i32, _args: &SlotArguments) {
+    panic!("Sender has no slots");
+  }
+}
+
+struct Receiver {
+  last_value: Rc<Cell<i32>>,
+}
+
+impl DynamicObjectHandler for Receiver {
+  fn invoke_slot(&mut self, _object: &mut DynamicObject, slot: i32, args: &SlotArguments) {
+    assert_eq!(slot, 0);
+    assert_eq!(args.len(), 1);
+    self.last_value.set(unsafe { *args.get::<i32>(0) });
+  }
+
+  fn read_property(&mut self,
+                   _object: &mut DynamicObject,
+                   property: i32,
+                   value: &mut PropertyValue) {
+    assert_eq!(property, 0);
+    unsafe { value.set(self.last_value.get()) };
+  }
+}
+
+#[test]
+fn meta_object_builder() {
+  let mut builder = MetaObjectBuilder::new("RustSender");
+  let changed = builder.add_signal("changed(int)");
+  assert_eq!(changed, 0);
+  let sender_meta_object = builder.build();
+
+  let mut builder = MetaObjectBuilder::new("RustReceiver");
+  assert_eq!(builder.add_slot("set(int)"), 0);
+  assert_eq!(builder.add_property("lastValue", "int", false, None), 0);
+  let receiver_meta_object = builder.build();
+
+  let mut sender = DynamicObject::with_handler(sender_meta_object, Sender);
+  let last_value = Rc::new(Cell::new(0));
+  let receiver = DynamicObject::with_handler(receiver_meta_object,
+                                             Receiver { last_value: last_value.clone() });
+  {
+    let sender_object: &Object = &sender;
+    let class_name = unsafe { CStr::from_ptr((*sender_object.meta_object()).class_name()) };
+    assert_eq!(class_name.to_str().unwrap(), "RustSender");
+  }
+  {
+    let sender_object: &Object = &sender;
+    let receiver_object: &Object = &receiver;
+    unsafe {
+      Object::connect_static((sender_object as *const Object,
+                              b"2changed(int)\0".as_ptr() as *const c_char,
+                              receiver_object as *const Object,
+                              b"1set(int)\0".as_ptr() as *const c_char));
+    }
+  }
+  let value = 5;
+  unsafe { sender.emit(changed, &[&value as *const i32 as *const _]) };
+  assert_eq!(last_value.get(), 5);
+  let property = unsafe { receiver.property(b"lastValue\0".as_ptr() as *const c_char) };
+  assert_eq!(property.to_int(()), 5);
+}
@@ -5,6 +5,8 @@ use qt_core::string_list::StringList;
 use qt_core::string_list_model::StringListModel;
 use qt_core::abstract_item_model::AbstractItemModel;
 use qt_core::abstract_table_model::AbstractTableModel;
+use qt_core::object::{Object, Cast};
+use qt_core::timer::Timer;
 use qt_core::qt::ItemDataRole;
 
 #[test]
@@ -43,4 +45,18 @@ fn models_and_casts() {
 
   let table_model_attempt: Option<&AbstractTableModel> = abstract_model.dynamic_cast();
   assert!(table_model_attempt.is_none());
+
+  let table_model_attempt: Option<&AbstractTableModel> = abstract_model.qobject_cast();
+  assert!(table_model_attempt.is_none());
+
+  let object: &mut Object = abstract_model.static_cast_mut();
+  {
+    let string_list_model_back: &mut StringListModel =
+      object
+        .qobject_cast_mut()
+        .expect("qobject_cast should be successful");
+    assert_eq!(string_list_model_back.row_count(()), 2);
+  }
+  let timer_attempt: Option<&Timer> = object.qobject_cast();
+  assert!(timer_attempt.is_none());
 }
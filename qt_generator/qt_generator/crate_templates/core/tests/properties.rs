@@ -0,0 +1,35 @@
+extern crate qt_core;
+use qt_core::object::Object;
+use qt_core::slots::SlotStringRef;
+use qt_core::connection::Signal;
+use qt_core::string::String;
+use std::cell::Cell;
+
+#[test]
+fn properties() {
+  let mut object = Object::new();
+  object
+    .object_properties_mut()
+    .object_name()
+    .set(&String::from("name1"));
+  assert_eq!(object.object_name().to_std_string(), "name1");
+  assert_eq!(object
+               .object_properties()
+               .object_name()
+               .get()
+               .to_std_string(),
+             "name1");
+
+  let count = Cell::new(0);
+  let slot1 = SlotStringRef::new(|_| count.set(count.get() + 1));
+  object
+    .object_properties()
+    .object_name()
+    .changed()
+    .connect(&slot1);
+  object
+    .object_properties_mut()
+    .object_name()
+    .set(&String::from("name2"));
+  assert_eq!(count.get(), 1);
+}
@@ -234,6 +234,15 @@ fn make_config(sublib_name: &str,
             }
           }
         }
+        for typedef in &mut cpp_data.typedefs {
+          match parser.doc_for_type(&typedef.name) {
+            Ok(doc) => typedef.doc = Some(doc.0),
+            Err(err) => {
+              log::llog(log::DebugQtDoc,
+                        || format!("Not found doc for typedef: {}: {}", typedef.name, err));
+            }
+          }
+        }
         parser.report_unused_anchors();
       }
       Err(err) => {
@@ -103,6 +103,16 @@ pub fn fix_header_names(data: &mut ParserCppData, headers_dir: &PathBuf) -> Resu
     let x = map.real_to_fancy(&m.include_file, m.class_name().map(|x| x.as_ref()));
     m.include_file = x;
   }
+  for t in &mut data.typedefs {
+    t.include_file = map.real_to_fancy(&t.include_file, Some(&t.name));
+  }
+  for v in &mut data.variables {
+    let x = map.real_to_fancy(&v.include_file, v.class_type.as_ref().map(|x| x.name.as_ref()));
+    v.include_file = x;
+  }
+  for m in &mut data.macros {
+    m.include_file = map.real_to_fancy(&m.include_file, None);
+  }
   Ok(())
 }
 
@@ -150,6 +150,8 @@ pub fn core(config: &mut Config) -> Result<()> {
   // but this method is obsolete anyway
   config.add_cpp_parser_blocked_names(vec!["QProcess::pid"]);
 
+  config.add_cpp_parser_allowed_macros(vec!["QT_VERSION", "QT_VERSION_STR"]);
+
   exclude_qvector_eq_based_methods(config, &["QStaticPlugin", "QTimeZone::OffsetData"]);
   exclude_qlist_eq_based_methods(config,
                                  &["QAbstractEventDispatcher::TimerInfo", "QCommandLineOption"]);